@@ -1,38 +1,212 @@
 use anyhow::{Context, Result};
 use clap::{Args, Parser, Subcommand, ValueEnum};
+use regex::Regex;
 use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
 use std::time::Duration;
+use uuid::Uuid;
 
 /// Default MiniJinja template used by `--text` when no custom template string is provided.
 pub const DEFAULT_TEXT_TEMPLATE: &str = "{{ kind }} | {{ state }} | elapsed {{ '%02d:%02d' | format(elapsed_secs // 60, elapsed_secs % 60) }} | remaining {{ '%02d:%02d' | format(remaining_secs // 60, remaining_secs % 60) }}";
 
-/// Runtime configuration loaded from `$XDG_CONFIG_HOME/pomodoro/config.toml`.
+/// Default MiniJinja template used by `--output tmux` when no custom template string is provided.
+pub const DEFAULT_TMUX_TEMPLATE: &str = "{% if kind == 'break' %}☕{% else %}🍅{% endif %} {{ '%02d:%02d' | format(remaining_secs // 60, remaining_secs % 60) }}";
+
+/// Commented configuration file written by `pomodoro edit-config` when none exists yet. Every
+/// key is commented out, so the file parses to [`ProgramConfig::default`].
+pub const DEFAULT_CONFIG_TEMPLATE: &str = r##"# pomodoro configuration — uncomment a key to change it.
+# Durations use humantime format: "25m", "1h30m", "90s", or a decimal like "1.5h" or "90min".
+
+# Length of a focus session.
+# focus_duration = "25m"
+
+# Length of a break session.
+# break_duration = "5m"
+
+# Length of the long break taken after every `long_break_interval` completed
+# focus sessions ("0" as the interval disables long breaks).
+# long_break_duration = "15m"
+# long_break_interval = 4
+
+# Let `status` record the completion of a session that ran out of time. Turn it
+# off when your own scripts complete sessions (e.g. with `stop --complete`).
+# auto_complete = true
+
+# Start a break right away when `status` completes a focus session.
+# auto_break = false
+
+# Treat a running session with at most this much time left as completed.
+# auto_complete_tolerance = "0s"
+
+# Start a break instead when starting focus this soon after a focus session completed.
+# force_break_between_focus = "0s"
+
+# Report `break_overdue` in `status` once a focus session completed this long ago
+# without a new session being started.
+# break_reminder_after = "0s"
+
+# Report `stale` in `status` once a session ran this long, e.g. because it was
+# forgotten ("0s" disables it).
+# stale_after = "8h"
+
+# What `start` begins after a session ended when no --mode is passed: "focus",
+# or "next" to take a break after a completed focus session.
+# default_start_kind = "focus"
+
+# Session kinds `start` may begin, e.g. ["focus"] to refuse breaks.
+# allowed_modes = ["focus", "break"]
+
+# MiniJinja template used by `status` when no --format is passed.
+# status_template = "{{ kind }} {{ remaining_secs // 60 }}m"
+
+# How many times to retry while another invocation holds the database lock.
+# lock_retries = 5
+
+# Run `tmux refresh-client -S` after every state change.
+# refresh_tmux = false
+
+# Write the status to a cache file after every state change, for `status --cached`.
+# status_cache = false
+
+# MiniJinja templates used by `status` per --output when no --format is passed;
+# `text` takes precedence over `status_template`.
+# [templates]
+# text = "{{ kind }} {{ remaining_secs // 60 }}m"
+# tmux = "🍅 {{ remaining_secs // 60 }}m"
+
+# Colors used by `status --output tmux`.
+# [colors]
+# focus = "red"
+# break = "blue"
+# ending = "magenta"
+# paused = "yellow"
+# completed = "green"
+# aborted = "red"
+"##;
+
+/// File names searched for in `$XDG_CONFIG_HOME/pomodoro`, in order. The
+/// extension selects the format: TOML or JSON.
+pub const CONFIG_FILE_NAMES: [&str; 2] = ["config.toml", "config.json"];
+
+/// Runtime configuration loaded from `$XDG_CONFIG_HOME/pomodoro/config.toml`
+/// (or one of the other [`CONFIG_FILE_NAMES`]).
 ///
 /// All fields are optional in the file; missing keys fall back to the
 /// [`Default`] values (25 min focus, 5 min break).
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
 pub struct ProgramConfig {
     /// Duration of a focus session (default: 25 minutes).
-    #[serde(with = "humantime_serde")]
+    #[serde(deserialize_with = "deserialize_duration")]
     pub focus_duration: Duration,
     /// Duration of a break session (default: 5 minutes).
-    #[serde(with = "humantime_serde")]
+    #[serde(deserialize_with = "deserialize_duration")]
     pub break_duration: Duration,
+    /// Duration of a long break session (default: 15 minutes).
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub long_break_duration: Duration,
+    /// A break started after this many completed focus sessions since the last
+    /// long break is a long one (default: 4; 0 disables long breaks).
+    pub long_break_interval: u32,
+    /// Whether `status` completes a running session that has no time left
+    /// (default: true).
+    pub auto_complete: bool,
+    /// Whether `status` starts a break once it completed a focus session
+    /// (default: false).
+    pub auto_break: bool,
+    /// A running session with at most this much time left is treated as
+    /// completed (default: 0s).
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub auto_complete_tolerance: Duration,
+    /// Starting a focus session within this long after a focus session
+    /// completed starts a break instead (default: 0s, disabled).
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub force_break_between_focus: Duration,
+    /// Report the break as overdue once a focus session completed this long
+    /// ago with no new session started (default: 0s, disabled).
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub break_reminder_after: Duration,
+    /// Report a session as stale once its elapsed time exceeds this, as it was
+    /// most likely forgotten (default: 8 hours; 0s disables it).
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub stale_after: Duration,
+    /// What `start` begins after a session ended when no `--mode` is given
+    /// (default: focus).
+    pub default_start_kind: DefaultStartKind,
+    /// Session kinds that `start` may begin; starting any other kind is an
+    /// error (default: focus and break).
+    pub allowed_modes: Vec<StartMode>,
+    /// MiniJinja template used by `status --output text` when no `--format` is
+    /// given (default: [`DEFAULT_TEXT_TEMPLATE`]).
+    pub status_template: Option<String>,
+    /// How many times to retry when another process holds the database lock (default: 5).
+    pub lock_retries: u32,
+    /// Refresh the tmux status line after every state change (default: false).
+    pub refresh_tmux: bool,
+    /// Write the status to a cache file after every state change, for
+    /// `status --cached` (default: false).
+    pub status_cache: bool,
+    /// Templates used by `status` per output format (the `[templates]` table).
+    pub templates: TemplatesConfig,
+    /// tmux colors used by `status --output tmux` (the `[colors]` table).
+    pub colors: ColorsConfig,
 }
 
 impl ProgramConfig {
-    /// Load configuration from `$XDG_CONFIG_HOME/pomodoro/config.toml`.
+    /// Load configuration from the file returned by [`ProgramConfig::path`].
     ///
     /// Returns an error if the file cannot be read or parsed. Callers
     /// should fall back to [`Default`] when the file does not exist.
     pub fn load() -> Result<Self> {
-        let path = xdg::BaseDirectories::with_prefix("pomodoro")
-            .place_config_file("config.toml")
-            .context("Failed to determine configuration path")?;
+        Self::load_from(&Self::path()?)
+    }
 
+    /// Load configuration from the file at `path`.
+    ///
+    /// A `.json` file is parsed as JSON; anything else is parsed as TOML.
+    pub fn load_from(path: &Path) -> Result<Self> {
         let content = std::fs::read(path).context("Failed to read configuration file")?;
-        toml::from_slice(&content[..]).context("Failed to load configuration file")
+        let config = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_slice(&content[..]).map_err(anyhow::Error::from),
+            _ => toml::from_slice(&content[..]).map_err(anyhow::Error::from),
+        };
+        config.context("Failed to load configuration file")
+    }
+
+    /// Return the configured template of `status --output <output>`, if any:
+    /// the one of the `[templates]` table, or else `status_template` for
+    /// text, which predates the table. JSON output takes no template.
+    pub fn template_for(&self, output: StatusOutput) -> Option<&str> {
+        match output {
+            StatusOutput::Text => self
+                .templates
+                .text
+                .as_deref()
+                .or(self.status_template.as_deref()),
+            StatusOutput::Tmux => self.templates.tmux.as_deref(),
+            StatusOutput::Json => None,
+        }
+    }
+
+    /// Return the path of the configuration file read by [`ProgramConfig::load`].
+    ///
+    /// This is the first of [`CONFIG_FILE_NAMES`] that exists, or
+    /// `config.toml` when none does.
+    pub fn path() -> Result<PathBuf> {
+        let default = Self::toml_path()?;
+        Ok(CONFIG_FILE_NAMES
+            .iter()
+            .map(|name| default.with_file_name(name))
+            .find(|path| path.exists())
+            .unwrap_or(default))
+    }
+
+    /// Return the path of `config.toml`, whether or not it exists.
+    pub fn toml_path() -> Result<PathBuf> {
+        xdg::BaseDirectories::with_prefix("pomodoro")
+            .place_config_file(CONFIG_FILE_NAMES[0])
+            .context("Failed to determine configuration path")
     }
 }
 
@@ -42,6 +216,69 @@ impl Default for ProgramConfig {
         Self {
             focus_duration: Duration::from_secs(25 * 60),
             break_duration: Duration::from_secs(5 * 60),
+            long_break_duration: Duration::from_secs(15 * 60),
+            long_break_interval: 4,
+            auto_complete: true,
+            auto_break: false,
+            auto_complete_tolerance: Duration::ZERO,
+            force_break_between_focus: Duration::ZERO,
+            break_reminder_after: Duration::ZERO,
+            stale_after: Duration::from_secs(8 * 3600),
+            default_start_kind: DefaultStartKind::Focus,
+            allowed_modes: vec![StartMode::Focus, StartMode::Break],
+            status_template: None,
+            lock_retries: 5,
+            refresh_tmux: false,
+            status_cache: false,
+            templates: TemplatesConfig::default(),
+            colors: ColorsConfig::default(),
+        }
+    }
+}
+
+/// Default MiniJinja templates of `status` per output format, loaded from the
+/// `[templates]` table of the configuration file. `--format` overrides them.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TemplatesConfig {
+    /// Template of `status --output text`, also used for the lines of `watch`
+    /// and `start --wait --progress` (default: [`DEFAULT_TEXT_TEMPLATE`]).
+    pub text: Option<String>,
+    /// Template of `status --output tmux`, before it is colored (default:
+    /// [`DEFAULT_TMUX_TEMPLATE`]).
+    pub tmux: Option<String>,
+}
+
+/// tmux colors for each session state, loaded from the `[colors]` table of the
+/// configuration file. Values are anything tmux accepts as a color (`red`,
+/// `colour208`, `#ff8800`, …).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ColorsConfig {
+    /// Color of a running focus session (default: `red`).
+    pub focus: String,
+    /// Color of a running break session (default: `blue`).
+    pub r#break: String,
+    /// Color of a running session with less than 10% of its time left (default: `magenta`).
+    pub ending: String,
+    /// Color of a paused session (default: `yellow`).
+    pub paused: String,
+    /// Color of a completed session (default: `green`).
+    pub completed: String,
+    /// Color of an aborted session (default: `red`).
+    pub aborted: String,
+}
+
+/// Returns the default colors, matching the tmux plugin's built-in status format.
+impl Default for ColorsConfig {
+    fn default() -> Self {
+        Self {
+            focus: "red".to_string(),
+            r#break: "blue".to_string(),
+            ending: "magenta".to_string(),
+            paused: "yellow".to_string(),
+            completed: "green".to_string(),
+            aborted: "red".to_string(),
         }
     }
 }
@@ -55,37 +292,406 @@ pub struct Program {
         long = "in-memory",
         default_value_t = false,
         global = true,
-        hide = true
+        help_heading = "Advanced"
     )]
     pub in_memory: bool,
 
     /// Skip hook execution for this invocation.
-    #[arg(long = "no-hooks", default_value_t = false, global = true, hide = true)]
+    #[arg(
+        long = "no-hooks",
+        default_value_t = false,
+        global = true,
+        help_heading = "Advanced"
+    )]
     pub no_hooks: bool,
 
+    /// Fail when the hook for an event does not exist, instead of skipping it. Meant for setting
+    /// up hooks: a wrong hooks path is reported rather than silently ignored.
+    #[arg(
+        long = "require-hooks",
+        default_value_t = false,
+        global = true,
+        conflicts_with = "no_hooks",
+        help_heading = "Advanced"
+    )]
+    pub require_hooks: bool,
+
+    /// Exit with an error when a command changes nothing (e.g. `stop` without a session).
+    #[arg(long = "strict", default_value_t = false, global = true)]
+    pub strict: bool,
+
+    /// Refresh the tmux status line after a state change (also `refresh_tmux` in the config).
+    #[arg(long = "refresh-tmux", default_value_t = false, global = true)]
+    pub refresh_tmux: bool,
+
     /// Command specifies the subcommand to execute.
     #[command(subcommand)]
     pub command: ProgramCommand,
 }
 
+/// Examples shown at the end of `pomodoro start --help`.
+const START_EXAMPLES: &str = "\
+Examples:
+  pomodoro start                              Start a 25-minute focus session
+  pomodoro start --mode break --duration 10m  Start a 10-minute break
+  pomodoro start --wait --then 'say done'     Block, then run a command on completion
+  pomodoro start --print-ends-at --quiet      Print only when the session ends";
+
+/// Examples shown at the end of `pomodoro stop --help`.
+const STOP_EXAMPLES: &str = "\
+Examples:
+  pomodoro stop                  Pause the running session
+  pomodoro stop --reset          Abort the session (same as cancel)
+  pomodoro stop --complete       Count the session as completed
+  pomodoro stop --output json    Report the elapsed and remaining time as JSON";
+
+/// Examples shown at the end of `pomodoro cancel --help`.
+const CANCEL_EXAMPLES: &str = "\
+Examples:
+  pomodoro cancel                Abort the running or paused session
+  pomodoro --strict cancel       Fail when there is no session to cancel";
+
+/// Examples shown at the end of `pomodoro pause --help`.
+const PAUSE_EXAMPLES: &str = "\
+Examples:
+  pomodoro pause                 Pause the running session
+  pomodoro --strict pause        Fail when no session is running";
+
+/// Examples shown at the end of `pomodoro resume --help`.
+const RESUME_EXAMPLES: &str = "\
+Examples:
+  pomodoro resume                Resume the paused session
+  pomodoro --strict resume       Fail when no session is paused";
+
+/// Examples shown at the end of `pomodoro extend --help`.
+const EXTEND_EXAMPLES: &str = "\
+Examples:
+  pomodoro extend 10m            Add 10 minutes to the current session
+  pomodoro extend 5m -o json     Print the new remaining time as JSON";
+
+/// Examples shown at the end of `pomodoro interrupt --help`.
+const INTERRUPT_EXAMPLES: &str = "\
+Examples:
+  pomodoro interrupt                         Record that you got distracted
+  pomodoro interrupt --external \"call\"       Record a phone call with a note
+  pomodoro interrupt -o json                 Print the recorded interruption as JSON";
+
+/// Examples shown at the end of `pomodoro status --help`.
+const STATUS_EXAMPLES: &str = "\
+Examples:
+  pomodoro status --output tmux                                       Colored status for tmux
+  pomodoro status --format '{{ kind }} {{ remaining_secs // 60 }}m'  Custom template
+  pomodoro status --json-path remaining_secs                         A single JSON field";
+
+/// Examples shown at the end of `pomodoro summary --help`.
+const SUMMARY_EXAMPLES: &str = "\
+Examples:
+  pomodoro summary                Today: 4 focus (1h40m), 3 breaks; current: ...
+  pomodoro summary --output json  The same digest as JSON";
+
+/// Examples shown at the end of `pomodoro stats --help`.
+const STATS_EXAMPLES: &str = "\
+Examples:
+  pomodoro stats                  Last 7d: 20 focus sessions completed (8h20m), ...
+  pomodoro stats --since 1d       The same over the last day
+  pomodoro stats --output json    The aggregates as JSON";
+
+/// Examples shown at the end of `pomodoro context --help`.
+const CONTEXT_EXAMPLES: &str = "\
+Examples:
+  pomodoro context                Print the tmux environment, paths, and session activity
+  pomodoro whoami --output json   The same as JSON";
+
+/// Examples shown at the end of `pomodoro serve --help`.
+const SERVE_EXAMPLES: &str = "\
+Examples:
+  pomodoro serve --socket /tmp/pomodoro.sock                Answer each connection with one status line
+  pomodoro serve --socket /tmp/pomodoro.sock --interval 1s  Stream a status line every second";
+
+/// Examples shown at the end of `pomodoro watch --help`.
+const WATCH_EXAMPLES: &str = "\
+Examples:
+  pomodoro watch                 Print the status whenever the remaining time changes
+  pomodoro watch --output json   Print the status as a line of JSON instead
+  pomodoro watch --interval 5s   Check the status every five seconds";
+
+/// Examples shown at the end of `pomodoro note --help`.
+const NOTE_EXAMPLES: &str = "\
+Examples:
+  pomodoro note \"finished the parser\"   Add a timestamped note to the journal";
+
+/// Examples shown at the end of `pomodoro focus-on --help`.
+const FOCUS_ON_EXAMPLES: &str = "\
+Examples:
+  pomodoro focus-on <ID>        Make start, stop, and status act on this session
+  pomodoro focus-on --clear     Follow the most recent session again";
+
+/// Examples shown at the end of `pomodoro notes --help`.
+const NOTES_EXAMPLES: &str = "\
+Examples:
+  pomodoro notes --today           Today's notes, oldest first
+  pomodoro notes --output json     Every note as JSON";
+
+/// Examples shown at the end of `pomodoro replay --help`.
+const REPLAY_EXAMPLES: &str = "\
+Examples:
+  pomodoro replay                   How the elapsed time of the latest session adds up
+  pomodoro replay --session <ID>    The same for another session
+  pomodoro replay --output json     The steps as JSON";
+
+/// Examples shown at the end of `pomodoro list --help`.
+const LIST_EXAMPLES: &str = "\
+Examples:
+  pomodoro list                   The 10 most recent sessions
+  pomodoro list --limit 50        The 50 most recent sessions
+  pomodoro list --kind break      The 10 most recent breaks
+  pomodoro list --output json     The sessions as JSON";
+
+/// Examples shown at the end of `pomodoro gc --help`.
+const GC_EXAMPLES: &str = "\
+Examples:
+  pomodoro gc                     Remove pauses shorter than 5 seconds
+  pomodoro gc --threshold 1m      Remove pauses shorter than a minute";
+
+/// Examples shown at the end of `pomodoro edit-config --help`.
+const EDIT_CONFIG_EXAMPLES: &str = "\
+Examples:
+  pomodoro edit-config                Edit the configuration in $VISUAL or $EDITOR
+  EDITOR=nano pomodoro edit-config    Edit it with a specific editor";
+
+/// Examples shown at the end of `pomodoro init --help`.
+const INIT_EXAMPLES: &str = "\
+Examples:
+  pomodoro init                  Create the configuration file and example hooks
+  pomodoro init --force          Replace them with the templates";
+
+/// Examples shown at the end of `pomodoro suspend --help`.
+const SUSPEND_EXAMPLES: &str = "\
+Examples:
+  pomodoro suspend               Run from a system sleep hook (e.g. systemd-sleep)";
+
+/// Examples shown at the end of `pomodoro resume-from-suspend --help`.
+const RESUME_FROM_SUSPEND_EXAMPLES: &str = "\
+Examples:
+  pomodoro resume-from-suspend   Run from a system wake hook (e.g. systemd-sleep)";
+
 /// Top-level subcommand dispatched by [`Program`].
 #[derive(Debug, Subcommand)]
 pub enum ProgramCommand {
     /// StartCommand is responsible for starting a new pomodoro timer session.
-    #[command(name = "start", about = "Start a new pomodoro timer session")]
+    #[command(
+        name = "start",
+        about = "Start a new pomodoro timer session",
+        after_help = START_EXAMPLES
+    )]
     Start(StartCommandArgs),
 
     /// StopCommand is responsible for stopping the current pomodoro timer session.
-    #[command(name = "stop", about = "Stop the current pomodoro timer session")]
+    #[command(
+        name = "stop",
+        about = "Stop the current pomodoro timer session",
+        after_help = STOP_EXAMPLES
+    )]
     Stop(StopCommandArgs),
 
+    /// CancelCommand aborts the running or paused session, like `stop --reset`.
+    #[command(
+        name = "cancel",
+        visible_alias = "abort",
+        about = "Abort the running or paused pomodoro timer session",
+        after_help = CANCEL_EXAMPLES
+    )]
+    Cancel(PauseCommandArgs),
+
+    /// PauseCommand pauses the running session, and does nothing otherwise.
+    #[command(
+        name = "pause",
+        about = "Pause the running pomodoro timer session",
+        after_help = PAUSE_EXAMPLES
+    )]
+    Pause(PauseCommandArgs),
+
+    /// ResumeCommand resumes the paused session, and does nothing otherwise.
+    #[command(
+        name = "resume",
+        about = "Resume the paused pomodoro timer session",
+        after_help = RESUME_EXAMPLES
+    )]
+    Resume(PauseCommandArgs),
+
+    /// ExtendCommand adds time to the running or paused session, keeping the time spent on it.
+    #[command(
+        name = "extend",
+        about = "Add time to the running or paused pomodoro timer session",
+        after_help = EXTEND_EXAMPLES
+    )]
+    Extend(ExtendCommandArgs),
+
+    /// InterruptCommand records an interruption of the current session without changing its state.
+    #[command(
+        name = "interrupt",
+        about = "Record an interruption of the running or paused pomodoro timer session",
+        after_help = INTERRUPT_EXAMPLES
+    )]
+    Interrupt(InterruptCommandArgs),
+
     /// StatusCommand is responsible for displaying the current status of the pomodoro timer.
-    #[command(name = "status", about = "Display the current pomodoro timer status")]
+    #[command(
+        name = "status",
+        about = "Display the current pomodoro timer status",
+        after_help = STATUS_EXAMPLES
+    )]
     Status(StatusCommandArgs),
+
+    /// SummaryCommand prints a one-line digest of today's sessions and the current status.
+    #[command(
+        name = "summary",
+        about = "Display a one-line digest of today's sessions",
+        after_help = SUMMARY_EXAMPLES
+    )]
+    Summary(SummaryCommandArgs),
+
+    /// StatsCommand aggregates the focus sessions of a window, e.g. the last week.
+    #[command(
+        name = "stats",
+        about = "Display focus session aggregates over a window",
+        after_help = STATS_EXAMPLES
+    )]
+    Stats(StatsCommandArgs),
+
+    /// ContextCommand prints the runtime environment the timer sees, to debug the tmux setup.
+    #[command(
+        name = "context",
+        visible_alias = "whoami",
+        about = "Display the runtime context (tmux, paths, active session)",
+        after_help = CONTEXT_EXAMPLES
+    )]
+    Context(ContextCommandArgs),
+
+    /// ServeCommand answers status requests on a Unix domain socket, so that a long-running
+    /// client does not have to spawn a process for every refresh.
+    #[command(
+        name = "serve",
+        about = "Serve the status as JSON on a Unix domain socket",
+        after_help = SERVE_EXAMPLES
+    )]
+    Serve(ServeCommandArgs),
+
+    /// WatchCommand prints the status whenever it changes, until interrupted.
+    #[command(
+        name = "watch",
+        about = "Print the status whenever the remaining time changes",
+        after_help = WATCH_EXAMPLES
+    )]
+    Watch(WatchCommandArgs),
+
+    /// FocusOnCommand pins the session that start, stop, and status act on.
+    #[command(
+        name = "focus-on",
+        about = "Act on a given session instead of the most recent one",
+        after_help = FOCUS_ON_EXAMPLES
+    )]
+    FocusOn(FocusOnCommandArgs),
+
+    /// NoteCommand adds a free-form, timestamped note to the journal.
+    #[command(
+        name = "note",
+        about = "Add a timestamped note to the journal",
+        after_help = NOTE_EXAMPLES
+    )]
+    Note(NoteCommandArgs),
+
+    /// NotesCommand lists the notes of the journal.
+    #[command(
+        name = "notes",
+        about = "List the notes of the journal",
+        after_help = NOTES_EXAMPLES
+    )]
+    Notes(NotesCommandArgs),
+
+    /// ReplayCommand prints the timeline reconstructed from a session's events.
+    #[command(
+        name = "replay",
+        about = "Print the timeline reconstructed from a session's events",
+        after_help = REPLAY_EXAMPLES
+    )]
+    Replay(ReplayCommandArgs),
+
+    /// ListCommand lists the most recent sessions with their final state.
+    #[command(
+        name = "list",
+        about = "List the most recent sessions",
+        after_help = LIST_EXAMPLES
+    )]
+    List(ListCommandArgs),
+
+    /// GcCommand removes pauses too short to matter from the event log.
+    #[command(
+        name = "gc",
+        about = "Remove negligible pauses from the event log",
+        after_help = GC_EXAMPLES
+    )]
+    Gc(GcCommandArgs),
+
+    /// InitCommand creates the configuration file and example hooks to start from.
+    #[command(
+        name = "init",
+        about = "Create a commented configuration file and example hooks",
+        after_help = INIT_EXAMPLES
+    )]
+    Init(InitCommandArgs),
+
+    /// EditConfigCommand opens the configuration file in the user's editor and validates it.
+    #[command(
+        name = "edit-config",
+        about = "Edit the configuration file in $EDITOR and validate it",
+        after_help = EDIT_CONFIG_EXAMPLES
+    )]
+    EditConfig,
+
+    /// LogCommand appends a raw event to the most recent session. It bypasses the start/stop
+    /// semantics, so it is hidden and meant for scripts and integration tests.
+    #[command(
+        name = "log",
+        about = "Append an event to the most recent session",
+        hide = true
+    )]
+    Log(LogCommandArgs),
+
+    /// SuspendCommand pauses the running session because the system is going to sleep.
+    #[command(
+        name = "suspend",
+        about = "Pause the running session before the system sleeps",
+        after_help = SUSPEND_EXAMPLES
+    )]
+    Suspend(SuspendCommandArgs),
+
+    /// ResumeFromSuspendCommand resumes the session paused by `suspend` once the system wakes up.
+    #[command(
+        name = "resume-from-suspend",
+        about = "Resume the session paused by `suspend` after the system wakes up",
+        after_help = RESUME_FROM_SUSPEND_EXAMPLES
+    )]
+    ResumeFromSuspend(SuspendCommandArgs),
+}
+
+/// DefaultStartKind defines what `start` begins without `--mode` once the
+/// previous session has ended.
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DefaultStartKind {
+    /// Always start a focus session.
+    #[default]
+    Focus,
+
+    /// Continue the cycle: start a break after a completed focus session, and
+    /// a focus session otherwise.
+    Next,
 }
 
 /// StartMode defines the session mode for the StartCommand.
-#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[derive(ValueEnum, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
 pub enum StartMode {
     /// Focus mode is the default session type for the pomodoro timer, where users focus on their
     /// tasks.
@@ -107,43 +713,355 @@ impl std::fmt::Display for StartMode {
 }
 
 /// StartCommandArgs defines the arguments for the StartCommand.
-#[derive(Debug, Args, Default)]
+#[derive(Debug, Clone, Args, Default)]
 pub struct StartCommandArgs {
     /// Mode specifies the type of session to start, either "focus" or "break". The default is
-    /// "focus".
-    #[arg(help = "The session mode", default_value_t = StartMode::Focus, short, long)]
-    pub mode: StartMode,
+    /// "focus". It only applies to new sessions: resuming a paused session keeps that session's
+    /// mode, and a conflicting `--mode` is reported as a warning on stderr.
+    #[arg(
+        help = "The session mode [default: focus]; ignored with a warning when resuming",
+        short,
+        long
+    )]
+    pub mode: Option<StartMode>,
 
     /// Duration specifies the length of the pomodoro timer session. The default is 25 minutes for
     /// focus sessions and 5 minutes for break sessions. The duration can be specified in a
     /// human-readable format (e.g., "25m" for 25 minutes, "1h" for 1 hour) and will be parsed
-    /// using the humantime crate.
-    #[arg(help = "The duration of the pomodoro timer", value_parser = humantime::parse_duration, short, long)]
-    pub duration: Option<Duration>,
+    /// using the humantime crate. A duration of `-` is read from stdin, e.g. when piped from
+    /// another tool.
+    #[arg(
+        help = "The duration of the pomodoro timer, or - to read it from stdin",
+        short,
+        long,
+        value_parser = parse_duration_arg
+    )]
+    pub duration: Option<HumanDuration>,
+
+    /// Until specifies when the session should end instead of how long it lasts, either as a
+    /// local time of day (e.g. "17:00") or as an RFC 3339 timestamp. The duration is derived from
+    /// it by [`StartCommandArgs::with_until`].
+    #[arg(
+        help = "End the session at a local time of day (e.g. 17:00) or RFC 3339 timestamp",
+        short,
+        long,
+        conflicts_with = "duration"
+    )]
+    pub until: Option<UntilTime>,
+
+    /// Tag labels the new session, e.g. with the project worked on. It is stored with the session,
+    /// reported by `status` as `tag`, and passed to the hooks. Like `--mode`, it only applies to
+    /// new sessions.
+    #[arg(help = "Label the new session (e.g. a project name)", short, long)]
+    pub tag: Option<String>,
+
+    /// Wait blocks the command until the session is no longer running or paused, which makes it
+    /// usable as a foreground timer in scripts.
+    #[arg(help = "Block until the session completes or is aborted", short, long)]
+    pub wait: bool,
+
+    /// Then specifies a shell command that is run via `sh -c` once the session completes. Unlike
+    /// hooks it only applies to this invocation, and it is skipped when the session is aborted.
+    #[arg(
+        help = "Shell command to run when the session completes",
+        long,
+        requires = "wait"
+    )]
+    pub then: Option<String>,
+
+    /// Progress prints a status line at this interval while waiting, so that a script can show
+    /// a countdown. The line is JSON with `--output json` and the status template otherwise.
+    #[arg(
+        help = "Print a status line at this interval while waiting (default: 1s)",
+        long,
+        num_args = 0..=1,
+        default_missing_value = "1s",
+        requires = "wait",
+        value_parser = parse_progress_interval
+    )]
+    pub progress: Option<HumanDuration>,
+
+    /// Format specifies a custom MiniJinja template for the confirmation message, rendered with
+    /// `action`, `kind`, `session_id`, and `message`.
+    #[arg(
+        help = "Custom MiniJinja template for the confirmation message",
+        short,
+        long
+    )]
+    pub format: Option<String>,
+
+    /// Output specifies the format of the confirmation: a human-readable message or a JSON object
+    /// with the action taken and the resulting session.
+    #[arg(help = "The output type", default_value_t = CommandOutput::Text, short, long)]
+    pub output: CommandOutput,
+
+    /// PrintEndsAt prints when the started or resumed session runs out of time, as an RFC 3339
+    /// timestamp on a line of its own, e.g. to schedule a reminder with `at` or `systemd-run`.
+    #[arg(help = "Print when the session ends as an RFC 3339 timestamp", long)]
+    pub print_ends_at: bool,
+
+    /// Quiet suppresses the confirmation message, so that `--print-ends-at` prints nothing but
+    /// the timestamp.
+    #[arg(help = "Do not print the confirmation message", short, long)]
+    pub quiet: bool,
 }
 
 impl StartCommandArgs {
+    /// Derive `duration` from `until` as the time left between `now` and the requested end.
+    ///
+    /// A time of day refers to today; an end that is not in the future is an error.
+    pub fn with_until(mut self, now: chrono::DateTime<chrono::Local>) -> Result<Self> {
+        if let Some(until) = &self.until {
+            let ends_at = until.resolve(now)?;
+            let duration = (ends_at - now).to_std().ok().filter(|d| d.as_secs() > 0);
+            let duration = duration.with_context(|| format!("--until {} is in the past", until))?;
+            self.duration = Some(Duration::from_secs(duration.as_secs()).into());
+        }
+        Ok(self)
+    }
+
     /// Fill in `duration` from `config` when the user did not pass `--duration`.
     ///
     /// The config-sourced default depends on `mode`: focus sessions use
     /// `config.focus_duration`, break sessions use `config.break_duration`.
     pub fn with_config(mut self, config: &ProgramConfig) -> Self {
         if self.duration.is_none() {
-            self.duration = Some(match self.mode {
+            let duration = match self.mode.unwrap_or_default() {
                 StartMode::Focus => config.focus_duration,
                 StartMode::Break => config.break_duration,
-            });
+            };
+            self.duration = Some(duration.into());
         }
         self
     }
 }
 
+/// HumanDuration is a duration parsed from a human-readable string (e.g. `1h30m`) that keeps
+/// the original text, so that it can be stored next to the number of seconds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HumanDuration {
+    /// The parsed duration, used for all calculations.
+    pub duration: Duration,
+    /// The text the duration was parsed from.
+    pub text: String,
+}
+
+impl std::str::FromStr for HumanDuration {
+    type Err = humantime::DurationError;
+
+    fn from_str(text: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(Self {
+            duration: parse_duration(text)?,
+            text: text.to_string(),
+        })
+    }
+}
+
+/// Parse a human-readable duration such as `25m` or `1h30m` (see humantime). A single decimal
+/// number with a unit is accepted as well, e.g. `1.5h`, `90min`, or `2hr`, for units of seconds
+/// (`s`, `sec`), minutes (`m`, `min`), and hours (`h`, `hr`).
+pub fn parse_duration(text: &str) -> std::result::Result<Duration, humantime::DurationError> {
+    static DECIMAL: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^\s*(\d+(?:\.\d+)?)\s*([a-z]+)\s*$").expect("Invalid regex"));
+
+    if let Some(captures) = DECIMAL.captures(text) {
+        let unit_secs = match &captures[2] {
+            "s" | "sec" | "secs" | "second" | "seconds" => Some(1.0),
+            "m" | "min" | "mins" | "minute" | "minutes" => Some(60.0),
+            "h" | "hr" | "hrs" | "hour" | "hours" => Some(3600.0),
+            _ => None,
+        };
+        if let (Ok(value), Some(unit_secs)) = (captures[1].parse::<f64>(), unit_secs) {
+            return Ok(Duration::from_millis(
+                (value * unit_secs * 1000.0).round() as u64
+            ));
+        }
+    }
+    humantime::parse_duration(text)
+}
+
+/// Deserialize a configured duration with [`parse_duration`].
+fn deserialize_duration<'de, D>(deserializer: D) -> std::result::Result<Duration, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let text = String::deserialize(deserializer)?;
+    parse_duration(&text).map_err(serde::de::Error::custom)
+}
+
+/// Parse a `--duration` argument, reading the duration from stdin when given as `-`.
+fn parse_duration_arg(text: &str) -> Result<HumanDuration> {
+    let text = read_stdin_value(text, std::io::stdin().lock())?;
+    Ok(text.parse()?)
+}
+
+/// Parse a `--progress` interval, refusing zero, which would print in a busy loop.
+fn parse_progress_interval(text: &str) -> Result<HumanDuration> {
+    let interval: HumanDuration = text.parse()?;
+    anyhow::ensure!(
+        !interval.duration.is_zero(),
+        "Expected an interval longer than 0s"
+    );
+    Ok(interval)
+}
+
+/// Resolve an argument value given as `-` by reading it from `reader`, trimming
+/// surrounding whitespace such as the newline `echo` appends. Any other value is
+/// returned as is.
+pub fn read_stdin_value<R: std::io::Read>(value: &str, mut reader: R) -> Result<String> {
+    if value != "-" {
+        return Ok(value.to_string());
+    }
+    let mut text = String::new();
+    reader
+        .read_to_string(&mut text)
+        .context("Failed to read the argument value from stdin")?;
+    let text = text.trim();
+    anyhow::ensure!(!text.is_empty(), "Expected an argument value on stdin");
+    Ok(text.to_string())
+}
+
+/// UntilTime is the end of a session given by `start --until`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UntilTime {
+    /// A local time of day, e.g. `17:00`, on the day the session starts.
+    TimeOfDay(chrono::NaiveTime),
+    /// An RFC 3339 timestamp, e.g. `2024-01-02T17:00:00+01:00`.
+    Timestamp(chrono::DateTime<chrono::FixedOffset>),
+}
+
+impl UntilTime {
+    /// Return the instant this end refers to, taking a time of day from the date of `now`.
+    pub fn resolve(
+        &self,
+        now: chrono::DateTime<chrono::Local>,
+    ) -> Result<chrono::DateTime<chrono::Local>> {
+        match self {
+            UntilTime::TimeOfDay(time) => now
+                .date_naive()
+                .and_time(*time)
+                .and_local_timezone(chrono::Local)
+                .earliest()
+                .with_context(|| format!("{} does not exist in the local timezone today", time)),
+            UntilTime::Timestamp(timestamp) => Ok(timestamp.with_timezone(&chrono::Local)),
+        }
+    }
+}
+
+impl std::str::FromStr for UntilTime {
+    type Err = String;
+
+    fn from_str(text: &str) -> std::result::Result<Self, Self::Err> {
+        if let Ok(timestamp) = chrono::DateTime::parse_from_rfc3339(text) {
+            return Ok(UntilTime::Timestamp(timestamp));
+        }
+        ["%H:%M", "%H:%M:%S"]
+            .iter()
+            .find_map(|format| chrono::NaiveTime::parse_from_str(text, format).ok())
+            .map(UntilTime::TimeOfDay)
+            .ok_or_else(|| {
+                format!(
+                    "expected a time of day (e.g. 17:00) or an RFC 3339 timestamp, got {:?}",
+                    text
+                )
+            })
+    }
+}
+
+impl std::fmt::Display for UntilTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UntilTime::TimeOfDay(time) => write!(f, "{}", time.format("%H:%M:%S")),
+            UntilTime::Timestamp(timestamp) => write!(f, "{}", timestamp.to_rfc3339()),
+        }
+    }
+}
+
+/// Converts a configured [`Duration`] into a [`HumanDuration`], formatting it with humantime.
+impl From<Duration> for HumanDuration {
+    fn from(duration: Duration) -> Self {
+        Self {
+            duration,
+            text: humantime::format_duration(duration).to_string(),
+        }
+    }
+}
+
 /// Arguments for the `stop` subcommand.
 #[derive(Debug, Args, Default)]
 pub struct StopCommandArgs {
     /// Reset specifies whether to reset the pomodoro timer to zero when stopping.
     #[arg(help = "Reset the pomodoro timer to zero", short, long)]
     pub reset: bool,
+
+    /// Complete marks the active session as completed, e.g. when the task was finished early,
+    /// instead of pausing or aborting it.
+    #[arg(
+        help = "Mark the active session as completed",
+        long,
+        conflicts_with = "reset"
+    )]
+    pub complete: bool,
+
+    /// Output specifies the format of the confirmation: a human-readable message or a JSON object
+    /// with the action taken and the final elapsed and remaining time.
+    #[arg(help = "The output type", default_value_t = CommandOutput::Text, short, long)]
+    pub output: CommandOutput,
+
+    /// Format specifies a custom MiniJinja template for the text confirmation message, rendered
+    /// with the same fields as the JSON output.
+    #[arg(
+        help = "Custom MiniJinja template for the confirmation message",
+        short,
+        long
+    )]
+    pub format: Option<String>,
+}
+
+/// CommandOutput defines the output format for commands that report the result of an action.
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum CommandOutput {
+    /// Text output prints a short human-readable confirmation message.
+    #[default]
+    Text,
+
+    /// Json output prints the result as a JSON object for scripts and popups.
+    Json,
+}
+
+impl std::fmt::Display for CommandOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text => write!(f, "text"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// TimeFormat defines how timestamps are written in JSON output.
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum TimeFormat {
+    /// An RFC 3339 string, e.g. `2024-01-02T03:04:05.678Z`.
+    #[default]
+    Rfc3339,
+
+    /// Milliseconds since the Unix epoch, as a number.
+    #[value(name = "epoch_ms")]
+    EpochMs,
+
+    /// Seconds since the Unix epoch, as a number.
+    #[value(name = "epoch_s")]
+    EpochS,
+}
+
+impl std::fmt::Display for TimeFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rfc3339 => write!(f, "rfc3339"),
+            Self::EpochMs => write!(f, "epoch_ms"),
+            Self::EpochS => write!(f, "epoch_s"),
+        }
+    }
 }
 
 /// StatusOutput defines the output format for the StatusCommand.
@@ -157,6 +1075,10 @@ pub enum StatusOutput {
     /// Json output is a machine-readable format that provides the status of the pomodoro timer in a
     /// structured way, making it easier to integrate with other tools or scripts.
     Json,
+
+    /// Tmux output wraps the text output in tmux style directives (e.g. `#[fg=red]…#[default]`)
+    /// colored by the session state, ready to be embedded in `status-right`.
+    Tmux,
 }
 
 impl std::fmt::Display for StatusOutput {
@@ -164,26 +1086,430 @@ impl std::fmt::Display for StatusOutput {
         match self {
             Self::Text => write!(f, "text"),
             Self::Json => write!(f, "json"),
+            Self::Tmux => write!(f, "tmux"),
         }
     }
 }
 
 /// StatusCommandArgs defines the arguments for the StatusCommand.
-#[derive(Debug, Args, Default)]
+#[derive(Debug, Clone, Args, Default)]
 pub struct StatusCommandArgs {
     /// Output specifies the format for displaying the status of the pomodoro timer.
     #[arg(help = "The output type", default_value_t = StatusOutput::Text, short, long)]
     pub output: StatusOutput,
 
-    /// Format specifies a custom MiniJinja template for text output.
-    #[arg(help = "Custom MiniJinja template for text output", short, long)]
+    /// Format specifies a custom MiniJinja template for text output, or the file to read it from
+    /// when prefixed with `@` (e.g. `@~/.config/pomodoro/status.jinja`).
+    #[arg(
+        help = "Custom MiniJinja template for text output, or @FILE to read it from",
+        short,
+        long
+    )]
     pub format: Option<String>,
+
+    /// JsonPath extracts a single field from the JSON status using a dotted path (e.g.
+    /// `remaining_secs`) and prints only its value. It takes precedence over `--output`.
+    #[arg(help = "Print a single field of the JSON status", long = "json-path")]
+    pub json_path: Option<String>,
+
+    /// NoNewline omits the trailing newline, which would otherwise show up as a blank line when
+    /// the output is embedded with tmux `#()`.
+    #[arg(
+        help = "Do not print the trailing newline",
+        short = 'n',
+        long = "no-newline"
+    )]
+    pub no_newline: bool,
+
+    /// Debug adds the number of sessions and events in the database to the status, to check
+    /// that the expected database is in use when the status looks empty.
+    #[arg(help = "Include the database totals in the status", long)]
+    pub debug: bool,
+
+    /// Cached reads the status from the file written when `status_cache` is enabled, without
+    /// opening the database.
+    #[arg(help = "Read the status from the status cache", long)]
+    pub cached: bool,
+
+    /// NoAutoComplete keeps a running session that has no time left running instead of recording
+    /// its completion, like `auto_complete = false` for a single invocation.
+    #[arg(help = "Do not complete a session that ran out of time", long)]
+    pub no_auto_complete: bool,
+
+    /// Vars are extra string variables for the template, e.g. `--var pane=main`, so that one
+    /// template can render differently per pane. They never replace a status field.
+    #[arg(
+        help = "Add a string variable to the template (repeatable)",
+        long = "var",
+        visible_alias = "template-var",
+        value_name = "KEY=VALUE",
+        value_parser = parse_template_var
+    )]
+    pub vars: Vec<(String, String)>,
+
+    /// SetTmuxOption sets the rendered status as the global tmux option `@pomodoro` instead of
+    /// printing it, for a `status-right` that reads `#{@pomodoro}`. Outside of tmux, or when tmux
+    /// cannot be run, the status is printed after all, with a warning. `--tmux` is kept as an
+    /// alias, although it reads much like `--output tmux`.
+    #[arg(
+        help = "Set the tmux option @pomodoro to the status instead of printing it",
+        long,
+        visible_alias = "tmux"
+    )]
+    pub set_tmux_option: bool,
+
+    /// AssertRunning turns the status into a guard for scripts: it exits successfully only while a
+    /// session is running, and prints nothing unless `--verbose` is passed.
+    #[arg(help = "Exit with an error unless a session is running", long)]
+    pub assert_running: bool,
+
+    /// Verbose prints the status with `--assert-running`, which prints nothing otherwise.
+    #[arg(
+        help = "Print the status with --assert-running",
+        short,
+        long,
+        requires = "assert_running"
+    )]
+    pub verbose: bool,
+}
+
+/// Parse a `--var` argument of the form `key=value`.
+fn parse_template_var(text: &str) -> Result<(String, String)> {
+    let (key, value) = text
+        .split_once('=')
+        .with_context(|| format!("Expected KEY=VALUE, got {text:?}"))?;
+    anyhow::ensure!(!key.is_empty(), "Expected KEY=VALUE, got {text:?}");
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// SummaryCommandArgs defines the arguments for the SummaryCommand.
+#[derive(Debug, Args, Default)]
+pub struct SummaryCommandArgs {
+    /// Output specifies the format for displaying the digest.
+    #[arg(help = "The output type", default_value_t = CommandOutput::Text, short, long)]
+    pub output: CommandOutput,
+}
+
+/// StatsCommandArgs defines the arguments for the StatsCommand.
+#[derive(Debug, Args)]
+pub struct StatsCommandArgs {
+    /// Since is how far back the window reaches: sessions started within it are aggregated.
+    #[arg(
+        help = "Aggregate the sessions started within this long (e.g. 7d)",
+        default_value = "7d",
+        short,
+        long
+    )]
+    pub since: HumanDuration,
+
+    /// Output specifies the format for displaying the aggregates.
+    #[arg(help = "The output type", default_value_t = CommandOutput::Text, short, long)]
+    pub output: CommandOutput,
+}
+
+/// ContextCommandArgs defines the arguments for the ContextCommand.
+#[derive(Debug, Args, Default)]
+pub struct ContextCommandArgs {
+    /// Output specifies the format for displaying the context.
+    #[arg(help = "The output type", default_value_t = CommandOutput::Text, short, long)]
+    pub output: CommandOutput,
+}
+
+/// FocusOnCommandArgs defines the arguments for the FocusOnCommand.
+#[derive(Debug, Args)]
+pub struct FocusOnCommandArgs {
+    /// Session is the ID of the session to pin until it is aborted or completed.
+    #[arg(
+        help = "The ID of the session to act on",
+        required_unless_present = "clear"
+    )]
+    pub session: Option<Uuid>,
+
+    /// Clear removes the pin, so that commands act on the most recent session again.
+    #[arg(
+        help = "Act on the most recent session again",
+        long,
+        conflicts_with = "session"
+    )]
+    pub clear: bool,
+}
+
+/// NoteCommandArgs defines the arguments for the NoteCommand.
+#[derive(Debug, Args)]
+pub struct NoteCommandArgs {
+    /// Text is the content of the note.
+    #[arg(help = "The text of the note")]
+    pub text: String,
+}
+
+/// NotesCommandArgs defines the arguments for the NotesCommand.
+#[derive(Debug, Args, Default)]
+pub struct NotesCommandArgs {
+    /// Today restricts the list to the notes written since local midnight.
+    #[arg(help = "Only list the notes written today", short, long)]
+    pub today: bool,
+
+    /// Output specifies the format for displaying the notes.
+    #[arg(help = "The output type", default_value_t = CommandOutput::Text, short, long)]
+    pub output: CommandOutput,
+
+    /// TimeFormat selects how `created_at` is written in JSON output.
+    #[arg(help = "The timestamp format in JSON output", default_value_t = TimeFormat::Rfc3339, long)]
+    pub time_format: TimeFormat,
+}
+
+/// ReplayCommandArgs defines the arguments for the ReplayCommand.
+#[derive(Debug, Args, Default)]
+pub struct ReplayCommandArgs {
+    /// Session selects the session to replay instead of the most recent one.
+    #[arg(
+        help = "The ID of the session to replay (default: the most recent)",
+        short,
+        long
+    )]
+    pub session: Option<Uuid>,
+
+    /// Output specifies the format for displaying the timeline.
+    #[arg(help = "The output type", default_value_t = CommandOutput::Text, short, long)]
+    pub output: CommandOutput,
+}
+
+/// ListCommandArgs defines the arguments for the ListCommand.
+#[derive(Debug, Args)]
+pub struct ListCommandArgs {
+    /// Limit is the maximum number of sessions to list, newest first.
+    #[arg(
+        help = "The maximum number of sessions to list",
+        default_value_t = 10,
+        short,
+        long
+    )]
+    pub limit: u32,
+
+    /// Kind restricts the list to sessions of this kind.
+    #[arg(help = "Only list sessions of this kind", short, long)]
+    pub kind: Option<StartMode>,
+
+    /// Output specifies the format for displaying the sessions.
+    #[arg(help = "The output type", default_value_t = CommandOutput::Text, short, long)]
+    pub output: CommandOutput,
+}
+
+/// GcCommandArgs defines the arguments for the GcCommand.
+#[derive(Debug, Args)]
+pub struct GcCommandArgs {
+    /// Threshold is the length below which a pause and the resume that ended it are removed.
+    #[arg(
+        help = "Remove pauses shorter than this (e.g. 5s)",
+        default_value = "5s",
+        short,
+        long
+    )]
+    pub threshold: HumanDuration,
+}
+
+/// ServeCommandArgs defines the arguments for the ServeCommand.
+#[derive(Debug, Args)]
+pub struct ServeCommandArgs {
+    /// Socket is the path of the Unix domain socket to listen on. A stale socket left behind by
+    /// a previous server is replaced.
+    #[arg(help = "The path of the Unix domain socket to listen on", short, long)]
+    pub socket: PathBuf,
+
+    /// Interval makes the server stream a status line on every tick until the client hangs up,
+    /// instead of closing the connection after the first line.
+    #[arg(help = "Stream the status at this interval (e.g. 1s)", short, long)]
+    pub interval: Option<HumanDuration>,
+}
+
+/// WatchCommandArgs defines the arguments for the WatchCommand.
+#[derive(Debug, Args)]
+pub struct WatchCommandArgs {
+    /// Interval is how long to wait between two status checks.
+    #[arg(
+        help = "Check the status at this interval (e.g. 1s)",
+        default_value = "1s",
+        short,
+        long
+    )]
+    pub interval: HumanDuration,
+
+    /// Output specifies the format of every status line.
+    #[arg(help = "The output type", default_value_t = CommandOutput::Text, short, long)]
+    pub output: CommandOutput,
+}
+
+/// LogEventKind defines the event kinds that can be appended with the LogCommand.
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LogEventKind {
+    /// Start the session; only valid for a session without events.
+    Started,
+    /// Resume the paused session.
+    Resumed,
+    /// Pause the running session.
+    Paused,
+    /// Abort the running or paused session.
+    Aborted,
+    /// Complete the running or paused session.
+    Completed,
+}
+
+/// LogCommandArgs defines the arguments for the LogCommand.
+#[derive(Debug, Args)]
+pub struct LogCommandArgs {
+    /// Kind specifies the event to append.
+    #[arg(help = "The event to append", value_enum)]
+    pub kind: LogEventKind,
+
+    /// Output specifies the format of the confirmation: a human-readable message or a JSON object
+    /// with the action taken and the resulting session.
+    #[arg(help = "The output type", default_value_t = CommandOutput::Text, short, long)]
+    pub output: CommandOutput,
+}
+
+/// InitCommandArgs defines the arguments for the InitCommand.
+#[derive(Debug, Args, Default)]
+pub struct InitCommandArgs {
+    /// Force overwrites files that already exist with the templates.
+    #[arg(help = "Overwrite existing files", long)]
+    pub force: bool,
+}
+
+/// PauseCommandArgs defines the arguments for the PauseCommand, the ResumeCommand, and the
+/// CancelCommand.
+#[derive(Debug, Args, Default)]
+pub struct PauseCommandArgs {
+    /// Output specifies the format of the confirmation: a human-readable message or a JSON object
+    /// with the action taken and the resulting session.
+    #[arg(help = "The output type", default_value_t = CommandOutput::Text, short, long)]
+    pub output: CommandOutput,
+}
+
+/// ExtendCommandArgs defines the arguments for the ExtendCommand.
+#[derive(Debug, Args)]
+pub struct ExtendCommandArgs {
+    /// Duration specifies how much time to add to the session, in a human-readable format (e.g.
+    /// "10m").
+    #[arg(help = "The time to add to the session, e.g. 10m")]
+    pub duration: HumanDuration,
+
+    /// Output specifies the format of the confirmation: a human-readable message or a JSON object
+    /// with the action taken and the resulting session.
+    #[arg(help = "The output type", default_value_t = CommandOutput::Text, short, long)]
+    pub output: CommandOutput,
+}
+
+/// InterruptCommandArgs defines the arguments for the InterruptCommand.
+#[derive(Debug, Args, Default)]
+pub struct InterruptCommandArgs {
+    /// External marks the interruption as caused by someone or something else (e.g. a call),
+    /// rather than by yourself (e.g. checking the mail).
+    #[arg(help = "The interruption came from someone or something else", long)]
+    pub external: bool,
+
+    /// Note describes the interruption.
+    #[arg(help = "A note describing the interruption")]
+    pub note: Option<String>,
+
+    /// Output specifies the format of the confirmation: a human-readable message or a JSON object
+    /// with the action taken and the resulting session.
+    #[arg(help = "The output type", default_value_t = CommandOutput::Text, short, long)]
+    pub output: CommandOutput,
+}
+
+/// SuspendCommandArgs defines the arguments for the SuspendCommand and the
+/// ResumeFromSuspendCommand.
+#[derive(Debug, Args, Default)]
+pub struct SuspendCommandArgs {
+    /// Output specifies the format of the confirmation: a human-readable message or a JSON object
+    /// with the action taken and the resulting session.
+    #[arg(help = "The output type", default_value_t = CommandOutput::Text, short, long)]
+    pub output: CommandOutput,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn load_from_parses_toml_and_json_alike() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("pomodoro-formats-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let files = [
+            (
+                "config.toml",
+                "focus_duration = \"50m\"\nbreak_duration = \"10m 30s\"\n",
+            ),
+            (
+                "config.json",
+                r#"{"focus_duration": "50m", "break_duration": "10m 30s"}"#,
+            ),
+        ];
+
+        for (name, content) in files {
+            let path = dir.join(name);
+            std::fs::write(&path, content)?;
+            let config = ProgramConfig::load_from(&path)?;
+            assert_eq!(
+                config.focus_duration,
+                Duration::from_secs(50 * 60),
+                "{name}"
+            );
+            assert_eq!(
+                config.break_duration,
+                Duration::from_secs(10 * 60 + 30),
+                "{name}"
+            );
+            assert_eq!(config.lock_retries, 5, "{name}");
+        }
+
+        std::fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn allowed_modes_parses_from_toml() -> Result<()> {
+        let config: ProgramConfig = toml::from_str("allowed_modes = [\"focus\"]\n")?;
+        assert_eq!(config.allowed_modes, vec![StartMode::Focus]);
+        assert_eq!(
+            ProgramConfig::default().allowed_modes,
+            vec![StartMode::Focus, StartMode::Break]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn load_from_reports_the_parse_error_for_the_format() -> Result<()> {
+        let path =
+            std::env::temp_dir().join(format!("pomodoro-invalid-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"focus_duration": ["not", "a", "duration"]}"#)?;
+        let err = ProgramConfig::load_from(&path).unwrap_err();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(err.to_string(), "Failed to load configuration file");
+        Ok(())
+    }
+
+    #[test]
+    fn default_config_template_parses_to_defaults() -> Result<()> {
+        let config: ProgramConfig = toml::from_str(DEFAULT_CONFIG_TEMPLATE)?;
+        assert_eq!(
+            config.focus_duration,
+            ProgramConfig::default().focus_duration
+        );
+
+        // Every key is documented, commented out.
+        for key in [
+            "focus_duration",
+            "break_duration",
+            "lock_retries",
+            "[templates]",
+            "[colors]",
+        ] {
+            assert!(DEFAULT_CONFIG_TEMPLATE.contains(&format!("# {key}")));
+        }
+        Ok(())
+    }
+
     #[test]
     fn text_output_displays_as_text() {
         let output = StatusOutput::Text;
@@ -196,6 +1522,24 @@ mod tests {
         assert_eq!(output.to_string(), "json");
     }
 
+    #[test]
+    fn text_command_output_displays_as_text() {
+        let output = CommandOutput::Text;
+        assert_eq!(output.to_string(), "text");
+    }
+
+    #[test]
+    fn json_command_output_displays_as_json() {
+        let output = CommandOutput::Json;
+        assert_eq!(output.to_string(), "json");
+    }
+
+    #[test]
+    fn tmux_output_displays_as_tmux() {
+        let output = StatusOutput::Tmux;
+        assert_eq!(output.to_string(), "tmux");
+    }
+
     #[test]
     fn focus_mode_displays_as_focus() {
         let mode = StartMode::Focus;
@@ -212,22 +1556,135 @@ mod tests {
     fn with_config_uses_break_duration_for_break_mode() {
         let config = ProgramConfig::default();
         let args = StartCommandArgs {
-            mode: StartMode::Break,
+            mode: Some(StartMode::Break),
             duration: None,
+            ..Default::default()
         };
         let result = args.with_config(&config);
-        assert_eq!(result.duration, Some(config.break_duration));
+        assert_eq!(result.duration, Some(config.break_duration.into()));
     }
 
     #[test]
-    fn with_config_preserves_provided_duration() {
+    fn with_config_preserves_provided_duration() -> Result<()> {
         let config = ProgramConfig::default();
-        let custom = std::time::Duration::from_secs(45 * 60);
+        let custom: HumanDuration = "45m".parse()?;
         let args = StartCommandArgs {
-            mode: StartMode::Focus,
-            duration: Some(custom),
+            mode: Some(StartMode::Focus),
+            duration: Some(custom.clone()),
+            ..Default::default()
         };
         let result = args.with_config(&config);
         assert_eq!(result.duration, Some(custom));
+        Ok(())
+    }
+
+    #[test]
+    fn with_until_derives_duration_from_a_time_of_day() -> Result<()> {
+        use chrono::TimeZone;
+        let now = chrono::Local
+            .with_ymd_and_hms(2024, 1, 2, 15, 30, 0)
+            .single()
+            .context("unambiguous local time")?;
+        let args = StartCommandArgs {
+            until: Some("17:00".parse().map_err(anyhow::Error::msg)?),
+            ..Default::default()
+        };
+        let result = args.with_until(now)?.with_config(&ProgramConfig::default());
+        let duration = result.duration.context("duration derived from --until")?;
+        assert_eq!(duration.duration, Duration::from_secs(90 * 60));
+        Ok(())
+    }
+
+    #[test]
+    fn with_until_rejects_a_time_in_the_past() -> Result<()> {
+        use chrono::TimeZone;
+        let now = chrono::Local
+            .with_ymd_and_hms(2024, 1, 2, 18, 0, 0)
+            .single()
+            .context("unambiguous local time")?;
+        let args = StartCommandArgs {
+            until: Some("17:00".parse().map_err(anyhow::Error::msg)?),
+            ..Default::default()
+        };
+        let err = args.with_until(now).unwrap_err();
+        assert_eq!(err.to_string(), "--until 17:00:00 is in the past");
+        Ok(())
+    }
+
+    #[test]
+    fn until_time_parses_rfc3339_timestamps() -> Result<()> {
+        let until: UntilTime = "2024-01-02T17:00:00Z".parse().map_err(anyhow::Error::msg)?;
+        let expected = chrono::DateTime::parse_from_rfc3339("2024-01-02T17:00:00+00:00")?;
+        assert_eq!(until, UntilTime::Timestamp(expected));
+        assert!("5pm".parse::<UntilTime>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn read_stdin_value_reads_dash_from_reader() -> Result<()> {
+        let value = read_stdin_value("-", std::io::Cursor::new("30m\n"))?;
+        assert_eq!(value, "30m");
+
+        let value = read_stdin_value("10m", std::io::Cursor::new("30m\n"))?;
+        assert_eq!(value, "10m");
+
+        let result = read_stdin_value("-", std::io::Cursor::new("\n"));
+        assert!(result.is_err(), "An empty stdin is not a value");
+        Ok(())
+    }
+
+    #[test]
+    fn parse_duration_accepts_decimals_and_aliases() -> Result<()> {
+        let cases = [
+            ("1.5h", 90 * 60),
+            ("90min", 90 * 60),
+            ("25m", 25 * 60),
+            ("2hr", 7200),
+        ];
+        for (text, secs) in cases {
+            assert_eq!(parse_duration(text)?, Duration::from_secs(secs), "{text}");
+        }
+        assert_eq!(parse_duration("1h 30m")?, Duration::from_secs(90 * 60));
+        assert!(parse_duration("1.5 fortnights").is_err());
+        assert!(parse_duration("soon").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn config_durations_accept_decimals() -> Result<()> {
+        let config: ProgramConfig = toml::from_str("focus_duration = \"1.5h\"")?;
+        assert_eq!(config.focus_duration, Duration::from_secs(90 * 60));
+        Ok(())
+    }
+
+    #[test]
+    fn human_duration_keeps_original_text() -> Result<()> {
+        let duration: HumanDuration = "1h30m".parse()?;
+        assert_eq!(duration.duration, std::time::Duration::from_secs(5400));
+        assert_eq!(duration.text, "1h30m");
+        Ok(())
+    }
+
+    #[test]
+    fn config_parses_colors_table() -> Result<()> {
+        let config: ProgramConfig = toml::from_str("[colors]\nbreak = \"cyan\"\n")?;
+        assert_eq!(config.colors.r#break, "cyan");
+        assert_eq!(config.colors.focus, ColorsConfig::default().focus);
+        Ok(())
+    }
+
+    #[test]
+    fn template_for_prefers_templates_table_over_status_template() -> Result<()> {
+        let config: ProgramConfig = toml::from_str("status_template = \"legacy\"\n")?;
+        assert_eq!(config.template_for(StatusOutput::Text), Some("legacy"));
+        assert_eq!(config.template_for(StatusOutput::Tmux), None);
+
+        let config: ProgramConfig = toml::from_str(
+            "status_template = \"legacy\"\n[templates]\ntext = \"text\"\ntmux = \"tmux\"\n",
+        )?;
+        assert_eq!(config.template_for(StatusOutput::Text), Some("text"));
+        assert_eq!(config.template_for(StatusOutput::Tmux), Some("tmux"));
+        assert_eq!(config.template_for(StatusOutput::Json), None);
+        Ok(())
     }
 }
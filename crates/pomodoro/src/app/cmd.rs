@@ -1,26 +1,53 @@
 use crate::app::cli::*;
+use crate::app::output::*;
+use crate::app::tmux;
 use crate::hook::run::*;
 use crate::state::model::*;
 use crate::state::query::*;
-use anyhow::Result;
-use chrono::{Duration, Utc};
-use minijinja::Environment;
+use anyhow::{Context, Result};
+use chrono::Duration;
+use regex::Regex;
+use rusqlite::TransactionBehavior;
+use std::borrow::Cow;
+use std::io::Write;
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::UnixListener;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
 use uuid::Uuid;
 
-/// Converts [`StartCommandArgs`] into a [`Session`], applying default durations when none
-/// are provided (25 minutes for focus, 5 minutes for break).
-impl From<&StartCommandArgs> for Session {
-    fn from(value: &StartCommandArgs) -> Self {
-        let config = ProgramConfig::default();
-        let duration = value.duration.unwrap_or(match value.mode {
-            StartMode::Focus => config.focus_duration,
-            StartMode::Break => config.break_duration,
-        });
-        Session {
-            kind: value.mode.into(),
-            planned_duration: Duration::seconds(duration.as_secs() as i64),
+/// Longest planned duration a session may have, so that its end and the
+/// remaining time stay representable and readable.
+pub const MAX_SESSION_DURATION: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+/// Converts resolved [`StartCommandArgs`] into a [`Session`].
+///
+/// The duration must already be resolved — either passed via `--duration` or
+/// filled from the user's configuration by [`StartCommandArgs::with_config`].
+/// Building a session from unresolved arguments is an error rather than a
+/// silent fallback to the built-in defaults, and so is a duration longer than
+/// [`MAX_SESSION_DURATION`].
+impl TryFrom<&StartCommandArgs> for Session {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &StartCommandArgs) -> Result<Self> {
+        let duration = value
+            .duration
+            .as_ref()
+            .context("Session duration must be resolved with StartCommandArgs::with_config")?;
+        anyhow::ensure!(
+            duration.duration <= MAX_SESSION_DURATION,
+            "A session can last at most {}h.",
+            MAX_SESSION_DURATION.as_secs() / 3600
+        );
+        Ok(Session {
+            kind: value.mode.unwrap_or_default().into(),
+            planned_duration: Duration::seconds(duration.duration.as_secs() as i64),
+            planned_human: Some(duration.text.clone()),
+            tag: value.tag.clone(),
             ..Session::default()
-        }
+        })
     }
 }
 
@@ -34,64 +61,325 @@ impl From<StartMode> for SessionKind {
     }
 }
 
+/// Return the latest event of the session pinned with `pomodoro focus-on`, or
+/// `None` when no session is pinned.
+///
+/// A pin only lasts until its session ends: once the session is aborted or
+/// completed, the pin is removed and `None` is returned.
+fn pinned_session_event(querier: &Querier) -> Result<Option<SessionEvent>> {
+    let params = GetMetaArgs {
+        key: MetaKey::PinnedSessionId,
+    };
+    let Some(value) = querier.get_meta(&params)? else {
+        return Ok(None);
+    };
+
+    let session_id = Uuid::parse_str(&value).context("Failed to parse the pinned session ID")?;
+    let params = ListSessionEventsArgs::with_session_id(session_id);
+    let session_event = querier.list_session_events(&params)?.into_iter().next();
+    let ended = session_event.as_ref().is_none_or(|session_event| {
+        matches!(
+            session_event.kind,
+            SessionEventKind::Aborted | SessionEventKind::Completed
+        )
+    });
+    if ended {
+        let params = DeleteMetaArgs {
+            key: MetaKey::PinnedSessionId,
+        };
+        querier.delete_meta(&params)?;
+        return Ok(None);
+    }
+    Ok(session_event)
+}
+
+/// Run the hook for `session_event` of `session` with `runner`, if any, and
+/// return the names of the hooks that ran, for [`CommandOutcome::hooks_fired`].
+///
+/// Hooks are non-fatal (see [`Runner::notify`]).
+fn notify(
+    runner: &Option<Runner>,
+    session: &Session,
+    session_event: &SessionEvent,
+) -> Result<Vec<String>> {
+    let Some(runner) = runner else {
+        return Ok(Vec::new());
+    };
+    let args = SessionEventArgs {
+        session: session.clone(),
+        session_event: session_event.clone(),
+    };
+    Ok(runner.notify(&args)?.into_iter().collect())
+}
+
+/// Return the latest event of the current session: the pinned session (see
+/// [`pinned_session_event`]), or else the most recent one.
+///
+/// Like `status`, this goes by the most recent session rather than the most
+/// recent event, which can belong to a pinned session that was just ended.
+/// Interruptions are left out, so the event is always the session's state.
+fn current_session_event(querier: &Querier) -> Result<Option<SessionEvent>> {
+    if let Some(session_event) = pinned_session_event(querier)? {
+        return Ok(Some(session_event));
+    }
+    let Some(session) = querier.list_sessions(&ListSessionsArgs::first())?.pop() else {
+        return Ok(None);
+    };
+    let params = ListSessionEventsArgs {
+        limit: Some(1),
+        ..ListSessionEventsArgs::with_session_id(session.id)
+    };
+    Ok(querier.list_session_events(&params)?.pop())
+}
+
+/// Return the message reporting that `session` already ended with the terminal
+/// event `kind`, for the commands that have nothing left to do with it.
+fn ended_message(session: &Session, kind: &SessionEventKind) -> String {
+    match kind {
+        SessionEventKind::Aborted => format!("The {} session was already aborted.", session.kind),
+        _ => format!("The {} session already completed.", session.kind),
+    }
+}
+
+/// Return the message reporting that `session` was started; a break mentions
+/// its duration, which tells a long break from a short one.
+fn started_message(session: &Session) -> String {
+    match session.kind {
+        SessionKind::Focus => format!("Started a new {} session.", session.kind),
+        SessionKind::Break => format!(
+            "Started a new {} session ({}).",
+            session.kind,
+            humantime::format_duration(session.planned_duration.to_std().unwrap_or_default())
+        ),
+    }
+}
+
 /// StartCommand is responsible for starting a new pomodoro timer session.
 pub struct StartCommand<'q> {
     /// Runner is used to execute the hooks.
     pub runner: Option<Runner>,
     /// Querier is used to retrieve the current status of the pomodoro timer from the database.
     pub querier: Querier<'q>,
+    /// Config provides the settings that change what gets started (e.g. forced breaks).
+    pub config: ProgramConfig,
 }
 
 impl<'q> StartCommand<'q> {
-    /// Execute the StartCommand with the provided arguments.
-    pub fn execute(&self, args: &StartCommandArgs) -> Result<()> {
-        let params = ListSessionEventsArgs::first();
-        let result = self.querier.list_session_events(&params)?;
+    /// Start a new session or resume a paused one and return what happened.
+    ///
+    /// Pass `args` as given: a missing duration is filled in with
+    /// [`StartCommand::with_config`], once it is clear which kind of session
+    /// starts, so that a break started in place of a focus session keeps an
+    /// explicit `--duration` but not the focus default.
+    pub fn execute(&self, args: &StartCommandArgs) -> Result<CommandOutcome> {
+        let current = current_session_event(&self.querier)?;
 
         let mut session: Session;
-        let session_event = match result.first() {
+        let message;
+        let mut warnings = Vec::new();
+        let session_event = match current.as_ref() {
             None => {
-                session = Session::from(args);
+                session = Session::try_from(&self.with_config(args.clone())?)?;
                 session = self.insert_session(&session)?;
-                println!("Started a new {} session.", session.kind);
+                message = started_message(&session);
                 Some(SessionEvent::started(session.id))
             }
             Some(session_event) => match session_event.kind {
                 SessionEventKind::Started | SessionEventKind::Resumed => {
                     session = self.get_session(&session_event.session_id)?;
-                    println!("A {} session is already running.", session.kind);
+                    message = format!("A {} session is already running.", session.kind);
+                    if let Some(mode) = args.mode.filter(|&m| SessionKind::from(m) != session.kind)
+                    {
+                        warnings.push(format!(
+                            "--mode {mode} ignored: a {} session is already running.",
+                            session.kind
+                        ));
+                    }
+                    if let Some(tag) = args
+                        .tag
+                        .as_ref()
+                        .filter(|&t| session.tag.as_ref() != Some(t))
+                    {
+                        warnings.push(format!(
+                            "--tag {tag} ignored: a {} session is already running.",
+                            session.kind
+                        ));
+                    }
                     None
                 }
                 SessionEventKind::Aborted | SessionEventKind::Completed => {
-                    session = Session::from(args);
-                    session = self.insert_session(&session)?;
-                    println!("Started a new {} session.", session.kind);
+                    match self.forced_break(args, session_event)? {
+                        Some(args) => {
+                            session = Session::try_from(&args)?;
+                            session = self.insert_session(&session)?;
+                            message = format!(
+                                "Started a {} session first: a focus session just completed. \
+                                 Run start again after the break to focus.",
+                                session.kind
+                            );
+                        }
+                        None => {
+                            let args = match self.next_in_cycle(args, session_event)? {
+                                Some(next) => next,
+                                None => self.with_config(args.clone())?,
+                            };
+                            session = Session::try_from(&args)?;
+                            session = self.insert_session(&session)?;
+                            message = started_message(&session);
+                        }
+                    }
                     Some(SessionEvent::started(session.id))
                 }
                 SessionEventKind::Paused => {
                     session = self.get_session(&session_event.session_id)?;
-                    println!("Resumed the {} session.", session.kind);
+                    message = format!("Resumed the {} session.", session.kind);
+                    if let Some(mode) = args.mode.filter(|&m| SessionKind::from(m) != session.kind)
+                    {
+                        warnings.push(format!(
+                            "--mode {mode} ignored: resuming the paused {} session.",
+                            session.kind
+                        ));
+                    }
+                    if let Some(tag) = args
+                        .tag
+                        .as_ref()
+                        .filter(|&t| session.tag.as_ref() != Some(t))
+                    {
+                        warnings.push(format!(
+                            "--tag {tag} ignored: resuming the paused {} session.",
+                            session.kind
+                        ));
+                    }
                     Some(SessionEvent::resumed(session.id))
                 }
+                SessionEventKind::Interrupted => unreachable!("interruptions are not a state"),
             },
         };
 
+        let mut hooks_fired = Vec::new();
         if let Some(session_event) = session_event.as_ref() {
             let params = InsertSessionEventArgs { session_event };
             self.querier.insert_session_event(&params)?;
 
-            if let Some(runner) = &self.runner {
-                let args = SessionEventArgs {
-                    session: session.clone(),
-                    session_event: session_event.clone(),
-                };
-                // Hooks are non-fatal — ignore errors
-                // so a broken hook never kills the session.
-                runner.execute(&args).ok();
+            hooks_fired = notify(&self.runner, &session, session_event)?;
+        }
+
+        let action = match session_event.as_ref().map(|e| &e.kind) {
+            Some(SessionEventKind::Resumed) => CommandAction::Resumed,
+            Some(_) => CommandAction::Started,
+            None => CommandAction::None,
+        };
+        let outcome = CommandOutcome {
+            warnings,
+            hooks_fired,
+            ..CommandOutcome::new(action, message)
+        };
+        outcome.with_session(&self.querier, &session, session_event.as_ref())
+    }
+
+    /// Return the arguments of the break to start instead, when a focus
+    /// session is requested right after another one completed.
+    ///
+    /// Applies when [`ProgramConfig::force_break_between_focus`] is set and
+    /// `last`, the latest event, completed a focus session within that window.
+    /// The break keeps the other arguments (e.g. `--tag`), but not a
+    /// `--duration` meant for the focus session.
+    fn forced_break(
+        &self,
+        args: &StartCommandArgs,
+        last: &SessionEvent,
+    ) -> Result<Option<StartCommandArgs>> {
+        let window = self.config.force_break_between_focus;
+        if window.is_zero()
+            || !self.config.allowed_modes.contains(&StartMode::Break)
+            || args.mode.unwrap_or_default() != StartMode::Focus
+            || last.kind != SessionEventKind::Completed
+            || chrono::Utc::now() - last.created_at > Duration::from_std(window)?
+        {
+            return Ok(None);
+        }
+
+        let previous = self.get_session(&last.session_id)?;
+        if previous.kind != SessionKind::Focus {
+            return Ok(None);
+        }
+
+        let args = StartCommandArgs {
+            mode: Some(StartMode::Break),
+            duration: None,
+            ..args.clone()
+        };
+        Ok(Some(self.with_config(args)?))
+    }
+
+    /// Return the arguments of the break to start instead, when no `--mode`
+    /// is given right after a focus session completed. An explicit
+    /// `--duration` is the break's.
+    ///
+    /// Applies when [`ProgramConfig::default_start_kind`] is
+    /// [`DefaultStartKind::Next`] and `last`, the latest event, completed a
+    /// focus session.
+    fn next_in_cycle(
+        &self,
+        args: &StartCommandArgs,
+        last: &SessionEvent,
+    ) -> Result<Option<StartCommandArgs>> {
+        if self.config.default_start_kind != DefaultStartKind::Next
+            || !self.config.allowed_modes.contains(&StartMode::Break)
+            || args.mode.is_some()
+            || last.kind != SessionEventKind::Completed
+        {
+            return Ok(None);
+        }
+
+        let previous = self.get_session(&last.session_id)?;
+        if previous.kind != SessionKind::Focus {
+            return Ok(None);
+        }
+
+        let args = StartCommandArgs {
+            mode: Some(StartMode::Break),
+            ..args.clone()
+        };
+        Ok(Some(self.with_config(args)?))
+    }
+
+    /// Fill in the duration of `args` from the config, like
+    /// [`StartCommandArgs::with_config`], except that a break due to be a long
+    /// one (see [`StartCommand::long_break_due`]) lasts
+    /// [`ProgramConfig::long_break_duration`].
+    fn with_config(&self, args: StartCommandArgs) -> Result<StartCommandArgs> {
+        let long_break = args.duration.is_none()
+            && args.mode == Some(StartMode::Break)
+            && self.long_break_due()?;
+        let args = if long_break {
+            StartCommandArgs {
+                duration: Some(self.config.long_break_duration.into()),
+                ..args
             }
+        } else {
+            args
+        };
+        Ok(args.with_config(&self.config))
+    }
+
+    /// Whether a break started now is a long one: the number of focus sessions
+    /// completed since the last long break is a multiple of
+    /// [`ProgramConfig::long_break_interval`].
+    ///
+    /// A long break is a break session planned for at least
+    /// [`ProgramConfig::long_break_duration`].
+    fn long_break_due(&self) -> Result<bool> {
+        let interval = self.config.long_break_interval;
+        if interval == 0 {
+            return Ok(false);
         }
 
-        Ok(())
+        let params = CountFocusSinceLongBreakArgs {
+            long_break_duration: Duration::from_std(self.config.long_break_duration)?,
+        };
+        let completed = self.querier.count_focus_since_long_break(&params)?;
+        Ok(completed > 0 && completed % u64::from(interval) == 0)
     }
 
     /// Retrieve an existing [`Session`] by its UUID.
@@ -101,8 +389,23 @@ impl<'q> StartCommand<'q> {
         Ok(session)
     }
 
-    /// Persist a new [`Session`] and return the stored record.
+    /// Insert a new session, refusing kinds missing from
+    /// [`ProgramConfig::allowed_modes`].
     fn insert_session(&self, session: &Session) -> Result<Session> {
+        let allowed = &self.config.allowed_modes;
+        anyhow::ensure!(
+            allowed
+                .iter()
+                .any(|&m| SessionKind::from(m) == session.kind),
+            "Starting a {} session is not allowed (allowed_modes: {})",
+            session.kind,
+            allowed
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
         let params = InsertSessionArgs { session };
         let session = self.querier.insert_session(&params)?;
         Ok(session)
@@ -119,61 +422,104 @@ pub struct StopCommand<'q> {
 }
 
 impl<'q> StopCommand<'q> {
-    /// Execute the StopCommand with the provided arguments.
-    pub fn execute(&self, args: &StopCommandArgs) -> Result<()> {
-        let params = ListSessionEventsArgs::first();
-        let result = self.querier.list_session_events(&params)?;
+    /// Pause, abort, or complete the current session and return what happened.
+    pub fn execute(&self, args: &StopCommandArgs) -> Result<CommandOutcome> {
+        self.execute_at(args, chrono::Utc::now())
+    }
+
+    /// Like [`StopCommand::execute`], with the event recorded and the elapsed
+    /// time measured at `now`.
+    pub fn execute_at(
+        &self,
+        args: &StopCommandArgs,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<CommandOutcome> {
+        let current = current_session_event(&self.querier)?;
 
-        let mut session: Session = Session::default();
-        let session_event = match result.first() {
+        let mut session: Option<Session> = None;
+        let message;
+        let session_event = match current.as_ref() {
             Some(session_event) => match session_event.kind {
                 SessionEventKind::Started | SessionEventKind::Resumed => {
-                    session = self.get_session(&session_event.session_id)?;
-                    if args.reset {
-                        println!("Aborted the {} session.", session.kind);
-                        Some(SessionEvent::aborted(session.id))
+                    let current = self.get_session(&session_event.session_id)?;
+                    let session_event = if args.complete {
+                        message = format!("Completed the {} session.", current.kind);
+                        Some(SessionEvent::completed_manually(current.id))
+                    } else if args.reset {
+                        message = format!("Aborted the {} session.", current.kind);
+                        Some(SessionEvent::aborted(current.id))
                     } else {
-                        println!("Paused the {} session.", session.kind);
-                        Some(SessionEvent::paused(session.id))
-                    }
+                        message = format!("Paused the {} session.", current.kind);
+                        Some(SessionEvent::paused(current.id))
+                    };
+                    session = Some(current);
+                    session_event
                 }
                 SessionEventKind::Paused => {
-                    session = self.get_session(&session_event.session_id)?;
-                    if args.reset {
-                        println!("Aborted the {} session.", session.kind);
-                        Some(SessionEvent::aborted(session.id))
+                    let current = self.get_session(&session_event.session_id)?;
+                    let session_event = if args.complete {
+                        message = format!("Completed the {} session.", current.kind);
+                        Some(SessionEvent::completed_manually(current.id))
+                    } else if args.reset {
+                        message = format!("Aborted the {} session.", current.kind);
+                        Some(SessionEvent::aborted(current.id))
                     } else {
-                        println!("The {} session is already paused.", session.kind);
+                        message = format!("The {} session is already paused.", current.kind);
                         None
-                    }
+                    };
+                    session = Some(current);
+                    session_event
                 }
                 SessionEventKind::Aborted | SessionEventKind::Completed => {
-                    println!("No active session found.");
+                    // Another invocation (e.g. `status` auto-completing) may have
+                    // ended the session just before this one took the lock: report
+                    // that instead of failing.
+                    anyhow::ensure!(
+                        !args.complete || session_event.kind == SessionEventKind::Completed,
+                        "No active session to complete."
+                    );
+                    let ended = self.get_session(&session_event.session_id)?;
+                    message = ended_message(&ended, &session_event.kind);
+                    session = Some(ended);
                     None
                 }
+                SessionEventKind::Interrupted => unreachable!("interruptions are not a state"),
             },
             None => {
-                println!("No active session found.");
+                anyhow::ensure!(!args.complete, "No active session to complete.");
+                message = "No active session found.".to_string();
                 None
             }
         };
 
-        if let Some(session_event) = session_event.as_ref() {
+        let session_event = session_event.map(|session_event| SessionEvent {
+            created_at: now,
+            ..session_event
+        });
+        let mut hooks_fired = Vec::new();
+        if let (Some(session), Some(session_event)) = (&session, &session_event) {
             let params = InsertSessionEventArgs { session_event };
             self.querier.insert_session_event(&params)?;
 
-            if let Some(runner) = &self.runner {
-                let args = SessionEventArgs {
-                    session: session.clone(),
-                    session_event: session_event.clone(),
-                };
-                // Hooks are non-fatal — ignore errors
-                // so a broken hook never kills the session.
-                runner.execute(&args).ok();
-            }
+            hooks_fired = notify(&self.runner, session, session_event)?;
         }
 
-        Ok(())
+        let action = match session_event.as_ref().map(|e| &e.kind) {
+            Some(SessionEventKind::Aborted) => CommandAction::Aborted,
+            Some(SessionEventKind::Completed) => CommandAction::Completed,
+            Some(_) => CommandAction::Paused,
+            None => CommandAction::None,
+        };
+        let outcome = CommandOutcome {
+            hooks_fired,
+            ..CommandOutcome::new(action, message)
+        };
+        match &session {
+            Some(session) => {
+                outcome.with_session_at(&self.querier, session, session_event.as_ref(), now)
+            }
+            None => Ok(outcome),
+        }
     }
 
     /// Retrieve an existing [`Session`] by its UUID.
@@ -184,199 +530,2427 @@ impl<'q> StopCommand<'q> {
     }
 }
 
-/// The lifecycle state of the most recent session.
-#[derive(Default, serde::Serialize)]
-#[serde(rename_all = "lowercase")]
-pub enum SessionState {
-    /// No session exists yet.
-    #[default]
-    None,
-    /// The session is actively counting down.
-    Running,
-    /// The session has been paused by the user.
-    Paused,
-    /// The session reached its planned duration.
-    Completed,
-    /// The session was cancelled before finishing.
-    Aborted,
+/// PauseCommand pauses the current session. Unlike `stop`, it never aborts or
+/// completes a session, and it is a no-op unless the session is running.
+pub struct PauseCommand<'q> {
+    /// Runner is used to execute the hooks.
+    pub runner: Option<Runner>,
+    /// Querier is used to retrieve the current status of the pomodoro timer from the database.
+    pub querier: Querier<'q>,
 }
 
-impl From<&SessionEventKind> for SessionState {
-    fn from(kind: &SessionEventKind) -> Self {
-        match kind {
-            SessionEventKind::Started | SessionEventKind::Resumed => Self::Running,
-            SessionEventKind::Paused => Self::Paused,
-            SessionEventKind::Completed => Self::Completed,
-            SessionEventKind::Aborted => Self::Aborted,
+impl<'q> PauseCommand<'q> {
+    /// Pause the running session, if any, and return what happened.
+    pub fn execute(&self) -> Result<CommandOutcome> {
+        let Some(current) = current_session_event(&self.querier)? else {
+            let message = "No session to pause.".to_string();
+            return Ok(CommandOutcome::new(CommandAction::None, message));
+        };
+
+        let params = GetSessionByIdArgs {
+            session_id: &current.session_id,
+        };
+        let session = self.querier.get_session_by_id(&params)?;
+
+        let message = match current.kind {
+            SessionEventKind::Started | SessionEventKind::Resumed => None,
+            SessionEventKind::Paused => {
+                Some(format!("The {} session is already paused.", session.kind))
+            }
+            SessionEventKind::Aborted | SessionEventKind::Completed => {
+                Some(ended_message(&session, &current.kind))
+            }
+            SessionEventKind::Interrupted => unreachable!("interruptions are not a state"),
+        };
+        if let Some(message) = message {
+            let outcome = CommandOutcome::new(CommandAction::None, message);
+            return outcome.with_session(&self.querier, &session, None);
         }
+
+        let session_event = &SessionEvent::paused(session.id);
+        let params = InsertSessionEventArgs { session_event };
+        self.querier.insert_session_event(&params)?;
+
+        let hooks_fired = notify(&self.runner, &session, session_event)?;
+
+        let message = format!("Paused the {} session.", session.kind);
+        let outcome = CommandOutcome {
+            hooks_fired,
+            ..CommandOutcome::new(CommandAction::Paused, message)
+        };
+        outcome.with_session(&self.querier, &session, Some(session_event))
     }
 }
 
-/// SessionStatus holds the computed fields for the current session, used as the
-/// data model for both JSON and text output of the `status` command.
-#[derive(serde::Serialize)]
-pub struct SessionStatus {
-    /// The session kind: `"focus"`, `"break"`, or `"none"`.
-    pub kind: String,
-    /// The lifecycle state of the session.
-    pub state: SessionState,
-    /// Planned duration of the session in seconds.
-    pub planned_secs: i64,
-    /// Total elapsed time in seconds.
-    pub elapsed_secs: i64,
-    /// Remaining time in seconds (clamped to zero).
-    pub remaining_secs: i64,
+/// ResumeCommand resumes the current session. Unlike `start`, it never starts
+/// a new session, and it is a no-op unless the session is paused.
+pub struct ResumeCommand<'q> {
+    /// Runner is used to execute the hooks.
+    pub runner: Option<Runner>,
+    /// Querier is used to retrieve the current status of the pomodoro timer from the database.
+    pub querier: Querier<'q>,
 }
 
-impl Default for SessionStatus {
-    fn default() -> Self {
-        Self {
-            kind: "none".to_string(),
-            state: Default::default(),
-            planned_secs: Default::default(),
-            elapsed_secs: Default::default(),
-            remaining_secs: Default::default(),
+impl<'q> ResumeCommand<'q> {
+    /// Resume the paused session, if any, and return what happened.
+    pub fn execute(&self) -> Result<CommandOutcome> {
+        let Some(current) = current_session_event(&self.querier)? else {
+            let message = "No session to resume.".to_string();
+            return Ok(CommandOutcome::new(CommandAction::None, message));
+        };
+
+        let params = GetSessionByIdArgs {
+            session_id: &current.session_id,
+        };
+        let session = self.querier.get_session_by_id(&params)?;
+
+        let message = match current.kind {
+            SessionEventKind::Paused => None,
+            SessionEventKind::Started | SessionEventKind::Resumed => {
+                Some(format!("The {} session is already running.", session.kind))
+            }
+            SessionEventKind::Aborted | SessionEventKind::Completed => {
+                Some(ended_message(&session, &current.kind))
+            }
+            SessionEventKind::Interrupted => unreachable!("interruptions are not a state"),
+        };
+        if let Some(message) = message {
+            let outcome = CommandOutcome::new(CommandAction::None, message);
+            return outcome.with_session(&self.querier, &session, None);
         }
+
+        let session_event = &SessionEvent::resumed(session.id);
+        let params = InsertSessionEventArgs { session_event };
+        self.querier.insert_session_event(&params)?;
+
+        let hooks_fired = notify(&self.runner, &session, session_event)?;
+
+        let message = format!("Resumed the {} session.", session.kind);
+        let outcome = CommandOutcome {
+            hooks_fired,
+            ..CommandOutcome::new(CommandAction::Resumed, message)
+        };
+        outcome.with_session(&self.querier, &session, Some(session_event))
     }
 }
-/// StatusCommand computes and displays the current status of the most recent
-/// pomodoro session. It calculates elapsed and remaining time by replaying the
-/// session event log, auto-inserts a [`SessionEventKind::Completed`] event when
-/// a running session has no time left, and renders the result via
-/// [`StatusCommand::render`].
-pub struct StatusCommand<'q> {
+
+/// CancelCommand aborts the current session, like `stop --reset` does. Unlike
+/// `stop`, it never pauses or completes a session.
+pub struct CancelCommand<'q> {
     /// Runner is used to execute the hooks.
     pub runner: Option<Runner>,
     /// Querier is used to retrieve the current status of the pomodoro timer from the database.
     pub querier: Querier<'q>,
 }
 
-impl<'q> StatusCommand<'q> {
-    /// Compute the current [`SessionStatus`] and render it to stdout.
+impl<'q> CancelCommand<'q> {
+    /// Abort the running or paused session, if any, and return what happened.
+    pub fn execute(&self) -> Result<CommandOutcome> {
+        let Some(current) = current_session_event(&self.querier)? else {
+            let message = "No session to cancel.".to_string();
+            return Ok(CommandOutcome::new(CommandAction::None, message));
+        };
+
+        let params = GetSessionByIdArgs {
+            session_id: &current.session_id,
+        };
+        let session = self.querier.get_session_by_id(&params)?;
+        if let SessionEventKind::Aborted | SessionEventKind::Completed = current.kind {
+            let message = ended_message(&session, &current.kind);
+            let outcome = CommandOutcome::new(CommandAction::None, message);
+            return outcome.with_session(&self.querier, &session, None);
+        }
+
+        let session_event = &SessionEvent::aborted(session.id);
+        let params = InsertSessionEventArgs { session_event };
+        self.querier.insert_session_event(&params)?;
+
+        let hooks_fired = notify(&self.runner, &session, session_event)?;
+
+        let message = format!("Cancelled the {} session.", session.kind);
+        let outcome = CommandOutcome {
+            hooks_fired,
+            ..CommandOutcome::new(CommandAction::Aborted, message)
+        };
+        outcome.with_session(&self.querier, &session, Some(session_event))
+    }
+}
+
+/// ExtendCommand adds time to the current session. Only the planned duration
+/// changes: the events, and thus the time already spent, are kept as they are.
+pub struct ExtendCommand<'q> {
+    /// Querier is used to retrieve and update the current session.
+    pub querier: Querier<'q>,
+}
+
+impl<'q> ExtendCommand<'q> {
+    /// Extend the running or paused session by `args.duration` and return what happened.
     ///
-    /// 1. Fetches the most recent session and its full event log.
-    /// 2. Replays events in chronological order to accumulate elapsed time.
-    /// 3. Derives the current [`SessionState`] from the most recent event.
-    /// 4. Auto-completes the session (inserts a `Completed` event) when the
-    ///    session is still `Running` but has no remaining time.
-    /// 5. Delegates formatting to [`StatusCommand::render`].
-    pub fn execute(&self, args: &StatusCommandArgs) -> Result<()> {
-        let params = &ListSessionsArgs::first();
-        let result = self.querier.list_sessions(params)?;
+    /// Fails when there is no session or it already ended, since there is
+    /// nothing left to extend, and when the session would last longer than
+    /// [`MAX_SESSION_DURATION`]. The new planned duration is stored as
+    /// `planned_human` too, formatted with humantime.
+    pub fn execute(&self, args: &ExtendCommandArgs) -> Result<CommandOutcome> {
+        let extension = args.duration.duration;
+        anyhow::ensure!(
+            extension.as_secs() > 0,
+            "A session can only be extended by 1s or more."
+        );
 
-        match result.first() {
-            Some(session) => {
-                let params = &ListSessionEventsArgs::with_session_id(session.id);
-                let result = self.querier.list_session_events(params)?;
-
-                let mut session_started_at = None;
-                let mut session_elapsed_time = Duration::zero();
-
-                for session_event in result.iter().rev() {
-                    let kind = &session_event.kind;
-                    // Find the start and end of each range
-                    if matches!(kind, SessionEventKind::Started | SessionEventKind::Resumed) {
-                        session_started_at = Some(session_event.created_at);
-                    } else if let Some(since_start) = session_started_at.take() {
-                        session_elapsed_time += session_event.created_at - since_start;
-                    }
-                }
+        let current = current_session_event(&self.querier)?.context("No session to extend.")?;
+        let params = GetSessionByIdArgs {
+            session_id: &current.session_id,
+        };
+        let mut session = self.querier.get_session_by_id(&params)?;
+        if let SessionEventKind::Aborted | SessionEventKind::Completed = current.kind {
+            anyhow::bail!("{}", ended_message(&session, &current.kind));
+        }
 
-                if let Some(since_start) = session_started_at {
-                    session_elapsed_time += Utc::now() - since_start;
-                }
+        let planned = session.planned_duration.to_std()? + extension;
+        anyhow::ensure!(
+            planned <= MAX_SESSION_DURATION,
+            "A session can last at most {}h.",
+            MAX_SESSION_DURATION.as_secs() / 3600
+        );
+        // Whole seconds only, as stored in planned_secs.
+        let planned = std::time::Duration::from_secs(planned.as_secs());
+        session.planned_duration = Duration::from_std(planned)?;
+        session.planned_human = Some(humantime::format_duration(planned).to_string());
+        let params = UpdateSessionPlannedDurationArgs { session: &session };
+        self.querier.update_session_planned_duration(&params)?;
 
-                // prepare the session kind
-                let session_kind = session.kind.to_string();
+        let outcome = CommandOutcome::new(CommandAction::Extended, String::new());
+        let outcome = outcome.with_session(&self.querier, &session, None)?;
+        let remaining = std::time::Duration::from_secs(outcome.remaining_secs as u64);
+        let message = format!(
+            "Extended the {} session by {}; {} left.",
+            session.kind,
+            args.duration.text,
+            humantime::format_duration(remaining)
+        );
+        Ok(CommandOutcome { message, ..outcome })
+    }
+}
 
-                // Determine the session state from the last event
-                let session_state = result
-                    .first()
-                    .map(|e| SessionState::from(&e.kind))
-                    .unwrap_or_default();
+/// InterruptCommand records an interruption of the current session. The
+/// session keeps running (or stays paused): the interruption is only counted,
+/// so that `status` and `stats` can tell how often focus was broken.
+pub struct InterruptCommand<'q> {
+    /// Runner is used to execute the hooks.
+    pub runner: Option<Runner>,
+    /// Querier is used to retrieve the current session and record the interruption.
+    pub querier: Querier<'q>,
+}
 
-                // Calculate the different duration types
-                let session_planned_secs = session.planned_duration.num_seconds();
-                let session_elapsed_secs = session_elapsed_time.num_seconds().max(0);
-                let session_remaining_secs = (session_planned_secs - session_elapsed_secs).max(0);
+impl<'q> InterruptCommand<'q> {
+    /// Record an interruption of the running or paused session, if any, and
+    /// return what happened.
+    pub fn execute(&self, args: &InterruptCommandArgs) -> Result<CommandOutcome> {
+        let Some(current) = current_session_event(&self.querier)? else {
+            let message = "No session to interrupt.".to_string();
+            return Ok(CommandOutcome::new(CommandAction::None, message));
+        };
 
-                // Build the session status
-                let mut session_status = SessionStatus {
-                    kind: session_kind,
-                    state: session_state,
-                    planned_secs: session_planned_secs,
-                    elapsed_secs: session_elapsed_secs,
-                    remaining_secs: session_remaining_secs,
-                };
+        let params = GetSessionByIdArgs {
+            session_id: &current.session_id,
+        };
+        let session = self.querier.get_session_by_id(&params)?;
+        if let SessionEventKind::Aborted | SessionEventKind::Completed = current.kind {
+            let message = ended_message(&session, &current.kind);
+            let outcome = CommandOutcome::new(CommandAction::None, message);
+            return outcome.with_session(&self.querier, &session, None);
+        }
 
-                if matches!(session_status.state, SessionState::Running)
-                // Complete the session if needed
-                    && session_remaining_secs == 0
-                {
-                    let session_event = &SessionEvent::completed(session.id);
-                    let params = InsertSessionEventArgs { session_event };
-                    self.querier.insert_session_event(&params)?;
-                    // Determine the session state from the last event
-                    session_status.state = SessionState::from(&session_event.kind);
+        let source = if args.external {
+            InterruptionSource::External
+        } else {
+            InterruptionSource::Internal
+        };
+        let session_event = &SessionEvent::interrupted(session.id, source, args.note.clone());
+        let params = InsertSessionEventArgs { session_event };
+        self.querier.insert_session_event(&params)?;
 
-                    if let Some(runner) = &self.runner {
-                        let args = SessionEventArgs {
-                            session: session.clone(),
-                            session_event: session_event.clone(),
-                        };
-                        // Hooks are non-fatal — ignore errors
-                        // so a broken hook never kills the session.
-                        runner.execute(&args).ok();
-                    }
-                }
+        let hooks_fired = notify(&self.runner, &session, session_event)?;
 
-                self.render(&session_status, args)?;
-            }
-            None => {
-                let status = SessionStatus::default();
-                self.render(&status, args)?;
-            }
+        let message = format!(
+            "Recorded an {} interruption of the {} session.",
+            source, session.kind
+        );
+        let outcome = CommandOutcome {
+            hooks_fired,
+            ..CommandOutcome::new(CommandAction::Interrupted, message)
         };
+        outcome.with_session(&self.querier, &session, Some(session_event))
+    }
+}
 
-        Ok(())
+/// Converts a CLI [`LogEventKind`] into the equivalent [`SessionEventKind`].
+impl From<LogEventKind> for SessionEventKind {
+    fn from(value: LogEventKind) -> Self {
+        match value {
+            LogEventKind::Started => SessionEventKind::Started,
+            LogEventKind::Resumed => SessionEventKind::Resumed,
+            LogEventKind::Paused => SessionEventKind::Paused,
+            LogEventKind::Aborted => SessionEventKind::Aborted,
+            LogEventKind::Completed => SessionEventKind::Completed,
+        }
     }
+}
 
-    /// Render `status` to stdout according to `args.output`.
-    ///
-    /// - `--output json`: pretty-printed JSON via `serde_json`.
-    /// - `--output text`: MiniJinja template from `--format`, or [`DEFAULT_TEXT_TEMPLATE`].
-    fn render(&self, status: &SessionStatus, args: &StatusCommandArgs) -> Result<()> {
-        match args.output {
-            StatusOutput::Json => {
-                println!("{}", serde_json::to_string_pretty(status)?);
+/// LogCommand appends an arbitrary event to the most recent session, firing
+/// the matching hook. Unlike `start` and `stop` it does not pick the event for
+/// the user, but it still refuses transitions the session state machine does
+/// not allow (see [`SessionEventKind::can_follow`]).
+pub struct LogCommand<'q> {
+    /// Runner is used to execute the hooks.
+    pub runner: Option<Runner>,
+    /// Querier is used to retrieve the current status of the pomodoro timer from the database.
+    pub querier: Querier<'q>,
+}
+
+impl<'q> LogCommand<'q> {
+    /// Append the event and return what happened.
+    pub fn execute(&self, args: &LogCommandArgs) -> Result<CommandOutcome> {
+        // Log for the session that start, stop, and status act on.
+        let current = current_session_event(&self.querier)?;
+        let session = &match &current {
+            Some(session_event) => {
+                let session_id = &session_event.session_id;
+                self.querier
+                    .get_session_by_id(&GetSessionByIdArgs { session_id })?
             }
-            StatusOutput::Text => {
-                let template = args.format.as_deref().unwrap_or(DEFAULT_TEXT_TEMPLATE);
-                let output = Environment::new().render_str(template, status)?;
-                println!("{}", output);
+            None => self
+                .querier
+                .list_sessions(&ListSessionsArgs::first())?
+                .pop()
+                .context("No session to log an event for")?,
+        };
+        let previous = current.as_ref().map(|session_event| &session_event.kind);
+
+        let kind = SessionEventKind::from(args.kind);
+        if !kind.can_follow(previous) {
+            match previous {
+                Some(previous) => anyhow::bail!("Cannot log a {kind} event after {previous}"),
+                None => anyhow::bail!("Cannot log a {kind} event for a session without events"),
             }
         }
-        Ok(())
+
+        // A logged completion is the user's call, not the timer's.
+        let source = (kind == SessionEventKind::Completed).then_some(CompletionSource::Manual);
+        let session_event = &SessionEvent {
+            kind,
+            source,
+            ..SessionEvent::started(session.id)
+        };
+        let params = InsertSessionEventArgs { session_event };
+        self.querier.insert_session_event(&params)?;
+
+        let hooks_fired = notify(&self.runner, session, session_event)?;
+
+        let action = match session_event.kind {
+            SessionEventKind::Started => CommandAction::Started,
+            SessionEventKind::Resumed => CommandAction::Resumed,
+            SessionEventKind::Paused => CommandAction::Paused,
+            SessionEventKind::Aborted => CommandAction::Aborted,
+            SessionEventKind::Completed => CommandAction::Completed,
+            SessionEventKind::Interrupted => CommandAction::Interrupted,
+        };
+        let message = format!(
+            "Logged a {} event for the {} session.",
+            session_event.kind, session.kind
+        );
+        let outcome = CommandOutcome {
+            hooks_fired,
+            ..CommandOutcome::new(action, message)
+        };
+        outcome.with_session(&self.querier, session, Some(session_event))
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use anyhow::Result;
+/// SuspendCommand pauses the running session on behalf of the system (e.g. from a
+/// sleep hook) and remembers that the pause was caused by a suspend, so that
+/// [`ResumeFromSuspendCommand`] never resumes a session the user paused manually.
+pub struct SuspendCommand<'q> {
+    /// Runner is used to execute the hooks.
+    pub runner: Option<Runner>,
+    /// Querier is used to retrieve the current status of the pomodoro timer from the database.
+    pub querier: Querier<'q>,
+}
 
-    /// Open an in-memory database, apply the schema, and return it.
-    ///
-    /// Used by every test in this module as the starting point for a clean,
-    /// isolated database that is discarded when the test completes.
-    fn setup() -> Result<Database> {
-        let db = Database::open_in_memory()?;
-        db.migrate()?;
-        Ok(db)
-    }
+impl<'q> SuspendCommand<'q> {
+    /// Pause the running session and return what happened.
+    pub fn execute(&self) -> Result<CommandOutcome> {
+        let session_event = match current_session_event(&self.querier)? {
+            Some(session_event)
+                if matches!(
+                    session_event.kind,
+                    SessionEventKind::Started | SessionEventKind::Resumed
+                ) =>
+            {
+                session_event
+            }
+            _ => {
+                let message = "No running session to suspend.".to_string();
+                return Ok(CommandOutcome::new(CommandAction::None, message));
+            }
+        };
 
-    /// Insert a session and the events returned by `f` into the DB.
-    ///
+        let params = GetSessionByIdArgs {
+            session_id: &session_event.session_id,
+        };
+        let session = self.querier.get_session_by_id(&params)?;
+
+        let session_event = &SessionEvent::paused(session.id);
+        let params = InsertSessionEventArgs { session_event };
+        self.querier.insert_session_event(&params)?;
+
+        // Remember which pause was ours so that a manual pause is never resumed on wake.
+        let value = &session_event.id.to_string();
+        let params = SetMetaArgs {
+            key: MetaKey::SuspendedEventId,
+            value,
+        };
+        self.querier.set_meta(&params)?;
+
+        let hooks_fired = notify(&self.runner, &session, session_event)?;
+
+        let message = format!("Suspended the {} session.", session.kind);
+        let outcome = CommandOutcome {
+            hooks_fired,
+            ..CommandOutcome::new(CommandAction::Paused, message)
+        };
+        outcome.with_session(&self.querier, &session, Some(session_event))
+    }
+}
+
+/// ResumeFromSuspendCommand resumes the session paused by [`SuspendCommand`]. It
+/// is a no-op when the most recent event is anything other than that pause.
+pub struct ResumeFromSuspendCommand<'q> {
+    /// Runner is used to execute the hooks.
+    pub runner: Option<Runner>,
+    /// Querier is used to retrieve the current status of the pomodoro timer from the database.
+    pub querier: Querier<'q>,
+}
+
+impl<'q> ResumeFromSuspendCommand<'q> {
+    /// Resume the suspended session, if any, and return what happened.
+    pub fn execute(&self) -> Result<CommandOutcome> {
+        let params = GetMetaArgs {
+            key: MetaKey::SuspendedEventId,
+        };
+        let suspended_event_id = self.querier.get_meta(&params)?;
+
+        // The marker is consumed on every wake-up, whether or not it still applies.
+        let params = DeleteMetaArgs {
+            key: MetaKey::SuspendedEventId,
+        };
+        self.querier.delete_meta(&params)?;
+
+        let session_event = match (current_session_event(&self.querier)?, suspended_event_id) {
+            (Some(session_event), Some(suspended_event_id))
+                if session_event.kind == SessionEventKind::Paused
+                    && session_event.id.to_string() == suspended_event_id =>
+            {
+                session_event
+            }
+            _ => {
+                let message = "No suspended session found.".to_string();
+                return Ok(CommandOutcome::new(CommandAction::None, message));
+            }
+        };
+
+        let params = GetSessionByIdArgs {
+            session_id: &session_event.session_id,
+        };
+        let session = self.querier.get_session_by_id(&params)?;
+
+        let session_event = &SessionEvent::resumed(session.id);
+        let params = InsertSessionEventArgs { session_event };
+        self.querier.insert_session_event(&params)?;
+
+        let hooks_fired = notify(&self.runner, &session, session_event)?;
+
+        let message = format!("Resumed the {} session after suspend.", session.kind);
+        let outcome = CommandOutcome {
+            hooks_fired,
+            ..CommandOutcome::new(CommandAction::Resumed, message)
+        };
+        outcome.with_session(&self.querier, &session, Some(session_event))
+    }
+}
+
+/// The transition performed by a state-changing command.
+#[derive(Default, Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandAction {
+    /// Nothing changed, e.g. the session was already running or already paused.
+    #[default]
+    None,
+    /// A new session was started.
+    Started,
+    /// The paused session was resumed.
+    Resumed,
+    /// The running session was paused.
+    Paused,
+    /// The session was aborted via `--reset`.
+    Aborted,
+    /// The session was marked as completed via `--complete`.
+    Completed,
+    /// The running or paused session was given more time via `extend`.
+    Extended,
+    /// An interruption of the running or paused session was recorded via `interrupt`.
+    Interrupted,
+}
+
+/// CommandOutcome describes what a state-changing command did. It is returned
+/// by every command's `execute` and used as the data model for both its JSON
+/// and text output, which are printed by the caller.
+#[derive(Debug, serde::Serialize)]
+pub struct CommandOutcome {
+    /// The transition that was performed, if any.
+    pub action: CommandAction,
+    /// Human-readable confirmation message, printed when no `--format` is given.
+    pub message: String,
+    /// The session kind: `"focus"`, `"break"`, or `"none"`.
+    pub kind: String,
+    /// ID of the affected session, if there was one.
+    pub session_id: Option<Uuid>,
+    /// ID of the event recorded by the command, if any.
+    pub session_event_id: Option<Uuid>,
+    /// Total elapsed time in seconds once the command has run.
+    pub elapsed_secs: i64,
+    /// Remaining time in seconds (clamped to zero).
+    pub remaining_secs: i64,
+    /// When the session runs out of time, if the command started or resumed it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ends_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Non-fatal problems with the invocation, e.g. a flag that was ignored.
+    pub warnings: Vec<String>,
+    /// Names of the hooks that ran, relative to the hooks directory (e.g.
+    /// `start`); empty with `--no-hooks` or when no hook is installed.
+    pub hooks_fired: Vec<String>,
+}
+
+impl CommandOutcome {
+    /// Create an outcome that is not tied to any session.
+    pub fn new(action: CommandAction, message: String) -> Self {
+        Self {
+            action,
+            message,
+            kind: "none".to_string(),
+            session_id: None,
+            session_event_id: None,
+            elapsed_secs: 0,
+            remaining_secs: 0,
+            ends_at: None,
+            warnings: Vec::new(),
+            hooks_fired: Vec::new(),
+        }
+    }
+
+    /// Fail with the outcome message when `strict` is set and nothing changed
+    /// (see `--strict`), so that a script can tell a no-op from a state change.
+    pub fn strict(self, strict: bool) -> Result<Self> {
+        anyhow::ensure!(
+            !strict || self.action != CommandAction::None,
+            "{}",
+            self.message
+        );
+        Ok(self)
+    }
+
+    /// Attach `session` and the recorded `session_event` to the outcome.
+    ///
+    /// Elapsed and remaining time are computed by replaying the session's
+    /// event log after the new event is recorded, so they reflect the exact
+    /// moment the command ran.
+    pub fn with_session(
+        self,
+        querier: &Querier,
+        session: &Session,
+        session_event: Option<&SessionEvent>,
+    ) -> Result<Self> {
+        self.with_session_at(querier, session, session_event, chrono::Utc::now())
+    }
+
+    /// Like [`CommandOutcome::with_session`], with the time measured up to
+    /// `now`.
+    pub fn with_session_at(
+        mut self,
+        querier: &Querier,
+        session: &Session,
+        session_event: Option<&SessionEvent>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Self> {
+        let params = &SessionSummaryArgs {
+            session_id: &session.id,
+        };
+        let summary = querier.session_summary_at(params, now)?;
+        let planned_secs = session.planned_duration.num_seconds();
+        let elapsed_secs = summary.elapsed.num_seconds().max(0);
+
+        self.kind = session.kind.to_string();
+        self.session_id = Some(session.id);
+        self.session_event_id = session_event.map(|e| e.id);
+        self.elapsed_secs = elapsed_secs;
+        self.remaining_secs = (planned_secs - elapsed_secs).max(0);
+        // A session (re)started by this command runs out once the remaining
+        // time has passed, counted from the event.
+        self.ends_at = session_event
+            .filter(|e| {
+                matches!(
+                    e.kind,
+                    SessionEventKind::Started | SessionEventKind::Resumed
+                )
+            })
+            .and_then(|e| {
+                e.created_at
+                    .checked_add_signed(Duration::seconds(self.remaining_secs))
+            });
+        Ok(self)
+    }
+
+    /// Return [`CommandOutcome::ends_at`] as an RFC 3339 timestamp in whole
+    /// seconds (e.g. `2024-01-01T10:25:00Z`), for `start --print-ends-at`.
+    pub fn ends_at_rfc3339(&self) -> Option<String> {
+        let ends_at = self.ends_at?;
+        Some(ends_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+    }
+
+    /// Render the outcome according to `output` and `format`.
+    ///
+    /// - `--output json`: pretty-printed JSON via `serde_json`.
+    /// - `--output text`: MiniJinja template from `--format`, or the message.
+    pub fn render(&self, output: CommandOutput, format: Option<&str>) -> Result<String> {
+        Renderer::new(output)
+            .with_template(format)
+            .render(self, |outcome| Ok(outcome.message.clone()))
+    }
+}
+
+/// The kind of the most recent session, or `none` when there is no session.
+///
+/// Session kinds serialize through the [`SessionKind`] serde representation,
+/// so a new kind shows up in the `status` output without changes here.
+#[derive(Default, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusKind {
+    /// No session exists yet.
+    #[default]
+    None,
+    /// The kind of the most recent session.
+    #[serde(untagged)]
+    Session(SessionKind),
+}
+
+impl std::fmt::Display for StatusKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Session(kind) => write!(f, "{}", kind),
+        }
+    }
+}
+
+/// The lifecycle state of the most recent session.
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionState {
+    /// No session exists yet.
+    #[default]
+    None,
+    /// The session is actively counting down.
+    Running,
+    /// The session has been paused by the user.
+    Paused,
+    /// The session reached its planned duration.
+    Completed,
+    /// The session was cancelled before finishing.
+    Aborted,
+    /// The timer is switched off via `POMODORO_DISABLED`.
+    Disabled,
+}
+
+impl From<&SessionEventKind> for SessionState {
+    fn from(kind: &SessionEventKind) -> Self {
+        match kind {
+            // An interruption never is a session's state (see
+            // `SessionEventKind::is_transition`), but it happens while working.
+            SessionEventKind::Started
+            | SessionEventKind::Resumed
+            | SessionEventKind::Interrupted => Self::Running,
+            SessionEventKind::Paused => Self::Paused,
+            SessionEventKind::Completed => Self::Completed,
+            SessionEventKind::Aborted => Self::Aborted,
+        }
+    }
+}
+
+impl std::fmt::Display for SessionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Running => write!(f, "running"),
+            Self::Paused => write!(f, "paused"),
+            Self::Completed => write!(f, "completed"),
+            Self::Aborted => write!(f, "aborted"),
+            Self::Disabled => write!(f, "disabled"),
+        }
+    }
+}
+
+/// SessionStatus holds the computed fields for the current session, used as the
+/// data model for both JSON and text output of the `status` command.
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+pub struct SessionStatus {
+    /// The session kind: `"focus"`, `"break"`, or `"none"`.
+    pub kind: StatusKind,
+    /// The lifecycle state of the session.
+    pub state: SessionState,
+    /// Planned duration of the session in seconds.
+    pub planned_secs: i64,
+    /// Total elapsed time in seconds.
+    pub elapsed_secs: i64,
+    /// Wall-clock time since the session started in seconds, pauses included;
+    /// measured up to the end of a session that ended.
+    pub wall_elapsed_secs: i64,
+    /// Remaining time in seconds (clamped to zero).
+    pub remaining_secs: i64,
+    /// Time run past the planned duration in seconds (clamped to zero).
+    pub overtime_secs: i64,
+    /// Share of the planned duration that elapsed, from 0 to 1 (clamped); 1
+    /// for a session without a planned duration, 0 without a session.
+    #[serde(default)]
+    pub progress: f64,
+    /// [`SessionStatus::progress`] as a whole percentage, rounded down.
+    #[serde(default)]
+    pub percent: i64,
+    /// Number of times the session was paused.
+    pub pause_count: u32,
+    /// Total time spent paused in seconds, including a pause that is still open.
+    pub paused_secs: i64,
+    /// Time since the session was paused in seconds while it is paused; 0 otherwise.
+    pub paused_for_secs: i64,
+    /// Number of interruptions recorded with `interrupt` during the session.
+    #[serde(default)]
+    pub interruption_count: u32,
+    /// Label the session was started with via `start --tag`, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Whether a focus session completed more than
+    /// [`ProgramConfig::break_reminder_after`] ago and no break was started since.
+    pub break_overdue: bool,
+    /// Whether the session ran for longer than [`ProgramConfig::stale_after`],
+    /// e.g. because it was forgotten.
+    pub stale: bool,
+    /// Number of sessions in the database; only reported with `--debug`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_sessions: Option<u64>,
+    /// Number of session events in the database; only reported with `--debug`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_events: Option<u64>,
+    /// When the session ended; kept to derive [`SessionStatus::break_overdue`]
+    /// from a cached status, but not reported.
+    #[serde(skip)]
+    pub ended_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl SessionStatus {
+    /// The status reported while the timer is switched off via `POMODORO_DISABLED`.
+    pub fn disabled() -> Self {
+        Self {
+            state: SessionState::Disabled,
+            ..Self::default()
+        }
+    }
+
+    /// Derive [`SessionStatus::progress`] and [`SessionStatus::percent`] from
+    /// the elapsed and planned time.
+    fn update_progress(&mut self) {
+        self.progress = if self.planned_secs > 0 {
+            (self.elapsed_secs as f64 / self.planned_secs as f64).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        self.percent = (self.progress * 100.0).floor() as i64;
+    }
+
+    /// Derive the flags that change as time passes, i.e.
+    /// [`SessionStatus::overtime_secs`], [`SessionStatus::stale`] and
+    /// [`SessionStatus::break_overdue`], as of `now`.
+    fn update_flags(&mut self, config: &ProgramConfig, now: chrono::DateTime<chrono::Utc>) {
+        self.overtime_secs = (self.elapsed_secs - self.planned_secs).max(0);
+
+        // Flag an active session that ran implausibly long, e.g. one left running overnight
+        let stale_after = config.stale_after.as_secs() as i64;
+        self.stale = stale_after > 0
+            && matches!(self.state, SessionState::Running | SessionState::Paused)
+            && self.elapsed_secs > stale_after;
+
+        // Remind to take a break after a focus session completed a while ago
+        let reminder = config.break_reminder_after;
+        self.break_overdue = !reminder.is_zero()
+            && self.kind == StatusKind::Session(SessionKind::Focus)
+            && matches!(self.state, SessionState::Completed)
+            && self
+                .ended_at
+                .is_some_and(|ended_at| (now - ended_at).to_std().is_ok_and(|d| d > reminder));
+    }
+}
+
+/// StatusCommand computes the current status of the most recent
+/// pomodoro session. It calculates elapsed and remaining time by replaying the
+/// session event log, auto-inserts a [`SessionEventKind::Completed`] event when
+/// a running session has no time left. The result is rendered via
+/// [`StatusCommand::render`].
+pub struct StatusCommand<'q> {
+    /// Runner is used to execute the hooks.
+    pub runner: Option<Runner>,
+    /// Querier is used to retrieve the current status of the pomodoro timer from the database.
+    pub querier: Querier<'q>,
+    /// Config provides the user settings that affect rendering (e.g. tmux colors).
+    pub config: ProgramConfig,
+}
+
+impl<'q> StatusCommand<'q> {
+    /// Compute the current [`SessionStatus`] and return it.
+    ///
+    /// 1. Fetches the session pinned with `focus-on`, or else the most recent session.
+    /// 2. Replays its event log via [`Querier::session_summary`] to accumulate elapsed time.
+    /// 3. Derives the current [`SessionState`] from the most recent event.
+    /// 4. Auto-completes the session (inserts a `Completed` event) when the
+    ///    session is still `Running` but has no remaining time, give or take
+    ///    [`ProgramConfig::auto_complete_tolerance`], unless
+    ///    [`ProgramConfig::auto_complete`] is off.
+    ///
+    /// All times are measured against the session's own planned duration; the
+    /// configured focus and break durations only apply when a session starts.
+    ///
+    /// Formatting is left to the caller via [`StatusCommand::render`].
+    pub fn execute(&self) -> Result<SessionStatus> {
+        let result = match pinned_session_event(&self.querier)? {
+            Some(session_event) => {
+                let session_id = &session_event.session_id;
+                let params = &GetSessionByIdArgs { session_id };
+                vec![self.querier.get_session_by_id(params)?]
+            }
+            None => self.querier.list_sessions(&ListSessionsArgs::first())?,
+        };
+
+        match result.first() {
+            Some(session) => {
+                let params = &SessionSummaryArgs {
+                    session_id: &session.id,
+                };
+                let summary = self.querier.session_summary(params)?;
+
+                // prepare the session kind
+                let session_kind = StatusKind::Session(session.kind.clone());
+
+                // Determine the session state from the last event
+                let session_state = summary
+                    .final_state
+                    .as_ref()
+                    .map(SessionState::from)
+                    .unwrap_or_default();
+
+                // Calculate the different duration types
+                let session_planned_secs = session.planned_duration.num_seconds();
+                let session_elapsed_secs = summary.elapsed.num_seconds().max(0);
+                let session_remaining_secs = (session_planned_secs - session_elapsed_secs).max(0);
+
+                // Build the session status
+                let mut session_status = SessionStatus {
+                    kind: session_kind,
+                    state: session_state,
+                    planned_secs: session_planned_secs,
+                    elapsed_secs: session_elapsed_secs,
+                    wall_elapsed_secs: summary.started_at.map_or(0, |started_at| {
+                        let until = summary.ended_at.unwrap_or_else(chrono::Utc::now);
+                        (until - started_at).num_seconds().max(0)
+                    }),
+                    remaining_secs: session_remaining_secs,
+                    pause_count: summary.num_pauses,
+                    paused_secs: summary.paused.num_seconds().max(0),
+                    paused_for_secs: summary.paused_since.map_or(0, |paused_at| {
+                        (chrono::Utc::now() - paused_at).num_seconds().max(0)
+                    }),
+                    interruption_count: summary.num_interruptions,
+                    tag: session.tag.clone(),
+                    ended_at: summary.ended_at,
+                    ..SessionStatus::default()
+                };
+                session_status.update_progress();
+
+                // Status bars rarely refresh exactly on the second the session
+                // ends, so anything within the configured tolerance counts as done.
+                let tolerance_secs = self.config.auto_complete_tolerance.as_secs() as i64;
+
+                if self.config.auto_complete
+                    && matches!(session_status.state, SessionState::Running)
+                // Complete the session if needed
+                    && session_remaining_secs <= tolerance_secs
+                {
+                    let session_event = &SessionEvent::completed(session.id);
+                    let params = InsertSessionEventArgs { session_event };
+                    self.querier.insert_session_event(&params)?;
+                    // Determine the session state from the last event
+                    session_status.state = SessionState::from(&session_event.kind);
+                    session_status.remaining_secs = 0;
+                    session_status.ended_at = Some(session_event.created_at);
+
+                    notify(&self.runner, session, session_event)?;
+
+                    if session.kind == SessionKind::Focus {
+                        self.start_auto_break()?;
+                    }
+                }
+
+                // Flags reflect the final state, e.g. a session completed above is not stale.
+                session_status.update_flags(&self.config, chrono::Utc::now());
+                Ok(session_status)
+            }
+            None => Ok(SessionStatus::default()),
+        }
+    }
+
+    /// Start a break after a focus session was just completed, when
+    /// [`ProgramConfig::auto_break`] is set and breaks are allowed.
+    ///
+    /// The break is started like `start --mode break` does, so it is a long
+    /// one when due and fires the `start` hook. The status still reports the
+    /// completed focus session; the break shows from the next `status` on.
+    /// Only a completion recorded by `status` chains a break, and a new break
+    /// never completes right away, so one call starts at most one session.
+    fn start_auto_break(&self) -> Result<()> {
+        if !self.config.auto_break || !self.config.allowed_modes.contains(&StartMode::Break) {
+            return Ok(());
+        }
+
+        let command = StartCommand {
+            runner: self.runner.clone(),
+            querier: self.querier.clone(),
+            config: self.config.clone(),
+        };
+        let args = StartCommandArgs {
+            mode: Some(StartMode::Break),
+            ..Default::default()
+        };
+        command.execute(&args)?;
+        Ok(())
+    }
+
+    /// Add the database totals reported by `status --debug` to `status`, to
+    /// confirm the database actually holds data when the status looks empty.
+    pub fn include_totals(&self, status: &mut SessionStatus) -> Result<()> {
+        let params = &CountSessionsArgs::default();
+        status.total_sessions = Some(self.querier.count_sessions(params)?);
+        let params = &CountSessionEventsArgs::default();
+        status.total_events = Some(self.querier.count_session_events(params)?);
+        Ok(())
+    }
+
+    /// Render `status` according to `args.output`.
+    ///
+    /// - `--json-path`: the raw value of a single field (see [`json_path`]).
+    /// - `--output json`: pretty-printed JSON via `serde_json`.
+    /// - `--output text`: MiniJinja template from `--format`, the configured
+    ///   one (see [`ProgramConfig::template_for`]), or [`DEFAULT_TEXT_TEMPLATE`].
+    /// - `--output tmux`: the template from `--format`, the configured one, or
+    ///   [`DEFAULT_TMUX_TEMPLATE`], wrapped in tmux style directives by [`render_tmux`].
+    ///
+    /// With `--set-tmux-option` the result is set as a tmux option instead (see [`set_tmux_status`]).
+    pub fn render(&self, status: &SessionStatus, args: &StatusCommandArgs) -> Result<String> {
+        render_status(status, args, &self.config)
+    }
+}
+
+/// Render `status` according to `args` (see [`StatusCommand::render`]).
+///
+/// Takes the config instead of a [`StatusCommand`], so that `status --cached`
+/// renders without opening the database.
+pub fn render_status(
+    status: &SessionStatus,
+    args: &StatusCommandArgs,
+    config: &ProgramConfig,
+) -> Result<String> {
+    let output = format_status(status, args, config)?;
+    if args.set_tmux_option {
+        return Ok(set_tmux_status(output));
+    }
+    Ok(output)
+}
+
+/// Set [`tmux::STATUS_OPTION`] to `output` for `status --set-tmux-option`, and
+/// return what is left to print: nothing once tmux holds the status.
+///
+/// Outside of tmux, or when tmux cannot be run, `output` is returned to be
+/// printed instead, with a warning on stderr: the status bar must keep showing
+/// something rather than the command failing.
+fn set_tmux_status(output: String) -> String {
+    match tmux::set_option(tmux::STATUS_OPTION, &output) {
+        Ok(true) => String::new(),
+        Ok(false) => {
+            eprintln!("warning: not running inside tmux; printing the status instead");
+            output
+        }
+        Err(error) => {
+            eprintln!("warning: {:#}; printing the status instead", error);
+            output
+        }
+    }
+}
+
+/// Format `status` according to `args`, ignoring `--set-tmux-option`.
+fn format_status(
+    status: &SessionStatus,
+    args: &StatusCommandArgs,
+    config: &ProgramConfig,
+) -> Result<String> {
+    if let Some(path) = args.json_path.as_deref() {
+        let value = serde_json::to_value(status)?;
+        let output = match json_path(&value, path)? {
+            serde_json::Value::String(value) => value.clone(),
+            value => value.to_string(),
+        };
+        return Ok(output);
+    }
+
+    let format = args.format.as_deref().map(load_template).transpose()?;
+    let output = match args.output {
+        StatusOutput::Json => CommandOutput::Json,
+        StatusOutput::Text => CommandOutput::Text,
+        StatusOutput::Tmux => {
+            let template = format
+                .as_deref()
+                .or(config.template_for(StatusOutput::Tmux))
+                .unwrap_or(DEFAULT_TMUX_TEMPLATE);
+            return render_tmux(status, &args.vars, template, &config.colors);
+        }
+    };
+    let template = format
+        .as_deref()
+        .or(config.template_for(StatusOutput::Text))
+        .unwrap_or(DEFAULT_TEXT_TEMPLATE);
+    Renderer::new(output).render(status, |status| {
+        render_template(template, &template_context(status, &args.vars)?)
+    })
+}
+
+/// Return the template `format` stands for: the contents of the file it names
+/// when it starts with `@` (e.g. `@~/.config/pomodoro/status.jinja`, see
+/// [`expand_path`]), or else `format` itself.
+///
+/// A missing file is an error, rather than `@path` being rendered as is.
+fn load_template(format: &str) -> Result<Cow<'_, str>> {
+    let Some(path) = format.strip_prefix('@') else {
+        return Ok(Cow::Borrowed(format));
+    };
+    let path = expand_path(path)?;
+    let template = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read the template file {}", path.display()))?;
+    Ok(Cow::Owned(template))
+}
+
+/// Expand a leading `~` to `$HOME`, and `$VAR` or `${VAR}` to the value of
+/// the environment variable, in `path`. An unset variable is an error.
+fn expand_path(path: &str) -> Result<PathBuf> {
+    static VARIABLE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\$(?:\{(\w+)\}|(\w+))").expect("Invalid regex"));
+
+    let path = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => format!("$HOME{rest}"),
+        _ => path.to_string(),
+    };
+    let mut expanded = String::new();
+    let mut last = 0;
+    for captures in VARIABLE.captures_iter(&path) {
+        let all = captures.get_match();
+        let name = captures
+            .get(1)
+            .or(captures.get(2))
+            .map_or("", |name| name.as_str());
+        let value = std::env::var(name)
+            .with_context(|| format!("Environment variable {name} is not set"))?;
+        expanded.push_str(&path[last..all.start()]);
+        expanded.push_str(&value);
+        last = all.end();
+    }
+    expanded.push_str(&path[last..]);
+    Ok(PathBuf::from(expanded))
+}
+
+/// Return the template context of `status`: its fields together with the
+/// `--var` variables in `vars`. A variable never replaces a status field.
+fn template_context(
+    status: &SessionStatus,
+    vars: &[(String, String)],
+) -> Result<serde_json::Value> {
+    let mut context = serde_json::to_value(status)?;
+    if let Some(fields) = context.as_object_mut() {
+        for (key, value) in vars {
+            fields
+                .entry(key.clone())
+                .or_insert_with(|| serde_json::Value::String(value.clone()));
+        }
+    }
+    Ok(context)
+}
+
+/// Render `status` as a single line, for the commands that print one per
+/// tick: JSON, or `template` (default [`DEFAULT_TEXT_TEMPLATE`]) for text.
+fn status_line(
+    status: &SessionStatus,
+    output: CommandOutput,
+    template: Option<&str>,
+) -> Result<String> {
+    match output {
+        CommandOutput::Json => serde_json::to_string(status).context("Failed to serialize status"),
+        CommandOutput::Text => {
+            let template = template.unwrap_or(DEFAULT_TEXT_TEMPLATE);
+            render_template(template, &serde_json::to_value(status)?)
+        }
+    }
+}
+
+/// StatusCache is the status written to a file after every state change when
+/// [`ProgramConfig::status_cache`] is enabled, so that `status --cached` can
+/// answer without opening the database.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct StatusCache {
+    /// The status right after the state change.
+    pub status: SessionStatus,
+    /// When the running session runs out of time; `None` unless it is running.
+    pub ends_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the paused session was paused; `None` unless it is paused.
+    #[serde(default)]
+    pub paused_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the session ended; `None` while it is running or paused.
+    #[serde(default)]
+    pub ended_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl StatusCache {
+    /// Return the path of the cache file, `$XDG_STATE_HOME/pomodoro/status.json`.
+    pub fn path() -> Result<PathBuf> {
+        xdg::BaseDirectories::with_prefix("pomodoro")
+            .place_state_file("status.json")
+            .context("Failed to determine status cache path")
+    }
+
+    /// Cache `status` as computed at `now`.
+    pub fn new(status: SessionStatus, now: chrono::DateTime<chrono::Utc>) -> Self {
+        let ends_at = matches!(status.state, SessionState::Running)
+            .then(|| now + Duration::seconds(status.remaining_secs));
+        let paused_at = matches!(status.state, SessionState::Paused)
+            .then(|| now - Duration::seconds(status.paused_for_secs));
+        let ended_at = status.ended_at;
+        Self {
+            status,
+            ends_at,
+            paused_at,
+            ended_at,
+        }
+    }
+
+    /// Compute the current status of the database behind `database` and
+    /// write it to `path`, replacing the previous cache.
+    ///
+    /// The status is computed in a read-only transaction that is rolled back,
+    /// so that refreshing the cache never records an event by itself, nor
+    /// holds up a writer.
+    pub fn refresh(database: &mut Database, config: &ProgramConfig, path: &Path) -> Result<()> {
+        let tx = database.transaction_with(TransactionBehavior::Deferred)?;
+        let command = StatusCommand {
+            runner: None,
+            querier: Querier::new(&tx),
+            config: config.clone(),
+        };
+        let cache = Self::new(command.execute()?, chrono::Utc::now());
+        drop(tx);
+
+        // Write to a temporary file first, so that a reader never sees half a cache.
+        let data = serde_json::to_vec(&cache).context("Failed to serialize status cache")?;
+        let temp = path.with_extension("json.tmp");
+        std::fs::write(&temp, data).context("Failed to write status cache")?;
+        std::fs::rename(&temp, path).context("Failed to write status cache")
+    }
+
+    /// Read the cache written by [`StatusCache::refresh`] from `path`.
+    pub fn read(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path).context("Failed to read status cache")?;
+        serde_json::from_slice(&data).context("Failed to parse status cache")
+    }
+
+    /// Return the cached status as of `now`, with the remaining and elapsed
+    /// time of a running session derived from [`StatusCache::ends_at`], the
+    /// paused time of a paused one from [`StatusCache::paused_at`], and the
+    /// flags that change over time recomputed with `config`.
+    ///
+    /// Returns `None` once a running session has at most
+    /// [`ProgramConfig::auto_complete_tolerance`] left: completing it takes a
+    /// regular `status`, which records the event and runs the hooks.
+    pub fn status_at(
+        self,
+        now: chrono::DateTime<chrono::Utc>,
+        config: &ProgramConfig,
+    ) -> Option<SessionStatus> {
+        let mut status = self.status;
+        status.ended_at = self.ended_at;
+        if let Some(ends_at) = self.ends_at {
+            let remaining_secs = (ends_at - now).num_seconds();
+            if remaining_secs <= config.auto_complete_tolerance.as_secs() as i64 {
+                return None;
+            }
+            let elapsed_secs = status.planned_secs - remaining_secs;
+            status.wall_elapsed_secs += elapsed_secs - status.elapsed_secs;
+            status.remaining_secs = remaining_secs;
+            status.elapsed_secs = elapsed_secs;
+            status.update_progress();
+        }
+        if let Some(paused_at) = self.paused_at {
+            let paused_for_secs = (now - paused_at).num_seconds().max(0);
+            status.paused_secs += paused_for_secs - status.paused_for_secs;
+            status.wall_elapsed_secs += paused_for_secs - status.paused_for_secs;
+            status.paused_for_secs = paused_for_secs;
+        }
+        status.update_flags(config, now);
+        Some(status)
+    }
+}
+
+/// SessionDigest holds today's completed session counts together with the
+/// current [`SessionStatus`], used as the data model for both JSON and text
+/// output of the `summary` command.
+#[derive(serde::Serialize)]
+pub struct SessionDigest {
+    /// Number of focus sessions completed today.
+    pub focus_count: u32,
+    /// Combined planned duration of today's completed focus sessions, in seconds.
+    pub focus_secs: i64,
+    /// Number of break sessions completed today.
+    pub break_count: u32,
+    /// Combined planned duration of today's completed break sessions, in seconds.
+    pub break_secs: i64,
+    /// Status of the most recent session.
+    pub current: SessionStatus,
+}
+
+/// SummaryCommand composes today's completed session counts and the current
+/// status into a single digest, e.g.
+/// `Today: 4 focus (1h40m), 3 breaks; current: focus 12:30 left`.
+pub struct SummaryCommand<'q> {
+    /// Runner is used to execute the hooks fired when the current session auto-completes.
+    pub runner: Option<Runner>,
+    /// Querier is used to retrieve the sessions from the database.
+    pub querier: Querier<'q>,
+    /// Config is handed to the [`StatusCommand`] computing the current status.
+    pub config: ProgramConfig,
+}
+
+impl<'q> SummaryCommand<'q> {
+    /// Compute the [`SessionDigest`] for the current local day and return it.
+    ///
+    /// The current status comes from [`StatusCommand::execute`], so a running
+    /// session with no time left is auto-completed — and counted — first.
+    pub fn execute(&self) -> Result<SessionDigest> {
+        let command = StatusCommand {
+            runner: self.runner.clone(),
+            querier: self.querier.clone(),
+            config: self.config.clone(),
+        };
+        let current = command.execute()?;
+
+        let since = start_of_today()?;
+        let params = CountCompletedSessionsArgs { since };
+        let counts = self.querier.count_completed_sessions(&params)?;
+
+        let mut digest = SessionDigest {
+            focus_count: 0,
+            focus_secs: 0,
+            break_count: 0,
+            break_secs: 0,
+            current,
+        };
+        for count in counts {
+            let planned_secs = count.planned_duration.num_seconds();
+            match count.kind {
+                SessionKind::Focus => {
+                    digest.focus_count = count.num_sessions;
+                    digest.focus_secs = planned_secs;
+                }
+                SessionKind::Break => {
+                    digest.break_count = count.num_sessions;
+                    digest.break_secs = planned_secs;
+                }
+            }
+        }
+
+        Ok(digest)
+    }
+
+    /// Render `digest` according to `args.output`.
+    ///
+    /// - `--output json`: pretty-printed JSON via `serde_json`.
+    /// - `--output text`: the humanized one-line digest.
+    pub fn render(&self, digest: &SessionDigest, args: &SummaryCommandArgs) -> Result<String> {
+        Renderer::new(args.output).render(digest, |digest| Ok(Self::render_text(digest)))
+    }
+
+    /// Format `digest` as the humanized one-line digest.
+    fn render_text(digest: &SessionDigest) -> String {
+        let breaks = if digest.break_count == 1 {
+            "break"
+        } else {
+            "breaks"
+        };
+        let current = &digest.current;
+        let current = match current.state {
+            SessionState::None => "none".to_string(),
+            SessionState::Disabled => "disabled".to_string(),
+            SessionState::Running | SessionState::Paused => {
+                let remaining = format!(
+                    "{} {:02}:{:02} left",
+                    current.kind,
+                    current.remaining_secs / 60,
+                    current.remaining_secs % 60
+                );
+                match current.state {
+                    SessionState::Paused => format!("{remaining} (paused)"),
+                    _ => remaining,
+                }
+            }
+            SessionState::Completed => format!("{} completed", current.kind),
+            SessionState::Aborted => format!("{} aborted", current.kind),
+        };
+
+        format!(
+            "Today: {} focus ({}), {} {}; current: {}",
+            digest.focus_count,
+            humanize_secs(digest.focus_secs),
+            digest.break_count,
+            breaks,
+            current
+        )
+    }
+}
+
+/// SessionStatsReport holds the focus session aggregates of a window, used as
+/// the data model for both JSON and text output of the `stats` command.
+#[derive(serde::Serialize)]
+pub struct SessionStatsReport {
+    /// Start of the window; sessions started at or after it are aggregated.
+    pub since: chrono::DateTime<chrono::Utc>,
+    /// Number of focus sessions completed.
+    pub completed: u32,
+    /// Number of focus sessions aborted.
+    pub aborted: u32,
+    /// Number of focus sessions still running or paused.
+    pub in_progress: u32,
+    /// Number of interruptions recorded during the focus sessions.
+    pub interruptions: u32,
+    /// Combined planned duration of the completed focus sessions, in seconds.
+    pub focus_secs: i64,
+    /// Share of the ended sessions that were completed, from 0 to 1; `None`
+    /// while no session ended. Sessions in progress do not count.
+    pub completion_rate: Option<f64>,
+}
+
+/// StatsCommand aggregates the focus sessions started within a window, e.g.
+/// `Last 7d: 20 focus sessions completed (8h20m), 3 aborted, 1 in progress; 87% completed`.
+pub struct StatsCommand<'q> {
+    /// Querier is used to aggregate the sessions.
+    pub querier: Querier<'q>,
+}
+
+impl<'q> StatsCommand<'q> {
+    /// Aggregate the focus sessions started within `args.since` of now.
+    ///
+    /// A session counts by its latest event, so one that is still running is
+    /// in progress even when its time is up: only `status` completes it.
+    pub fn execute(&self, args: &StatsCommandArgs) -> Result<SessionStatsReport> {
+        let since = Duration::from_std(args.since.duration)
+            .ok()
+            .and_then(|since| chrono::Utc::now().checked_sub_signed(since))
+            .context("--since is too far in the past")?;
+        let stats = self
+            .querier
+            .aggregate_stats(&AggregateStatsArgs { since })?;
+
+        let ended = stats.num_completed + stats.num_aborted;
+        Ok(SessionStatsReport {
+            since,
+            completed: stats.num_completed,
+            aborted: stats.num_aborted,
+            in_progress: stats.num_in_progress(),
+            interruptions: stats.num_interruptions,
+            focus_secs: stats.focus_duration.num_seconds(),
+            completion_rate: (ended > 0).then(|| f64::from(stats.num_completed) / f64::from(ended)),
+        })
+    }
+
+    /// Render `report` according to `args.output`.
+    ///
+    /// - `--output json`: pretty-printed JSON via `serde_json`.
+    /// - `--output text`: the humanized one-line report.
+    pub fn render(&self, report: &SessionStatsReport, args: &StatsCommandArgs) -> Result<String> {
+        Renderer::new(args.output).render(report, |report| Ok(Self::render_text(report, args)))
+    }
+
+    /// Format `report` as the humanized one-line report.
+    fn render_text(report: &SessionStatsReport, args: &StatsCommandArgs) -> String {
+        let sessions = if report.completed == 1 {
+            "session"
+        } else {
+            "sessions"
+        };
+        let mut text = format!(
+            "Last {}: {} focus {} completed ({}), {} aborted, {} in progress",
+            args.since.text,
+            report.completed,
+            sessions,
+            humanize_secs(report.focus_secs),
+            report.aborted,
+            report.in_progress
+        );
+        match report.interruptions {
+            0 => {}
+            1 => text.push_str(", 1 interruption"),
+            n => text.push_str(&format!(", {n} interruptions")),
+        }
+        if let Some(rate) = report.completion_rate {
+            text.push_str(&format!("; {:.0}% completed", rate * 100.0));
+        }
+        text
+    }
+}
+
+/// Format `secs` as a compact duration such as `1h40m` or `25m`.
+fn humanize_secs(secs: i64) -> String {
+    let (hours, minutes) = (secs / 3600, secs % 3600 / 60);
+    if hours > 0 {
+        format!("{hours}h{minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+/// RuntimeContext describes the environment the timer runs in, used as the
+/// data model for both JSON and text output of the `context` command.
+#[derive(serde::Serialize)]
+pub struct RuntimeContext {
+    /// Whether `$TMUX` is set, i.e. the command runs inside a tmux client.
+    pub tmux: bool,
+    /// The value of `$TMUX_PANE`, if set.
+    pub tmux_pane: Option<String>,
+    /// Path of the configuration file, whether or not it exists.
+    pub config_path: Option<PathBuf>,
+    /// Path of the database file, or `None` for an in-memory database.
+    pub database_path: Option<PathBuf>,
+    /// Whether the most recent session is running or paused.
+    pub active_session: bool,
+    /// Number of sessions stored in the database.
+    pub session_count: u64,
+    /// Number of session events stored in the database.
+    pub session_event_count: u64,
+    /// Hook scripts found in the hooks directory (none with `--no-hooks`).
+    pub hooks: Vec<HookInfo>,
+}
+
+/// ContextCommand reports the runtime context relevant to this tool — tmux
+/// environment, resolved paths, and whether a session is active — to help
+/// debug a status line that does not show up. It never changes any state.
+pub struct ContextCommand<'q> {
+    /// Runner is used to find the installed hooks.
+    pub runner: Option<Runner>,
+    /// Querier is used to look up the most recent session event.
+    pub querier: Querier<'q>,
+    /// Path of the configuration file.
+    pub config_path: Option<PathBuf>,
+    /// Path of the database file, or `None` for an in-memory database.
+    pub database_path: Option<PathBuf>,
+}
+
+impl<'q> ContextCommand<'q> {
+    /// Collect the [`RuntimeContext`] and return it.
+    pub fn execute(&self) -> Result<RuntimeContext> {
+        let active_session = current_session_event(&self.querier)?.is_some_and(|session_event| {
+            matches!(
+                session_event.kind,
+                SessionEventKind::Started | SessionEventKind::Resumed | SessionEventKind::Paused
+            )
+        });
+
+        Ok(RuntimeContext {
+            tmux: std::env::var_os("TMUX").is_some_and(|tmux| !tmux.is_empty()),
+            tmux_pane: std::env::var("TMUX_PANE").ok(),
+            config_path: self.config_path.clone(),
+            database_path: self.database_path.clone(),
+            active_session,
+            session_count: self.querier.count_sessions(&CountSessionsArgs::default())?,
+            session_event_count: self
+                .querier
+                .count_session_events(&CountSessionEventsArgs::default())?,
+            hooks: self.runner.as_ref().map_or_else(Vec::new, |runner| {
+                let hooks = runner.installed_hooks().into_iter();
+                hooks.filter(|hook| hook.exists).collect()
+            }),
+        })
+    }
+
+    /// Render `context` according to `args.output`.
+    ///
+    /// - `--output json`: pretty-printed JSON via `serde_json`.
+    /// - `--output text`: one `key: value` line per field.
+    pub fn render(&self, context: &RuntimeContext, args: &ContextCommandArgs) -> Result<String> {
+        Renderer::new(args.output).render(context, |context| Ok(Self::render_text(context)))
+    }
+
+    /// Format `context` as one `key: value` line per field.
+    fn render_text(context: &RuntimeContext) -> String {
+        let path = |path: &Option<PathBuf>, none: &str| {
+            path.as_ref()
+                .map_or(none.to_string(), |path| path.display().to_string())
+        };
+        let hooks = context.hooks.iter().map(|hook| {
+            let suffix = if hook.executable {
+                ""
+            } else {
+                " (not executable)"
+            };
+            format!("{}{suffix}", hook.path.display())
+        });
+        let hooks = hooks.collect::<Vec<_>>();
+        [
+            format!("tmux: {}", context.tmux),
+            format!("tmux_pane: {}", context.tmux_pane.as_deref().unwrap_or("-")),
+            format!("config_path: {}", path(&context.config_path, "-")),
+            format!(
+                "database_path: {}",
+                path(&context.database_path, "(in-memory)")
+            ),
+            format!("active_session: {}", context.active_session),
+            format!("session_count: {}", context.session_count),
+            format!("session_event_count: {}", context.session_event_count),
+            format!(
+                "hooks: {}",
+                if hooks.is_empty() {
+                    "-".to_string()
+                } else {
+                    hooks.join(", ")
+                }
+            ),
+        ]
+        .join("\n")
+    }
+}
+
+/// Write `time` as a JSON value in `format`.
+fn format_time(time: &chrono::DateTime<chrono::Utc>, format: TimeFormat) -> serde_json::Value {
+    match format {
+        TimeFormat::Rfc3339 => serde_json::json!(time),
+        TimeFormat::EpochMs => serde_json::json!(time.timestamp_millis()),
+        TimeFormat::EpochS => serde_json::json!(time.timestamp()),
+    }
+}
+
+/// NoteCommand appends a free-form, timestamped note to the journal.
+pub struct NoteCommand<'q> {
+    /// Querier is used to store the note.
+    pub querier: Querier<'q>,
+}
+
+impl<'q> NoteCommand<'q> {
+    /// Store the note and return a confirmation message.
+    pub fn execute(&self, args: &NoteCommandArgs) -> Result<String> {
+        let text = args.text.trim();
+        anyhow::ensure!(!text.is_empty(), "A note cannot be empty.");
+
+        let note = &Note::new(text);
+        self.querier.insert_note(&InsertNoteArgs { note })?;
+        Ok("Added a note.".to_string())
+    }
+}
+
+/// FocusOnCommand pins a session, so that `start`, `stop`, and `status` act on
+/// it instead of the most recent session until it ends.
+pub struct FocusOnCommand<'q> {
+    /// Querier is used to look up the session and store the pin.
+    pub querier: Querier<'q>,
+}
+
+impl<'q> FocusOnCommand<'q> {
+    /// Pin the session given by `args.session`, or remove the pin with
+    /// `--clear`, and return a confirmation message.
+    pub fn execute(&self, args: &FocusOnCommandArgs) -> Result<String> {
+        let Some(session_id) = &args.session else {
+            let params = DeleteMetaArgs {
+                key: MetaKey::PinnedSessionId,
+            };
+            self.querier.delete_meta(&params)?;
+            return Ok("Following the most recent session.".to_string());
+        };
+
+        let session = self
+            .querier
+            .get_session_by_id(&GetSessionByIdArgs { session_id })
+            .with_context(|| format!("No session with ID {session_id}"))?;
+        let params = ListSessionEventsArgs::with_session_id(session.id);
+        let ended = self
+            .querier
+            .list_session_events(&params)?
+            .first()
+            .is_some_and(|session_event| {
+                matches!(
+                    session_event.kind,
+                    SessionEventKind::Aborted | SessionEventKind::Completed
+                )
+            });
+        anyhow::ensure!(
+            !ended,
+            "The {} session {} already ended.",
+            session.kind,
+            session.id
+        );
+
+        let value = &session.id.to_string();
+        let params = SetMetaArgs {
+            key: MetaKey::PinnedSessionId,
+            value,
+        };
+        self.querier.set_meta(&params)?;
+        Ok(format!(
+            "Focusing on the {} session {}.",
+            session.kind, session.id
+        ))
+    }
+}
+
+/// NotesCommand lists the journal written with [`NoteCommand`].
+pub struct NotesCommand<'q> {
+    /// Querier is used to retrieve the notes.
+    pub querier: Querier<'q>,
+}
+
+impl<'q> NotesCommand<'q> {
+    /// Return the notes in the order they were written, only today's with `--today`.
+    pub fn execute(&self, args: &NotesCommandArgs) -> Result<Vec<Note>> {
+        let since = args.today.then(start_of_today).transpose()?;
+        self.querier.list_notes(&ListNotesArgs { since })
+    }
+
+    /// Render `notes` according to `args.output`: a JSON array with
+    /// `created_at` in `args.time_format`, or one `YYYY-MM-DD HH:MM  text`
+    /// line per note in local time.
+    pub fn render(&self, notes: &[Note], args: &NotesCommandArgs) -> Result<String> {
+        let values = notes
+            .iter()
+            .map(|note| {
+                let mut value = serde_json::to_value(note)?;
+                value["created_at"] = format_time(&note.created_at, args.time_format);
+                Ok(value)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Renderer::new(args.output).render(&values, |_| {
+            Ok(notes
+                .iter()
+                .map(|note| {
+                    let created_at = note.created_at.with_timezone(&chrono::Local);
+                    format!("{}  {}", created_at.format("%Y-%m-%d %H:%M"), note.text)
+                })
+                .collect::<Vec<_>>()
+                .join("\n"))
+        })
+    }
+}
+
+/// ReplayStep is one point of a replayed event log, together with the
+/// interval that led up to it.
+#[derive(serde::Serialize)]
+pub struct ReplayStep {
+    /// When the event was recorded, or the current time for an interval that
+    /// is still open.
+    pub at: chrono::DateTime<chrono::Utc>,
+    /// The recorded event, or `None` for an interval that is still open.
+    pub event: Option<SessionEventKind>,
+    /// What the session did since the previous step: `running`, `paused`, or
+    /// `none` before the first event.
+    pub interval: SessionState,
+    /// Length of that interval in seconds.
+    pub interval_secs: i64,
+    /// Elapsed time in seconds accumulated up to this step.
+    pub elapsed_secs: i64,
+}
+
+/// SessionReplay is the timeline of a session reconstructed from its events,
+/// used as the data model for both JSON and text output of the `replay`
+/// command.
+#[derive(serde::Serialize)]
+pub struct SessionReplay {
+    /// ID of the replayed session.
+    pub session_id: Uuid,
+    /// Whether the session is a focus or break session.
+    pub kind: SessionKind,
+    /// Planned duration of the session in seconds.
+    pub planned_secs: i64,
+    /// The events of the session, oldest first.
+    pub steps: Vec<ReplayStep>,
+    /// Total elapsed time in seconds.
+    pub elapsed_secs: i64,
+    /// Total time spent paused in seconds.
+    pub paused_secs: i64,
+    /// Remaining time in seconds (clamped to zero).
+    pub remaining_secs: i64,
+}
+
+/// ReplayCommand prints how the elapsed time of a session adds up, event by
+/// event, to troubleshoot the numbers reported by `status`. It never changes
+/// any state.
+pub struct ReplayCommand<'q> {
+    /// Querier is used to retrieve the session and its events.
+    pub querier: Querier<'q>,
+}
+
+impl<'q> ReplayCommand<'q> {
+    /// Replay the events of the session given by `--session`, or of the most
+    /// recent session, and return the timeline.
+    ///
+    /// Each step is the [`SessionSummary::replay`] of the events up to and
+    /// including it, so the timeline always adds up to the numbers reported
+    /// by `status`; an interval still open after the last event is measured
+    /// up to now.
+    pub fn execute(&self, args: &ReplayCommandArgs) -> Result<SessionReplay> {
+        let session = match &args.session {
+            Some(session_id) => self
+                .querier
+                .get_session_by_id(&GetSessionByIdArgs { session_id })
+                .with_context(|| format!("No session with ID {session_id}"))?,
+            None => {
+                let result = self.querier.list_sessions(&ListSessionsArgs::first())?;
+                result.into_iter().next().context("No session to replay")?
+            }
+        };
+
+        let params = ListSessionEventsArgs::with_session_id(session.id);
+        let events = self.querier.list_session_events(&params)?;
+
+        // Events are listed newest first, so each suffix of `events` is the
+        // history of the session up to one event.
+        let now = chrono::Utc::now();
+        let mut steps = Vec::new();
+        let mut previous: Option<(chrono::DateTime<chrono::Utc>, SessionSummary)> = None;
+        let mut push = |at, event: Option<&SessionEventKind>, summary: SessionSummary| {
+            let (interval, length) = match &previous {
+                Some((since, last)) => (
+                    last.final_state
+                        .as_ref()
+                        .map_or(SessionState::None, SessionState::from),
+                    at - *since,
+                ),
+                None => (SessionState::None, Duration::zero()),
+            };
+            steps.push(ReplayStep {
+                at,
+                event: event.cloned(),
+                interval,
+                interval_secs: length.num_seconds(),
+                elapsed_secs: summary.elapsed.num_seconds(),
+            });
+            previous = Some((at, summary));
+        };
+        for index in (0..events.len()).rev() {
+            let event = &events[index];
+            let summary =
+                SessionSummary::replay(session.clone(), &events[index..], event.created_at);
+            push(event.created_at, Some(&event.kind), summary);
+        }
+
+        let summary = SessionSummary::replay(session, &events, now);
+        let open = matches!(
+            summary.final_state,
+            Some(SessionEventKind::Started | SessionEventKind::Resumed | SessionEventKind::Paused)
+        );
+        if open {
+            push(now, None, summary.clone());
+        }
+
+        let planned_secs = summary.session.planned_duration.num_seconds();
+        let elapsed_secs = summary.elapsed.num_seconds().max(0);
+        Ok(SessionReplay {
+            session_id: summary.session.id,
+            kind: summary.session.kind,
+            planned_secs,
+            steps,
+            elapsed_secs,
+            paused_secs: summary.paused.num_seconds().max(0),
+            remaining_secs: (planned_secs - elapsed_secs).max(0),
+        })
+    }
+
+    /// Render `replay` according to `args.output`.
+    ///
+    /// - `--output json`: pretty-printed JSON via `serde_json`.
+    /// - `--output text`: a header, one line per step in local time, and the
+    ///   resulting elapsed, paused, and remaining time.
+    pub fn render(&self, replay: &SessionReplay, args: &ReplayCommandArgs) -> Result<String> {
+        Renderer::new(args.output).render(replay, |replay| Ok(Self::render_text(replay)))
+    }
+
+    /// Format `replay` as a header, one line per step in local time, and the
+    /// resulting elapsed, paused, and remaining time.
+    fn render_text(replay: &SessionReplay) -> String {
+        let clock = |secs: i64| format!("{:02}:{:02}", secs / 60, secs % 60);
+        let mut lines = vec![format!(
+            "{} session {} (planned {})",
+            replay.kind,
+            replay.session_id,
+            clock(replay.planned_secs)
+        )];
+        for step in &replay.steps {
+            let at = match step.event {
+                Some(_) => step
+                    .at
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string(),
+                None => "now".to_string(),
+            };
+            let event = step
+                .event
+                .as_ref()
+                .map_or("-".to_string(), ToString::to_string);
+            let interval = match step.interval {
+                SessionState::Running => format!("running {}", clock(step.interval_secs)),
+                SessionState::Paused => format!("paused  {}", clock(step.interval_secs)),
+                _ => "-".to_string(),
+            };
+            lines.push(format!(
+                "{at:<19}  {event:<9}  {interval:<13}  elapsed {}",
+                clock(step.elapsed_secs)
+            ));
+        }
+        lines.push(format!(
+            "elapsed {} | paused {} | remaining {}",
+            clock(replay.elapsed_secs),
+            clock(replay.paused_secs),
+            clock(replay.remaining_secs)
+        ));
+        lines.join("\n")
+    }
+}
+
+/// SessionRecord is one session of the history listed by the `list` command,
+/// with its final state derived by replaying its events.
+#[derive(serde::Serialize)]
+pub struct SessionRecord {
+    /// ID of the session.
+    pub session_id: Uuid,
+    /// Whether the session is a focus or break session.
+    pub kind: SessionKind,
+    /// The lifecycle state after the session's latest event.
+    pub state: SessionState,
+    /// Planned duration of the session in seconds.
+    pub planned_secs: i64,
+    /// Total elapsed time in seconds.
+    pub elapsed_secs: i64,
+    /// Number of times the session was paused.
+    pub pause_count: u32,
+    /// When the session was created.
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// ListCommand lists the most recent sessions, newest first. Unlike `status`
+/// it never completes a session that ran out of time.
+pub struct ListCommand<'q> {
+    /// Querier is used to retrieve the sessions and their events.
+    pub querier: Querier<'q>,
+}
+
+impl<'q> ListCommand<'q> {
+    /// Return up to `args.limit` sessions, each replayed with
+    /// [`Querier::session_summary`] like [`StatusCommand::execute`] does.
+    pub fn execute(&self, args: &ListCommandArgs) -> Result<Vec<SessionRecord>> {
+        let params = &ListSessionsArgs {
+            kind: args.kind.map(SessionKind::from),
+            limit: Some(args.limit),
+            offset: None,
+        };
+        let sessions = self.querier.list_sessions(params)?;
+
+        let mut records = Vec::new();
+        for session in sessions {
+            let params = &SessionSummaryArgs {
+                session_id: &session.id,
+            };
+            let summary = self.querier.session_summary(params)?;
+            records.push(SessionRecord {
+                session_id: session.id,
+                kind: session.kind,
+                state: summary
+                    .final_state
+                    .as_ref()
+                    .map(SessionState::from)
+                    .unwrap_or_default(),
+                planned_secs: session.planned_duration.num_seconds(),
+                elapsed_secs: summary.elapsed.num_seconds().max(0),
+                pause_count: summary.num_pauses,
+                created_at: session.created_at,
+            });
+        }
+        Ok(records)
+    }
+
+    /// Render `records` according to `args.output`: a JSON array, or one
+    /// `kind  state  planned  created-at` line per session in local time.
+    /// An empty history renders as `[]` or nothing at all.
+    pub fn render(&self, records: &[SessionRecord], args: &ListCommandArgs) -> Result<String> {
+        Renderer::new(args.output).render(&records, |records| {
+            Ok(records
+                .iter()
+                .map(|record| {
+                    let created_at = record.created_at.with_timezone(&chrono::Local);
+                    format!(
+                        "{:<5}  {:<9}  {:02}:{:02}  {}",
+                        record.kind.to_string(),
+                        record.state.to_string(),
+                        record.planned_secs / 60,
+                        record.planned_secs % 60,
+                        created_at.format("%Y-%m-%d %H:%M")
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n"))
+        })
+    }
+}
+
+/// GcCommand rewrites the event log to drop pauses too short to matter, e.g.
+/// from a status bar click that was undone right away. Every affected session
+/// keeps its elapsed time and final state.
+pub struct GcCommand<'q> {
+    /// Querier is used to rewrite the session events.
+    pub querier: Querier<'q>,
+}
+
+impl<'q> GcCommand<'q> {
+    /// Remove every pause shorter than `--threshold` together with the resume
+    /// that ended it, and return a confirmation message.
+    ///
+    /// A removed pause turns into running time, so every earlier event of the
+    /// session moves forward by the length of the pause. A pause ended by the
+    /// latest event of its session is kept, so the final state never changes.
+    /// The session is replayed before and after the rewrite and the command
+    /// fails, rolling everything back, should its elapsed time differ.
+    pub fn execute(&self, args: &GcCommandArgs) -> Result<String> {
+        let threshold = Duration::from_std(args.threshold.duration)?;
+        let now = chrono::Utc::now();
+
+        let mut num_pauses = 0;
+        let mut num_sessions = 0;
+        for session in self.querier.list_sessions(&ListSessionsArgs::default())? {
+            let params = ListSessionEventsArgs::with_session_id(session.id);
+            let events = self.querier.list_session_events(&params)?;
+
+            // Events are listed newest first, so a pause is followed by the event before it.
+            let noise = (2..events.len())
+                .filter(|&i| {
+                    events[i].kind == SessionEventKind::Paused
+                        && events[i - 1].kind == SessionEventKind::Resumed
+                        && events[i - 1].created_at - events[i].created_at < threshold
+                })
+                .collect::<Vec<_>>();
+            if noise.is_empty() {
+                continue;
+            }
+
+            // Walk back in time, moving every event forward by the pauses removed after it.
+            let mut shift = Duration::zero();
+            for (i, event) in events.iter().enumerate() {
+                if noise.contains(&(i + 1)) {
+                    shift += event.created_at - events[i + 1].created_at;
+                }
+                if noise.contains(&i) || noise.contains(&(i + 1)) {
+                    let session_event_id = &event.id;
+                    let params = DeleteSessionEventArgs { session_event_id };
+                    self.querier.delete_session_event(&params)?;
+                } else if !shift.is_zero() {
+                    let session_event = &SessionEvent {
+                        created_at: event.created_at + shift,
+                        ..event.clone()
+                    };
+                    let params = UpdateSessionEventArgs { session_event };
+                    self.querier.update_session_event(&params)?;
+                }
+            }
+
+            let before = SessionSummary::replay(session.clone(), &events, now);
+            let events = self.querier.list_session_events(&params)?;
+            let after = SessionSummary::replay(session, &events, now);
+            anyhow::ensure!(
+                before.elapsed == after.elapsed && before.final_state == after.final_state,
+                "Removing the pauses of session {} would change its elapsed time",
+                before.session.id
+            );
+
+            num_pauses += noise.len();
+            num_sessions += 1;
+        }
+
+        if num_pauses == 0 {
+            return Ok("No pauses shorter than the threshold.".to_string());
+        }
+        Ok(format!(
+            "Removed {num_pauses} short pause(s) from {num_sessions} session(s)."
+        ))
+    }
+}
+
+/// EditConfigCommand opens the configuration file in the user's editor,
+/// creating it from [`DEFAULT_CONFIG_TEMPLATE`] first when it does not exist,
+/// and validates the result once the editor exits.
+///
+/// It never touches the database, so it runs before the database is opened
+/// and does not hold the lock while the editor is open.
+pub struct EditConfigCommand {
+    /// Path of the configuration file.
+    pub path: PathBuf,
+    /// Editor command, run via `sh -c` with the path appended (e.g. `code -w`).
+    pub editor: String,
+}
+
+impl EditConfigCommand {
+    /// Resolve the editor from `$VISUAL`, then `$EDITOR`, falling back to `vi`.
+    pub fn editor() -> String {
+        ["VISUAL", "EDITOR"]
+            .into_iter()
+            .filter_map(std::env::var_os)
+            .find(|editor| !editor.is_empty())
+            .map(|editor| editor.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "vi".to_string())
+    }
+
+    /// Create the file if needed, run the editor, and validate the file.
+    ///
+    /// Returns an error when the editor fails or the edited file does not
+    /// parse, so a mistake is reported right away instead of being silently
+    /// replaced by the defaults on the next run.
+    pub fn execute(&self) -> Result<String> {
+        if !self.path.exists() {
+            std::fs::write(&self.path, DEFAULT_CONFIG_TEMPLATE).with_context(|| {
+                format!(
+                    "Failed to create configuration file {}",
+                    self.path.display()
+                )
+            })?;
+        }
+
+        let status = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("{} \"$1\"", self.editor))
+            .arg("sh")
+            .arg(&self.path)
+            .status()
+            .context("Failed to run the editor")?;
+        anyhow::ensure!(status.success(), "The editor exited with {}", status);
+
+        ProgramConfig::load_from(&self.path)
+            .with_context(|| format!("Invalid configuration file {}", self.path.display()))?;
+        Ok(format!(
+            "Configuration file {} is valid.",
+            self.path.display()
+        ))
+    }
+}
+
+/// InitCommand scaffolds the configuration file from
+/// [`DEFAULT_CONFIG_TEMPLATE`] and the hooks directory with commented,
+/// non-executable `start` and `stop` hooks. Existing files are kept unless
+/// `--force` is passed.
+pub struct InitCommand {
+    /// Path of the TOML configuration file, the format of the template.
+    pub config_path: PathBuf,
+    /// Path of the hooks directory.
+    pub hooks_path: PathBuf,
+}
+
+impl InitCommand {
+    /// Create the missing files and return a line per file telling what was done.
+    ///
+    /// The configuration is left alone, even with `--force`, while one of the
+    /// other [`CONFIG_FILE_NAMES`] exists: a new `config.toml` would take
+    /// precedence over it.
+    pub fn execute(&self, args: &InitCommandArgs) -> Result<String> {
+        let mut lines = Vec::new();
+        let mut files = vec![
+            (self.hooks_path.join("start"), START_HOOK_TEMPLATE),
+            (self.hooks_path.join("stop"), STOP_HOOK_TEMPLATE),
+        ];
+        let other_config = CONFIG_FILE_NAMES
+            .iter()
+            .map(|name| self.config_path.with_file_name(name))
+            .find(|path| *path != self.config_path && path.exists());
+        match other_config {
+            Some(path) => lines.push(format!(
+                "Kept {} (not creating {})",
+                path.display(),
+                self.config_path.display()
+            )),
+            None => files.insert(0, (self.config_path.clone(), DEFAULT_CONFIG_TEMPLATE)),
+        }
+
+        for (path, content) in files {
+            let exists = path.exists();
+            if exists && !args.force {
+                lines.push(format!("Kept {} (already exists)", path.display()));
+                continue;
+            }
+
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+            std::fs::write(&path, content)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            let action = if exists { "Overwrote" } else { "Created" };
+            lines.push(format!("{} {}", action, path.display()));
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+/// Return local midnight of the current day as a UTC instant, the lower bound
+/// used by the "today" views (`summary`, `notes --today`).
+fn start_of_today() -> Result<chrono::DateTime<chrono::Utc>> {
+    Ok(chrono::Local::now()
+        .date_naive()
+        .and_time(chrono::NaiveTime::MIN)
+        .and_local_timezone(chrono::Local)
+        .earliest()
+        .context("Failed to determine the start of the day")?
+        .to_utc())
+}
+
+/// Render `template` against `status` and the `--var` variables in `vars` and
+/// wrap it in tmux style directives, e.g. `#[fg=red]🍅 24:59#[default]`.
+///
+/// The foreground color is picked from `colors` by state: running sessions use
+/// their kind's color, or `ending` once less than 10% of the planned time is
+/// left; paused, completed, and aborted sessions use their own color. When no
+/// session exists or the timer is disabled the text is left unstyled.
+pub fn render_tmux(
+    status: &SessionStatus,
+    vars: &[(String, String)],
+    template: &str,
+    colors: &ColorsConfig,
+) -> Result<String> {
+    let output = render_template(template, &template_context(status, vars)?)?;
+    let color = match status.state {
+        SessionState::None | SessionState::Disabled => return Ok(output),
+        SessionState::Running if status.remaining_secs * 10 < status.planned_secs => &colors.ending,
+        SessionState::Running if status.kind == StatusKind::Session(SessionKind::Break) => {
+            &colors.r#break
+        }
+        SessionState::Running => &colors.focus,
+        SessionState::Paused => &colors.paused,
+        SessionState::Completed => &colors.completed,
+        SessionState::Aborted => &colors.aborted,
+    };
+    Ok(format!("#[fg={color}]{output}#[default]"))
+}
+
+/// WaitCommand blocks until the most recent session is no longer active, then
+/// runs the optional `--then` shell command when the session completed.
+///
+/// Unlike the other commands it owns the [`Database`] rather than a
+/// [`Querier`]: each tick opens and commits its own transaction so that other
+/// invocations (e.g. `pomodoro stop`) can observe and change the session while
+/// this command is waiting. That transaction only takes the write lock when
+/// the session is auto-completed, so waiting never holds up a writer.
+pub struct WaitCommand<'d> {
+    /// Runner is used to execute the hooks.
+    pub runner: Option<Runner>,
+    /// Config is handed to the [`StatusCommand`] run on every tick.
+    pub config: ProgramConfig,
+    /// Database is used to open a fresh transaction on every tick.
+    pub database: &'d mut Database,
+}
+
+impl<'d> WaitCommand<'d> {
+    /// How long to sleep between two consecutive status checks.
+    const INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+    /// Execute the WaitCommand with the arguments the session was started with.
+    ///
+    /// Polls [`StatusCommand::execute`] — which auto-completes the session once
+    /// no time is left — until the session is neither running nor paused. With
+    /// `args.progress`, a status line is printed at that interval meanwhile.
+    /// When the session ended up [`SessionState::Completed`], `args.then` is
+    /// run via `sh -c`; an aborted session skips it.
+    pub fn execute(&mut self, args: &StartCommandArgs) -> Result<()> {
+        let progress = args.progress.as_ref().map(|progress| progress.duration);
+        let tick = progress.map_or(Self::INTERVAL, |progress| progress.min(Self::INTERVAL));
+        let mut next_progress = std::time::Instant::now();
+        // A tick that lost the race for the write lock runs again, so its
+        // hooks wait for the commit.
+        let hooks = self.runner.clone().map(Runner::deferred);
+        let status = loop {
+            let status =
+                self.database
+                    .with_transaction(TransactionBehavior::Deferred, |querier| {
+                        if let Some(hooks) = &hooks {
+                            hooks.discard();
+                        }
+                        let command = StatusCommand {
+                            runner: hooks.clone(),
+                            querier,
+                            config: self.config.clone(),
+                        };
+                        command.execute()
+                    })?;
+            if let Some(hooks) = &hooks {
+                hooks.flush();
+            }
+
+            if let Some(progress) = progress {
+                let now = std::time::Instant::now();
+                if now >= next_progress {
+                    let template = self.config.template_for(StatusOutput::Text);
+                    let line = status_line(&status, args.output, template)?;
+                    println!("{}", line);
+                    next_progress = now + progress;
+                }
+            }
+
+            if !matches!(status.state, SessionState::Running | SessionState::Paused) {
+                break status;
+            }
+            std::thread::sleep(tick);
+        };
+
+        if let (SessionState::Completed, Some(then)) = (&status.state, &args.then) {
+            std::process::Command::new("sh")
+                .arg("-c")
+                .arg(then)
+                .status()
+                .context("Failed to run the --then command")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// ServeCommand answers status requests on a Unix domain socket, so that a
+/// long-running client (e.g. a tmux plugin) can poll the timer without
+/// spawning a process on every refresh.
+///
+/// Like [`WaitCommand`] it owns the [`Database`] and opens a fresh transaction
+/// for every request. That transaction is never committed: serving the status
+/// must not change the session, so an auto-completion recorded by
+/// [`StatusCommand::execute`] is rolled back and no hooks fire. The next
+/// `pomodoro status` records it for real.
+///
+/// Every connection is served on a thread of its own, so that a client
+/// streaming with `--interval` never holds up the others; they take turns on
+/// the database only while computing a status.
+pub struct ServeCommand<'d> {
+    /// Config is handed to the [`StatusCommand`] run on every request.
+    pub config: ProgramConfig,
+    /// Disabled reports [`SessionStatus::disabled`] without reading the database.
+    pub disabled: bool,
+    /// Database is used to open a fresh transaction on every request.
+    pub database: Mutex<&'d mut Database>,
+}
+
+impl<'d> ServeCommand<'d> {
+    /// How long writing a status line may block before the client is dropped,
+    /// so that a client that stopped reading cannot pin its thread forever.
+    const WRITE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+    /// Bind a listener at `path`, replacing a stale socket left behind by a
+    /// previous server. Any other file at `path` is left alone and reported as
+    /// an error by the bind.
+    pub fn bind(path: &Path) -> Result<UnixListener> {
+        let stale =
+            std::fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket());
+        if stale {
+            std::fs::remove_file(path)
+                .with_context(|| format!("Failed to remove the stale socket {}", path.display()))?;
+        }
+
+        UnixListener::bind(path).with_context(|| format!("Failed to listen on {}", path.display()))
+    }
+
+    /// Serve every connection on a thread of its own until the listener fails.
+    ///
+    /// A failing connection is reported on stderr; it never stops the server.
+    pub fn execute(&self, listener: &UnixListener, args: &ServeCommandArgs) -> Result<()> {
+        std::thread::scope(|scope| {
+            for stream in listener.incoming() {
+                let stream = stream.context("Failed to accept a connection")?;
+                scope.spawn(move || {
+                    let served = stream
+                        .set_write_timeout(Some(Self::WRITE_TIMEOUT))
+                        .context("Failed to set the write timeout")
+                        .and_then(|()| self.serve(stream, args));
+                    if let Err(error) = served {
+                        eprintln!("warning: {:#}", error);
+                    }
+                });
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Write the status to `stream` as a single line of JSON. With
+    /// `--interval`, keep writing a line per tick until the client hangs up.
+    pub fn serve<W: Write>(&self, mut stream: W, args: &ServeCommandArgs) -> Result<()> {
+        loop {
+            let status = self.status()?;
+            let line = serde_json::to_string(&status).context("Failed to serialize status")?;
+            if writeln!(stream, "{}", line).is_err() {
+                // The client hung up — that is how a stream ends.
+                return Ok(());
+            }
+
+            match &args.interval {
+                Some(interval) => std::thread::sleep(interval.duration),
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Compute the current status in a read-only transaction that is rolled back.
+    fn status(&self) -> Result<SessionStatus> {
+        if self.disabled {
+            return Ok(SessionStatus::disabled());
+        }
+
+        // A thread that panicked while holding the lock rolled its transaction
+        // back, so the database is still fine to use.
+        let mut database = self
+            .database
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        let tx = database.transaction_with(TransactionBehavior::Deferred)?;
+        let command = StatusCommand {
+            runner: None,
+            querier: Querier::new(&tx),
+            config: self.config.clone(),
+        };
+        // Dropping the transaction rolls it back.
+        command.execute()
+    }
+}
+
+/// WatchCommand prints the status as a line of text or JSON, and again
+/// whenever the remaining time or the state changed, until it is interrupted.
+///
+/// Like [`ServeCommand`] it opens a fresh transaction on every tick and never
+/// commits it, so that `start` and `stop` run from elsewhere are observed and
+/// watching never changes the session.
+pub struct WatchCommand<'d> {
+    /// Config is handed to the [`StatusCommand`] run on every tick.
+    pub config: ProgramConfig,
+    /// Disabled reports [`SessionStatus::disabled`] without reading the database.
+    pub disabled: bool,
+    /// Database is used to open a fresh transaction on every tick.
+    pub database: &'d mut Database,
+}
+
+impl<'d> WatchCommand<'d> {
+    /// The longest stretch slept before checking whether to stop.
+    const STOP_CHECK: std::time::Duration = std::time::Duration::from_millis(100);
+
+    /// Write a status line to `out` on every tick that changed it, until
+    /// `stop` is set (e.g. by a SIGINT handler). The first line is always written.
+    pub fn execute<W: Write>(
+        &mut self,
+        mut out: W,
+        args: &WatchCommandArgs,
+        stop: &AtomicBool,
+    ) -> Result<()> {
+        let mut last = None;
+        loop {
+            if let Some(line) = self.line(args, &mut last)? {
+                writeln!(out, "{}", line).context("Failed to write the status")?;
+                out.flush().context("Failed to write the status")?;
+            }
+
+            let deadline = std::time::Instant::now() + args.interval.duration;
+            loop {
+                if stop.load(Ordering::Relaxed) {
+                    return Ok(());
+                }
+                let left = deadline.saturating_duration_since(std::time::Instant::now());
+                if left.is_zero() {
+                    break;
+                }
+                std::thread::sleep(left.min(Self::STOP_CHECK));
+            }
+        }
+    }
+
+    /// Return the status line, or `None` when the kind, state, and remaining
+    /// time are the same as in `last`, which is updated.
+    fn line(
+        &mut self,
+        args: &WatchCommandArgs,
+        last: &mut Option<(String, String, i64)>,
+    ) -> Result<Option<String>> {
+        let status = self.status()?;
+        let key = (
+            status.kind.to_string(),
+            status.state.to_string(),
+            status.remaining_secs,
+        );
+        if last.as_ref() == Some(&key) {
+            return Ok(None);
+        }
+        *last = Some(key);
+
+        let template = self.config.template_for(StatusOutput::Text);
+        status_line(&status, args.output, template).map(Some)
+    }
+
+    /// Compute the current status in a read-only transaction that is rolled back.
+    fn status(&mut self) -> Result<SessionStatus> {
+        if self.disabled {
+            return Ok(SessionStatus::disabled());
+        }
+
+        let tx = self
+            .database
+            .transaction_with(TransactionBehavior::Deferred)?;
+        let command = StatusCommand {
+            runner: None,
+            querier: Querier::new(&tx),
+            config: self.config.clone(),
+        };
+        // Dropping the transaction rolls it back.
+        command.execute()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use chrono::Utc;
+
+    /// Open an in-memory database, apply the schema, and return it.
+    ///
+    /// Used by every test in this module as the starting point for a clean,
+    /// isolated database that is discarded when the test completes.
+    fn setup() -> Result<Database> {
+        let db = Database::open_in_memory()?;
+        db.migrate()?;
+        Ok(db)
+    }
+
+    /// Insert a session and the events returned by `f` into the DB.
+    ///
     /// `f` receives the persisted [`Session`] so that event constructors can
     /// reference the correct `session_id`. Return one event per seed state
     /// transition needed by the test.
@@ -385,444 +2959,3553 @@ mod tests {
         F: Fn(&Session) -> Vec<SessionEvent>,
     {
         let querier = Querier::new(db.connection());
-        let session = querier.insert_session(&InsertSessionArgs {
-            session: &Session::default(),
+        let session = querier.insert_session(&InsertSessionArgs {
+            session: &Session::default(),
+        })?;
+        for event in f(&session) {
+            querier.insert_session_event(&InsertSessionEventArgs {
+                session_event: &event,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Fetch all session events and invoke `f(index, event)` for each one.
+    ///
+    /// Events are ordered by `created_at DESC`, so index `0` is always the most
+    /// recent event. Use this to make per-event assertions without manually
+    /// fetching or enumerating the list.
+    fn for_each_event<F>(db: &Database, f: F) -> Result<()>
+    where
+        F: Fn(usize, &SessionEvent),
+    {
+        let querier = Querier::new(db.connection());
+        let args = &ListSessionEventsArgs::default();
+        let result = querier.list_session_events(args)?;
+        for (index, event) in result.iter().enumerate() {
+            f(index, event);
+        }
+        Ok(())
+    }
+
+    // --- StartCommand ---
+
+    #[test]
+    fn start_with_no_prior_events_starts_new_session() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: ProgramConfig::default(),
+        };
+        let args = &StartCommandArgs::default().with_config(&ProgramConfig::default());
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::Started);
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Started),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn start_uses_configured_focus_duration() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        let config = ProgramConfig {
+            focus_duration: std::time::Duration::from_secs(50 * 60),
+            ..ProgramConfig::default()
+        };
+
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: ProgramConfig::default(),
+        };
+        let args = &StartCommandArgs::default().with_config(&config);
+        let outcome = cmd.execute(args)?;
+
+        let querier = Querier::new(db.connection());
+        let params = GetSessionByIdArgs {
+            session_id: &outcome
+                .session_id
+                .expect("a session should have been started"),
+        };
+        let session = querier.get_session_by_id(&params)?;
+        assert_eq!(session.planned_duration, Duration::minutes(50));
+        Ok(())
+    }
+
+    #[test]
+    fn start_stores_duration_as_written() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: ProgramConfig::default(),
+        };
+        let args = &StartCommandArgs {
+            duration: Some("1h30m".parse()?),
+            ..Default::default()
+        };
+        let outcome = cmd.execute(args)?;
+
+        let querier = Querier::new(db.connection());
+        let params = GetSessionByIdArgs {
+            session_id: &outcome
+                .session_id
+                .expect("a session should have been started"),
+        };
+        let session = querier.get_session_by_id(&params)?;
+        assert_eq!(session.planned_duration, Duration::seconds(5400));
+        assert_eq!(session.planned_human.as_deref(), Some("1h30m"));
+        Ok(())
+    }
+
+    #[test]
+    fn start_with_tag_reports_it_in_status() -> Result<()> {
+        let db = setup()?;
+
+        let cmd = StartCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+            config: ProgramConfig::default(),
+        };
+        let args = &StartCommandArgs {
+            duration: Some("25m".parse()?),
+            tag: Some("writing".to_string()),
+            ..Default::default()
+        };
+        cmd.execute(args)?;
+
+        let cmd = StatusCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+            config: ProgramConfig::default(),
+        };
+        let status = cmd.execute()?;
+        assert_eq!(status.tag.as_deref(), Some("writing"));
+
+        let args = &StatusCommandArgs {
+            format: Some("{{ kind }} on {{ tag }}".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(cmd.render(&status, args)?, "focus on writing");
+        Ok(())
+    }
+
+    #[test]
+    fn start_when_session_is_running_with_other_tag_warns() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: ProgramConfig::default(),
+        };
+        let args = &StartCommandArgs {
+            tag: Some("writing".to_string()),
+            ..Default::default()
+        };
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::None);
+        assert_eq!(
+            outcome.warnings,
+            vec!["--tag writing ignored: a focus session is already running.".to_string()]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn start_without_resolved_duration_fails() -> Result<()> {
+        let args = &StartCommandArgs::default();
+        assert!(
+            Session::try_from(args).is_err(),
+            "Should refuse to guess a duration that was not resolved from the config"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn start_refuses_a_duration_longer_than_a_day() -> Result<()> {
+        let args = &StartCommandArgs {
+            duration: Some("1000000y".parse()?),
+            ..StartCommandArgs::default()
+        };
+        let error = Session::try_from(args).unwrap_err();
+        assert_eq!(error.to_string(), "A session can last at most 24h.");
+        Ok(())
+    }
+
+    #[test]
+    fn start_resolves_the_duration_from_config() -> Result<()> {
+        let db = setup()?;
+        let cmd = StartCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+            config: ProgramConfig {
+                focus_duration: std::time::Duration::from_secs(50 * 60),
+                ..ProgramConfig::default()
+            },
+        };
+        let outcome = cmd.execute(&StartCommandArgs::default())?;
+        assert_eq!(outcome.remaining_secs, 50 * 60);
+        Ok(())
+    }
+
+    #[test]
+    fn start_when_session_is_started_does_nothing() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            // Session is currently running — start should be a no-op.
+            vec![SessionEvent::started(session.id)]
+        })?;
+
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: ProgramConfig::default(),
+        };
+        let args = &StartCommandArgs::default().with_config(&ProgramConfig::default());
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::None);
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Started),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn start_when_session_is_resumed_does_nothing() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            // Session was resumed and is currently running — start should be a no-op.
+            vec![SessionEvent::resumed(session.id)]
+        })?;
+
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: ProgramConfig::default(),
+        };
+        let args = &StartCommandArgs::default().with_config(&ProgramConfig::default());
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::None);
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Resumed),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn start_when_session_is_paused_resumes_session() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            // Session is paused — start should resume it.
+            vec![SessionEvent::paused(session.id)]
+        })?;
+
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: ProgramConfig::default(),
+        };
+        let args = &StartCommandArgs::default().with_config(&ProgramConfig::default());
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::Resumed);
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Resumed),
+            1 => assert_eq!(event.kind, SessionEventKind::Paused),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn start_when_session_is_paused_with_conflicting_mode_warns() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        // The paused session is a focus session; `--mode break` cannot apply to it.
+        seed_event(&db, |session| vec![SessionEvent::paused(session.id)])?;
+
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: ProgramConfig::default(),
+        };
+        let args = &StartCommandArgs {
+            mode: Some(StartMode::Break),
+            ..Default::default()
+        }
+        .with_config(&ProgramConfig::default());
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::Resumed);
+        assert_eq!(outcome.kind, "focus");
+        assert_eq!(
+            outcome.warnings,
+            vec!["--mode break ignored: resuming the paused focus session.".to_string()]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn start_when_session_is_paused_with_matching_mode_does_not_warn() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| vec![SessionEvent::paused(session.id)])?;
+
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: ProgramConfig::default(),
+        };
+        let args = &StartCommandArgs {
+            mode: Some(StartMode::Focus),
+            ..Default::default()
+        }
+        .with_config(&ProgramConfig::default());
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::Resumed);
+        assert!(outcome.warnings.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn start_after_aborted_session_starts_new_session() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            // Previous session was aborted — start should begin a new one.
+            vec![SessionEvent::aborted(session.id)]
+        })?;
+
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: ProgramConfig::default(),
+        };
+        let args = &StartCommandArgs::default().with_config(&ProgramConfig::default());
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::Started);
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Started),
+            1 => assert_eq!(event.kind, SessionEventKind::Aborted),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn start_after_completed_session_starts_new_session() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            // Previous session completed naturally — start should begin a new one.
+            vec![SessionEvent::completed(session.id)]
+        })?;
+
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: ProgramConfig::default(),
+        };
+        let args = &StartCommandArgs::default().with_config(&ProgramConfig::default());
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::Started);
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Started),
+            1 => assert_eq!(event.kind, SessionEventKind::Completed),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn start_after_completed_focus_forces_break_when_enabled() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            vec![SessionEvent {
+                created_at: Utc::now() - Duration::minutes(2),
+                ..SessionEvent::completed(session.id)
+            }]
+        })?;
+
+        let config = ProgramConfig {
+            force_break_between_focus: std::time::Duration::from_secs(10 * 60),
+            ..ProgramConfig::default()
+        };
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: config.clone(),
+        };
+        let args = &StartCommandArgs {
+            duration: Some("50m".parse()?),
+            tag: Some("writing".to_string()),
+            ..StartCommandArgs::default()
+        };
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::Started);
+        assert_eq!(outcome.kind, "break");
+        assert!(outcome.message.contains("Run start again after the break"));
+        assert_eq!(outcome.remaining_secs, 5 * 60);
+        let session = cmd.get_session(&outcome.session_id.unwrap())?;
+        assert_eq!(session.tag.as_deref(), Some("writing"));
+        Ok(())
+    }
+
+    #[test]
+    fn start_after_completed_focus_starts_focus_when_disabled() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            vec![SessionEvent {
+                created_at: Utc::now() - Duration::minutes(2),
+                ..SessionEvent::completed(session.id)
+            }]
+        })?;
+
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: ProgramConfig::default(),
+        };
+        let args = &StartCommandArgs::default().with_config(&ProgramConfig::default());
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.kind, "focus");
+        assert_eq!(outcome.message, "Started a new focus session.");
+        Ok(())
+    }
+
+    #[test]
+    fn start_after_completed_focus_starts_focus_outside_window() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            vec![SessionEvent {
+                created_at: Utc::now() - Duration::minutes(30),
+                ..SessionEvent::completed(session.id)
+            }]
+        })?;
+
+        let config = ProgramConfig {
+            force_break_between_focus: std::time::Duration::from_secs(10 * 60),
+            ..ProgramConfig::default()
+        };
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: config.clone(),
+        };
+        let args = &StartCommandArgs::default().with_config(&config);
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.kind, "focus");
+        Ok(())
+    }
+
+    #[test]
+    fn start_after_completed_focus_starts_focus_by_default() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+        seed_event(&db, |session| vec![SessionEvent::completed(session.id)])?;
+
+        let config = ProgramConfig {
+            default_start_kind: DefaultStartKind::Focus,
+            ..ProgramConfig::default()
+        };
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: config.clone(),
+        };
+        let outcome = cmd.execute(&StartCommandArgs::default().with_config(&config))?;
+
+        assert_eq!(outcome.kind, "focus");
+        Ok(())
+    }
+
+    #[test]
+    fn start_after_completed_focus_starts_break_with_next() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+        seed_event(&db, |session| vec![SessionEvent::completed(session.id)])?;
+
+        let config = ProgramConfig {
+            default_start_kind: DefaultStartKind::Next,
+            ..ProgramConfig::default()
+        };
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: config.clone(),
+        };
+        let outcome = cmd.execute(&StartCommandArgs::default())?;
+
+        assert_eq!(outcome.kind, "break");
+        assert_eq!(outcome.message, "Started a new break session (5m).");
+        assert_eq!(outcome.remaining_secs, 5 * 60);
+
+        // The break completes; the next session is focus again.
+        let session_id = outcome.session_id.unwrap();
+        let session_event = &SessionEvent::completed(session_id);
+        cmd.querier
+            .insert_session_event(&InsertSessionEventArgs { session_event })?;
+        let outcome = cmd.execute(&StartCommandArgs::default())?;
+        assert_eq!(outcome.kind, "focus");
+        assert_eq!(outcome.remaining_secs, 25 * 60);
+        Ok(())
+    }
+
+    #[test]
+    fn start_with_next_keeps_an_explicit_duration_for_the_break() -> Result<()> {
+        let db = setup()?;
+        seed_event(&db, |session| vec![SessionEvent::completed(session.id)])?;
+
+        let cmd = StartCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+            config: ProgramConfig {
+                default_start_kind: DefaultStartKind::Next,
+                ..ProgramConfig::default()
+            },
+        };
+        let args = StartCommandArgs {
+            duration: Some("10m".parse()?),
+            ..StartCommandArgs::default()
+        };
+        let outcome = cmd.execute(&args)?;
+
+        assert_eq!(outcome.kind, "break");
+        assert_eq!(outcome.message, "Started a new break session (10m).");
+        assert_eq!(outcome.remaining_secs, 10 * 60);
+        Ok(())
+    }
+
+    #[test]
+    fn start_break_is_long_after_long_break_interval_focus_sessions() -> Result<()> {
+        let db = setup()?;
+        let config = ProgramConfig {
+            long_break_interval: 2,
+            ..ProgramConfig::default()
+        };
+        let cmd = StartCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+            config,
+        };
+        let args = StartCommandArgs {
+            mode: Some(StartMode::Break),
+            ..StartCommandArgs::default()
+        };
+        let complete = |outcome: &CommandOutcome| {
+            let session_event = &SessionEvent::completed(outcome.session_id.unwrap());
+            cmd.querier
+                .insert_session_event(&InsertSessionEventArgs { session_event })
+        };
+
+        // One completed focus session: a short break.
+        seed_event(&db, |session| vec![SessionEvent::completed(session.id)])?;
+        let outcome = cmd.execute(&cmd.with_config(args.clone())?)?;
+        assert_eq!(outcome.message, "Started a new break session (5m).");
+        complete(&outcome)?;
+
+        // Two: a long break.
+        seed_event(&db, |session| vec![SessionEvent::completed(session.id)])?;
+        let outcome = cmd.execute(&cmd.with_config(args.clone())?)?;
+        assert_eq!(outcome.message, "Started a new break session (15m).");
+        assert_eq!(outcome.remaining_secs, 15 * 60);
+        complete(&outcome)?;
+
+        // The count starts over after the long break.
+        seed_event(&db, |session| vec![SessionEvent::aborted(session.id)])?;
+        seed_event(&db, |session| vec![SessionEvent::completed(session.id)])?;
+        let outcome = cmd.execute(&cmd.with_config(args.clone())?)?;
+        assert_eq!(outcome.message, "Started a new break session (5m).");
+        complete(&outcome)?;
+
+        // An explicit --duration always wins.
+        seed_event(&db, |session| vec![SessionEvent::completed(session.id)])?;
+        let args = StartCommandArgs {
+            duration: Some("7m".parse()?),
+            ..args
+        };
+        let outcome = cmd.execute(&cmd.with_config(args)?)?;
+        assert_eq!(outcome.message, "Started a new break session (7m).");
+        Ok(())
+    }
+
+    #[test]
+    fn start_with_next_honors_explicit_mode() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+        seed_event(&db, |session| vec![SessionEvent::completed(session.id)])?;
+
+        let config = ProgramConfig {
+            default_start_kind: DefaultStartKind::Next,
+            ..ProgramConfig::default()
+        };
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: config.clone(),
+        };
+        let args = StartCommandArgs {
+            mode: Some(StartMode::Focus),
+            ..StartCommandArgs::default()
+        };
+        let outcome = cmd.execute(&args.with_config(&config))?;
+
+        assert_eq!(outcome.kind, "focus");
+        Ok(())
+    }
+
+    #[test]
+    fn start_reports_ends_at_as_start_plus_duration() -> Result<()> {
+        let db = setup()?;
+        let config = ProgramConfig::default();
+        let cmd = StartCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+            config: config.clone(),
+        };
+        let args = StartCommandArgs {
+            duration: Some("10m".parse()?),
+            ..StartCommandArgs::default()
+        };
+        let outcome = cmd.execute(&args.with_config(&config))?;
+
+        let started = cmd
+            .querier
+            .get_session_event_by_id(&GetSessionEventByIdArgs {
+                session_event_id: &outcome.session_event_id.unwrap(),
+            })?;
+        assert_eq!(
+            outcome.ends_at,
+            Some(started.created_at + Duration::minutes(10))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn start_allows_modes_listed_in_config() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+        let config = ProgramConfig {
+            allowed_modes: vec![StartMode::Focus],
+            ..ProgramConfig::default()
+        };
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: config.clone(),
+        };
+        let args = &StartCommandArgs::default().with_config(&config);
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::Started);
+        assert_eq!(outcome.kind, "focus");
+        Ok(())
+    }
+
+    #[test]
+    fn start_refuses_modes_missing_from_config() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+        let config = ProgramConfig {
+            allowed_modes: vec![StartMode::Focus],
+            ..ProgramConfig::default()
+        };
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: config.clone(),
+        };
+        let args = &StartCommandArgs {
+            mode: Some(StartMode::Break),
+            ..StartCommandArgs::default()
+        }
+        .with_config(&config);
+        let err = cmd.execute(args).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Starting a break session is not allowed (allowed_modes: focus)"
+        );
+        let sessions = cmd.querier.count_sessions(&CountSessionsArgs::default())?;
+        assert_eq!(sessions, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn start_does_not_force_a_disallowed_break() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            vec![SessionEvent {
+                created_at: Utc::now() - Duration::minutes(2),
+                ..SessionEvent::completed(session.id)
+            }]
+        })?;
+
+        let config = ProgramConfig {
+            force_break_between_focus: std::time::Duration::from_secs(10 * 60),
+            allowed_modes: vec![StartMode::Focus],
+            ..ProgramConfig::default()
+        };
+        let cmd = StartCommand {
+            runner: None,
+            querier,
+            config: config.clone(),
+        };
+        let args = &StartCommandArgs::default().with_config(&config);
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.kind, "focus");
+        Ok(())
+    }
+
+    // --- StopCommand ---
+
+    #[test]
+    fn stop_with_no_prior_events_does_nothing() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        let cmd = StopCommand {
+            runner: None,
+            querier,
+        };
+        let args = &StopCommandArgs::default();
+        cmd.execute(args)?;
+
+        for_each_event(&db, |index, _event| {
+            panic!("unexpected event at index {index}")
+        })
+    }
+
+    #[test]
+    fn stop_when_session_is_started_pauses_session() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            // Session is currently running — stop should pause it.
+            vec![SessionEvent::started(session.id)]
+        })?;
+
+        let cmd = StopCommand {
+            runner: None,
+            querier,
+        };
+        let args = &StopCommandArgs::default();
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::Paused);
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Paused),
+            1 => assert_eq!(event.kind, SessionEventKind::Started),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn stop_when_session_is_started_with_reset_aborts_session() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            // Session is currently running — stop --reset should abort it.
+            vec![SessionEvent::started(session.id)]
+        })?;
+
+        let cmd = StopCommand {
+            runner: None,
+            querier,
+        };
+        let args = &StopCommandArgs {
+            reset: true,
+            ..Default::default()
+        };
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::Aborted);
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Aborted),
+            1 => assert_eq!(event.kind, SessionEventKind::Started),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn stop_when_session_is_resumed_pauses_session() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            // Session was resumed and is running — stop should pause it.
+            vec![SessionEvent::resumed(session.id)]
+        })?;
+
+        let cmd = StopCommand {
+            runner: None,
+            querier,
+        };
+        let args = &StopCommandArgs::default();
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::Paused);
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Paused),
+            1 => assert_eq!(event.kind, SessionEventKind::Resumed),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn stop_when_session_is_resumed_with_reset_aborts_session() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            // Session was resumed and is running — stop --reset should abort it.
+            vec![SessionEvent::resumed(session.id)]
+        })?;
+
+        let cmd = StopCommand {
+            runner: None,
+            querier,
+        };
+        let args = &StopCommandArgs {
+            reset: true,
+            ..Default::default()
+        };
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::Aborted);
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Aborted),
+            1 => assert_eq!(event.kind, SessionEventKind::Resumed),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn stop_when_session_is_paused_does_nothing() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            // Session is paused — stop should be a no-op.
+            vec![SessionEvent::paused(session.id)]
+        })?;
+
+        let cmd = StopCommand {
+            runner: None,
+            querier,
+        };
+        let args = &StopCommandArgs::default();
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::None);
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Paused),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn stop_when_session_is_paused_with_reset_aborts_session() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            // Session is paused — stop --reset should abort it.
+            vec![SessionEvent::paused(session.id)]
+        })?;
+
+        let cmd = StopCommand {
+            runner: None,
+            querier,
+        };
+        let args = &StopCommandArgs {
+            reset: true,
+            ..Default::default()
+        };
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::Aborted);
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Aborted),
+            1 => assert_eq!(event.kind, SessionEventKind::Paused),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn stop_when_session_is_aborted_does_nothing() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            // Session was aborted — stop should be a no-op.
+            vec![SessionEvent::aborted(session.id)]
+        })?;
+
+        let cmd = StopCommand {
+            runner: None,
+            querier,
+        };
+        let args = &StopCommandArgs::default();
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::None);
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Aborted),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn stop_reports_how_the_session_ended() -> Result<()> {
+        let db = setup()?;
+        let cmd = StopCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+        };
+        let args = &StopCommandArgs::default();
+        let mut messages = vec![cmd.execute(args)?.message];
+
+        seed_event(&db, |session| vec![SessionEvent::aborted(session.id)])?;
+        messages.push(cmd.execute(args)?.message);
+        seed_event(&db, |session| vec![SessionEvent::completed(session.id)])?;
+        messages.push(cmd.execute(args)?.message);
+
+        assert_eq!(
+            messages,
+            [
+                "No active session found.",
+                "The focus session was already aborted.",
+                "The focus session already completed.",
+            ]
+        );
+
+        // --strict turns stopping a session that already ended into an error.
+        let error = cmd.execute(args)?.strict(true).unwrap_err();
+        assert_eq!(error.to_string(), "The focus session already completed.");
+        Ok(())
+    }
+
+    #[test]
+    fn stop_when_session_already_completed_reports_it() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        // `status` auto-completed the session right before `stop --reset` ran.
+        seed_event(&db, |session| {
+            vec![
+                SessionEvent::started(session.id),
+                SessionEvent::completed(session.id),
+            ]
+        })?;
+
+        let cmd = StopCommand {
+            runner: None,
+            querier,
+        };
+        for args in [
+            StopCommandArgs {
+                reset: true,
+                ..Default::default()
+            },
+            StopCommandArgs {
+                complete: true,
+                ..Default::default()
+            },
+        ] {
+            let outcome = cmd.execute(&args)?;
+            assert_eq!(outcome.action, CommandAction::None);
+            assert_eq!(outcome.message, "The focus session already completed.");
+            assert_eq!(outcome.kind, "focus");
+        }
+
+        for_each_event(&db, |index, event| {
+            if index == 0 {
+                assert_eq!(event.kind, SessionEventKind::Completed);
+            }
+        })?;
+        let params = &CountSessionEventsArgs::default();
+        assert_eq!(cmd.querier.count_session_events(params)?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn stop_when_session_is_started_with_complete_completes_session() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+
+        let cmd = StopCommand {
+            runner: None,
+            querier,
+        };
+        let args = &StopCommandArgs {
+            complete: true,
+            ..Default::default()
+        };
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::Completed);
+        for_each_event(&db, |index, event| match index {
+            0 => {
+                assert_eq!(event.kind, SessionEventKind::Completed);
+                assert_eq!(event.source, Some(CompletionSource::Manual));
+            }
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn stop_when_session_is_paused_with_complete_completes_session() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| vec![SessionEvent::paused(session.id)])?;
+
+        let cmd = StopCommand {
+            runner: None,
+            querier,
+        };
+        let args = &StopCommandArgs {
+            complete: true,
+            ..Default::default()
+        };
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::Completed);
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Completed),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn stop_when_session_is_aborted_with_complete_fails() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| vec![SessionEvent::aborted(session.id)])?;
+
+        let cmd = StopCommand {
+            runner: None,
+            querier,
+        };
+        let args = &StopCommandArgs {
+            complete: true,
+            ..Default::default()
+        };
+        assert!(
+            cmd.execute(args).is_err(),
+            "Should refuse to complete a session that is not active"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn stop_when_session_is_started_reports_elapsed_and_remaining() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        let now = Utc::now();
+        seed_event(&db, |session| {
+            // Session has been running for exactly five minutes.
+            vec![SessionEvent {
+                created_at: now - Duration::minutes(5),
+                ..SessionEvent::started(session.id)
+            }]
+        })?;
+
+        let cmd = StopCommand {
+            runner: None,
+            querier,
+        };
+        let args = &StopCommandArgs {
+            output: CommandOutput::Json,
+            ..Default::default()
+        };
+        let outcome = cmd.execute_at(args, now)?;
+
+        assert_eq!(outcome.action, CommandAction::Paused);
+        assert_eq!(outcome.kind, "focus");
+        assert_eq!(outcome.elapsed_secs, 5 * 60);
+        assert_eq!(outcome.remaining_secs, 20 * 60);
+        Ok(())
+    }
+
+    #[test]
+    fn stop_with_no_prior_events_reports_no_action() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        let cmd = StopCommand {
+            runner: None,
+            querier,
+        };
+        let args = &StopCommandArgs::default();
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::None);
+        assert_eq!(outcome.session_id, None);
+        assert_eq!(outcome.elapsed_secs, 0);
+        Ok(())
+    }
+
+    // --- ServeCommand ---
+
+    #[test]
+    fn serve_writes_status_as_json_line() -> Result<()> {
+        let mut db = setup()?;
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+
+        let cmd = ServeCommand {
+            config: ProgramConfig::default(),
+            disabled: false,
+            database: Mutex::new(&mut db),
+        };
+        let args = &ServeCommandArgs {
+            socket: PathBuf::from("unused.sock"),
+            interval: None,
+        };
+        let mut buffer = Vec::new();
+        cmd.serve(&mut buffer, args)?;
+
+        let output = String::from_utf8(buffer)?;
+        assert_eq!(output.lines().count(), 1);
+        let status: serde_json::Value = serde_json::from_str(&output)?;
+        assert_eq!(status["kind"], "focus");
+        assert_eq!(status["state"], "running");
+        Ok(())
+    }
+
+    #[test]
+    fn serve_does_not_record_auto_completion() -> Result<()> {
+        let mut db = setup()?;
+        seed_event(&db, |session| {
+            vec![SessionEvent {
+                created_at: Utc::now() - Duration::seconds(1501),
+                ..SessionEvent::started(session.id)
+            }]
+        })?;
+
+        let cmd = ServeCommand {
+            config: ProgramConfig::default(),
+            disabled: false,
+            database: Mutex::new(&mut db),
+        };
+        let args = &ServeCommandArgs {
+            socket: PathBuf::from("unused.sock"),
+            interval: None,
+        };
+        let mut buffer = Vec::new();
+        cmd.serve(&mut buffer, args)?;
+
+        let status: serde_json::Value = serde_json::from_slice(&buffer)?;
+        assert_eq!(status["state"], "completed");
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Started),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    // --- WatchCommand ---
+
+    #[test]
+    fn watch_prints_a_line_only_when_the_status_changed() -> Result<()> {
+        let mut db = setup()?;
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+
+        let mut cmd = WatchCommand {
+            config: ProgramConfig::default(),
+            disabled: false,
+            database: &mut db,
+        };
+        let args = &WatchCommandArgs {
+            interval: "1s".parse()?,
+            output: CommandOutput::Json,
+        };
+        let mut last = None;
+        let line = cmd.line(args, &mut last)?.context("a first line")?;
+        let status: serde_json::Value = serde_json::from_str(&line)?;
+        assert_eq!(status["state"], "running");
+
+        // Pausing stops the clock: the line after it is the last one.
+        let session = Querier::new(cmd.database.connection())
+            .list_sessions(&ListSessionsArgs::first())?
+            .remove(0);
+        let session_event = &SessionEvent::paused(session.id);
+        Querier::new(cmd.database.connection())
+            .insert_session_event(&InsertSessionEventArgs { session_event })?;
+        let line = cmd.line(args, &mut last)?.context("a line for the pause")?;
+        let status: serde_json::Value = serde_json::from_str(&line)?;
+        assert_eq!(status["state"], "paused");
+        assert_eq!(cmd.line(args, &mut last)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn watch_stops_once_interrupted() -> Result<()> {
+        let mut db = setup()?;
+        let mut cmd = WatchCommand {
+            config: ProgramConfig::default(),
+            disabled: false,
+            database: &mut db,
+        };
+        let args = &WatchCommandArgs {
+            interval: "1h".parse()?,
+            output: CommandOutput::Text,
+        };
+        let mut buffer = Vec::new();
+        cmd.execute(&mut buffer, args, &AtomicBool::new(true))?;
+
+        assert_eq!(String::from_utf8(buffer)?.lines().count(), 1);
+        Ok(())
+    }
+
+    // --- FocusOnCommand ---
+
+    #[test]
+    fn focus_on_makes_commands_target_the_pinned_session() -> Result<()> {
+        let db = setup()?;
+
+        // An older paused session and a newer running one.
+        seed_event(&db, |session| {
+            vec![
+                SessionEvent {
+                    created_at: Utc::now() - Duration::minutes(20),
+                    ..SessionEvent::started(session.id)
+                },
+                SessionEvent {
+                    created_at: Utc::now() - Duration::minutes(15),
+                    ..SessionEvent::paused(session.id)
+                },
+            ]
+        })?;
+        seed_event(&db, |session| {
+            vec![SessionEvent {
+                created_at: Utc::now() - Duration::minutes(10),
+                ..SessionEvent::started(session.id)
+            }]
+        })?;
+        let querier = Querier::new(db.connection());
+        let sessions = querier.list_sessions(&ListSessionsArgs::default())?;
+        let (latest, pinned) = (sessions[0].id, sessions[1].id);
+
+        let cmd = FocusOnCommand { querier };
+        let args = &FocusOnCommandArgs {
+            session: Some(pinned),
+            clear: false,
+        };
+        assert_eq!(
+            cmd.execute(args)?,
+            format!("Focusing on the focus session {pinned}.")
+        );
+
+        let status = StatusCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+            config: ProgramConfig::default(),
+        };
+        assert!(matches!(status.execute()?.state, SessionState::Paused));
+
+        let stop = StopCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+        };
+        let args = &StopCommandArgs {
+            reset: true,
+            ..StopCommandArgs::default()
+        };
+        let outcome = stop.execute(args)?;
+        assert_eq!(outcome.session_id, Some(pinned));
+        assert_eq!(outcome.action, CommandAction::Aborted);
+
+        // The pin ends with its session: commands follow the most recent one again.
+        assert!(matches!(status.execute()?.state, SessionState::Running));
+        let params = GetMetaArgs {
+            key: MetaKey::PinnedSessionId,
+        };
+        assert_eq!(cmd.querier.get_meta(&params)?, None);
+        let outcome = stop.execute(&StopCommandArgs::default())?;
+        assert_eq!(outcome.session_id, Some(latest));
+        Ok(())
+    }
+
+    #[test]
+    fn focus_on_makes_suspend_log_and_context_target_the_pinned_session() -> Result<()> {
+        let db = setup()?;
+
+        // An older running session and a newer completed one.
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+        seed_event(&db, |session| {
+            vec![
+                SessionEvent::started(session.id),
+                SessionEvent::completed(session.id),
+            ]
+        })?;
+        let querier = Querier::new(db.connection());
+        let sessions = querier.list_sessions(&ListSessionsArgs::default())?;
+        let pinned = sessions[1].id;
+        let args = &FocusOnCommandArgs {
+            session: Some(pinned),
+            clear: false,
+        };
+        FocusOnCommand { querier }.execute(args)?;
+
+        let context = ContextCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+            config_path: None,
+            database_path: None,
+        };
+        assert!(context.execute()?.active_session);
+
+        let suspend = SuspendCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+        };
+        let outcome = suspend.execute()?;
+        assert_eq!(outcome.action, CommandAction::Paused);
+        assert_eq!(outcome.session_id, Some(pinned));
+
+        let wake = ResumeFromSuspendCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+        };
+        let outcome = wake.execute()?;
+        assert_eq!(outcome.action, CommandAction::Resumed);
+        assert_eq!(outcome.session_id, Some(pinned));
+
+        let log = LogCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+        };
+        let args = &LogCommandArgs {
+            kind: LogEventKind::Paused,
+            output: CommandOutput::Text,
+        };
+        let outcome = log.execute(args)?;
+        assert_eq!(outcome.action, CommandAction::Paused);
+        assert_eq!(outcome.session_id, Some(pinned));
+        Ok(())
+    }
+
+    #[test]
+    fn focus_on_rejects_a_session_that_ended() -> Result<()> {
+        let db = setup()?;
+        seed_event(&db, |session| {
+            vec![
+                SessionEvent::started(session.id),
+                SessionEvent::aborted(session.id),
+            ]
+        })?;
+        let querier = Querier::new(db.connection());
+        let session = querier.list_sessions(&ListSessionsArgs::first())?.remove(0);
+
+        let cmd = FocusOnCommand { querier };
+        let args = &FocusOnCommandArgs {
+            session: Some(session.id),
+            clear: false,
+        };
+        let error = cmd.execute(args).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("The focus session {} already ended.", session.id)
+        );
+        Ok(())
+    }
+
+    // --- NoteCommand / NotesCommand ---
+
+    #[test]
+    fn notes_lists_todays_notes_in_order() -> Result<()> {
+        let db = setup()?;
+
+        for text in ["finished the parser", "reviewed the backlog"] {
+            let cmd = NoteCommand {
+                querier: Querier::new(db.connection()),
+            };
+            let args = &NoteCommandArgs {
+                text: text.to_string(),
+            };
+            assert_eq!(cmd.execute(args)?, "Added a note.");
+        }
+
+        let cmd = NotesCommand {
+            querier: Querier::new(db.connection()),
+        };
+        let args = &NotesCommandArgs {
+            today: true,
+            output: CommandOutput::Text,
+            time_format: TimeFormat::Rfc3339,
+        };
+        let notes = cmd.execute(args)?;
+        let texts = notes
+            .iter()
+            .map(|note| note.text.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(texts, ["finished the parser", "reviewed the backlog"]);
+
+        let output = cmd.render(&notes, args)?;
+        assert!(output
+            .lines()
+            .nth(1)
+            .unwrap()
+            .ends_with("  reviewed the backlog"));
+        Ok(())
+    }
+
+    #[test]
+    fn notes_json_writes_timestamps_in_time_format() -> Result<()> {
+        let db = setup()?;
+        let note = &Note {
+            created_at: "2024-01-02T03:04:05.678Z".parse()?,
+            ..Note::new("finished the parser")
+        };
+        let querier = Querier::new(db.connection());
+        querier.insert_note(&InsertNoteArgs { note })?;
+
+        let cmd = NotesCommand { querier };
+        let notes = cmd.execute(&NotesCommandArgs::default())?;
+        let expected = [
+            (
+                TimeFormat::Rfc3339,
+                serde_json::json!("2024-01-02T03:04:05.678Z"),
+            ),
+            (TimeFormat::EpochMs, serde_json::json!(1704164645678_i64)),
+            (TimeFormat::EpochS, serde_json::json!(1704164645)),
+        ];
+        for (time_format, created_at) in expected {
+            let args = &NotesCommandArgs {
+                output: CommandOutput::Json,
+                time_format,
+                ..NotesCommandArgs::default()
+            };
+            let output: serde_json::Value = serde_json::from_str(&cmd.render(&notes, args)?)?;
+            assert_eq!(output[0]["created_at"], created_at, "{time_format}");
+            assert_eq!(output[0]["text"], "finished the parser");
+        }
+        Ok(())
+    }
+
+    // --- ReplayCommand ---
+
+    #[test]
+    fn replay_elapsed_matches_status() -> Result<()> {
+        let db = setup()?;
+
+        // started → paused after 10m → resumed 10m later → completed 15m later.
+        seed_event(&db, |session| {
+            [
+                (SessionEvent::started(session.id), 40),
+                (SessionEvent::paused(session.id), 30),
+                (SessionEvent::resumed(session.id), 20),
+                (SessionEvent::completed(session.id), 5),
+            ]
+            .map(|(session_event, minutes)| SessionEvent {
+                created_at: Utc::now() - Duration::minutes(minutes),
+                ..session_event
+            })
+            .to_vec()
+        })?;
+
+        let cmd = ReplayCommand {
+            querier: Querier::new(db.connection()),
+        };
+        let replay = cmd.execute(&ReplayCommandArgs::default())?;
+        let elapsed = replay
+            .steps
+            .iter()
+            .map(|step| step.elapsed_secs)
+            .collect::<Vec<_>>();
+        assert_eq!(elapsed, [0, 10 * 60, 10 * 60, 25 * 60]);
+        assert_eq!(replay.paused_secs, 10 * 60);
+
+        let status = StatusCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+            config: ProgramConfig::default(),
+        }
+        .execute()?;
+        assert_eq!(replay.elapsed_secs, status.elapsed_secs);
+        assert_eq!(replay.remaining_secs, status.remaining_secs);
+        Ok(())
+    }
+
+    #[test]
+    fn replay_measures_an_open_interval_up_to_now() -> Result<()> {
+        let db = setup()?;
+
+        seed_event(&db, |session| {
+            vec![
+                SessionEvent {
+                    created_at: Utc::now() - Duration::minutes(40),
+                    ..SessionEvent::started(session.id)
+                },
+                SessionEvent {
+                    created_at: Utc::now() - Duration::minutes(10),
+                    ..SessionEvent::paused(session.id)
+                },
+            ]
+        })?;
+
+        let cmd = ReplayCommand {
+            querier: Querier::new(db.connection()),
+        };
+        let args = &ReplayCommandArgs {
+            output: CommandOutput::Json,
+            ..ReplayCommandArgs::default()
+        };
+        let replay = cmd.execute(args)?;
+        let output: serde_json::Value = serde_json::from_str(&cmd.render(&replay, args)?)?;
+
+        let last = &output["steps"][2];
+        assert_eq!(last["event"], serde_json::Value::Null);
+        assert_eq!(last["interval"], "paused");
+        assert_eq!(last["interval_secs"], 10 * 60);
+        assert_eq!(output["elapsed_secs"], 30 * 60);
+        assert_eq!(output["paused_secs"], 10 * 60);
+        Ok(())
+    }
+
+    #[test]
+    fn replay_without_session_fails() -> Result<()> {
+        let db = setup()?;
+        let cmd = ReplayCommand {
+            querier: Querier::new(db.connection()),
+        };
+
+        let err = cmd
+            .execute(&ReplayCommandArgs::default())
+            .err()
+            .expect("there is no session");
+        assert_eq!(err.to_string(), "No session to replay");
+        Ok(())
+    }
+
+    #[test]
+    fn note_rejects_empty_text() -> Result<()> {
+        let db = setup()?;
+        let cmd = NoteCommand {
+            querier: Querier::new(db.connection()),
+        };
+        let args = &NoteCommandArgs {
+            text: "  ".to_string(),
+        };
+        assert!(cmd.execute(args).is_err());
+        Ok(())
+    }
+
+    // --- ListCommand ---
+
+    #[test]
+    fn list_replays_the_state_of_each_session() -> Result<()> {
+        let db = setup()?;
+        seed_event(&db, |session| {
+            vec![
+                SessionEvent {
+                    created_at: Utc::now() - Duration::hours(2),
+                    ..SessionEvent::started(session.id)
+                },
+                SessionEvent {
+                    created_at: Utc::now() - Duration::hours(1),
+                    ..SessionEvent::completed(session.id)
+                },
+            ]
+        })?;
+        seed_event(&db, |session| {
+            vec![
+                SessionEvent {
+                    created_at: Utc::now() - Duration::minutes(10),
+                    ..SessionEvent::started(session.id)
+                },
+                SessionEvent {
+                    created_at: Utc::now() - Duration::minutes(5),
+                    ..SessionEvent::paused(session.id)
+                },
+            ]
+        })?;
+
+        let cmd = ListCommand {
+            querier: Querier::new(db.connection()),
+        };
+        let args = &ListCommandArgs {
+            limit: 10,
+            kind: None,
+            output: CommandOutput::Json,
+        };
+        let records = cmd.execute(args)?;
+        let output: serde_json::Value = serde_json::from_str(&cmd.render(&records, args)?)?;
+
+        let states = output
+            .as_array()
+            .context("an array")?
+            .iter()
+            .map(|record| record["state"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(states, ["paused", "completed"]);
+        assert_eq!(output[0]["elapsed_secs"], 5 * 60);
+
+        let args = &ListCommandArgs {
+            limit: 1,
+            kind: None,
+            output: CommandOutput::Text,
+        };
+        let records = cmd.execute(args)?;
+        let output = cmd.render(&records, args)?;
+        assert!(output.starts_with("focus  paused     25:00  "), "{output}");
+        assert_eq!(output.lines().count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn list_renders_an_empty_history_as_nothing() -> Result<()> {
+        let db = setup()?;
+        let cmd = ListCommand {
+            querier: Querier::new(db.connection()),
+        };
+
+        let args = &ListCommandArgs {
+            limit: 10,
+            kind: None,
+            output: CommandOutput::Text,
+        };
+        assert_eq!(cmd.render(&cmd.execute(args)?, args)?, "");
+
+        let args = &ListCommandArgs {
+            limit: 10,
+            kind: None,
+            output: CommandOutput::Json,
+        };
+        assert_eq!(cmd.render(&cmd.execute(args)?, args)?, "[]");
+        Ok(())
+    }
+
+    #[test]
+    fn list_kind_lists_only_sessions_of_that_kind() -> Result<()> {
+        let db = setup()?;
+        seed_event(&db, |session| vec![SessionEvent::completed(session.id)])?;
+        let querier = Querier::new(db.connection());
+        let session = &Session {
+            kind: SessionKind::Break,
+            ..Session::default()
+        };
+        querier.insert_session(&InsertSessionArgs { session })?;
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+
+        let cmd = ListCommand { querier };
+        let args = &ListCommandArgs {
+            limit: 10,
+            kind: Some(StartMode::Break),
+            output: CommandOutput::Text,
+        };
+        let records = cmd.execute(args)?;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].session_id, session.id);
+
+        let args = &ListCommandArgs {
+            kind: Some(StartMode::Focus),
+            ..*args
+        };
+        let records = cmd.execute(args)?;
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|r| r.kind == SessionKind::Focus));
+        Ok(())
+    }
+
+    // --- GcCommand ---
+
+    #[test]
+    fn gc_removes_short_pauses_and_keeps_the_elapsed_time() -> Result<()> {
+        let db = setup()?;
+
+        // started → 2s pause at 5m → 30s pause at 10m → completed at 20m.
+        let now = Utc::now() - Duration::hours(1);
+        let at = |secs: i64| now + Duration::seconds(secs);
+        seed_event(&db, |session| {
+            [
+                (SessionEvent::started(session.id), 0),
+                (SessionEvent::paused(session.id), 300),
+                (SessionEvent::resumed(session.id), 302),
+                (SessionEvent::paused(session.id), 600),
+                (SessionEvent::resumed(session.id), 630),
+                (SessionEvent::completed(session.id), 1200),
+            ]
+            .map(|(session_event, secs)| SessionEvent {
+                created_at: at(secs),
+                ..session_event
+            })
+            .to_vec()
+        })?;
+
+        let querier = Querier::new(db.connection());
+        let session = querier.list_sessions(&ListSessionsArgs::first())?.remove(0);
+        let args = &SessionSummaryArgs {
+            session_id: &session.id,
+        };
+        let before = querier.session_summary(args)?;
+
+        let cmd = GcCommand { querier };
+        let gc_args = &GcCommandArgs {
+            threshold: "5s".parse()?,
+        };
+        let message = cmd.execute(gc_args)?;
+        assert_eq!(message, "Removed 1 short pause(s) from 1 session(s).");
+
+        let after = cmd.querier.session_summary(args)?;
+        assert_eq!(after.elapsed, before.elapsed);
+        assert_eq!(after.final_state, Some(SessionEventKind::Completed));
+        assert_eq!(after.num_pauses, 1);
+        assert_eq!(after.paused, Duration::seconds(30));
+
+        let params = ListSessionEventsArgs::with_session_id(session.id);
+        let kinds = cmd
+            .querier
+            .list_session_events(&params)?
+            .into_iter()
+            .rev()
+            .map(|event| (event.kind, event.created_at))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                (SessionEventKind::Started, at(2)),
+                (SessionEventKind::Paused, at(600)),
+                (SessionEventKind::Resumed, at(630)),
+                (SessionEventKind::Completed, at(1200)),
+            ]
+        );
+
+        // Nothing is left to remove the second time around.
+        let message = cmd.execute(gc_args)?;
+        assert_eq!(message, "No pauses shorter than the threshold.");
+        Ok(())
+    }
+
+    #[test]
+    fn gc_keeps_a_short_pause_ended_by_the_latest_event() -> Result<()> {
+        let db = setup()?;
+
+        seed_event(&db, |session| {
+            vec![
+                SessionEvent {
+                    created_at: Utc::now() - Duration::minutes(5),
+                    ..SessionEvent::started(session.id)
+                },
+                SessionEvent {
+                    created_at: Utc::now() - Duration::seconds(3),
+                    ..SessionEvent::paused(session.id)
+                },
+                SessionEvent {
+                    created_at: Utc::now() - Duration::seconds(2),
+                    ..SessionEvent::resumed(session.id)
+                },
+            ]
+        })?;
+
+        let cmd = GcCommand {
+            querier: Querier::new(db.connection()),
+        };
+        let args = &GcCommandArgs {
+            threshold: "5s".parse()?,
+        };
+        assert_eq!(cmd.execute(args)?, "No pauses shorter than the threshold.");
+        for_each_event(&db, |index, event| {
+            if index == 0 {
+                assert_eq!(event.kind, SessionEventKind::Resumed);
+            }
+        })?;
+        Ok(())
+    }
+
+    // --- EditConfigCommand ---
+
+    #[test]
+    fn edit_config_creates_missing_file_from_template() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("pomodoro-config-{}.toml", Uuid::now_v7()));
+
+        let cmd = EditConfigCommand {
+            path: path.clone(),
+            editor: "true".to_string(),
+        };
+        cmd.execute()?;
+
+        let content = std::fs::read_to_string(&path)?;
+        std::fs::remove_file(&path).ok();
+        assert_eq!(content, DEFAULT_CONFIG_TEMPLATE);
+        Ok(())
+    }
+
+    #[test]
+    fn edit_config_reports_invalid_file() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("pomodoro-config-{}.toml", Uuid::now_v7()));
+
+        // The "editor" appends a duration humantime cannot parse.
+        let cmd = EditConfigCommand {
+            path: path.clone(),
+            editor: "echo 'focus_duration = \"soon\"' >>".to_string(),
+        };
+        let result = cmd.execute();
+        std::fs::remove_file(&path).ok();
+
+        let error = result.expect_err("an invalid configuration must be reported");
+        assert!(error.to_string().starts_with("Invalid configuration file"));
+        Ok(())
+    }
+
+    // --- InitCommand ---
+
+    #[test]
+    fn init_creates_missing_files_and_keeps_existing_ones() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("pomodoro-init-{}", Uuid::now_v7()));
+        let cmd = InitCommand {
+            config_path: dir.join("config.toml"),
+            hooks_path: dir.join("hooks"),
+        };
+
+        let output = cmd.execute(&InitCommandArgs::default())?;
+        assert_eq!(output.lines().count(), 3);
+        assert!(output.lines().all(|line| line.starts_with("Created ")));
+        let config = ProgramConfig::load_from(&cmd.config_path)?;
+        assert_eq!(
+            config.focus_duration,
+            ProgramConfig::default().focus_duration
+        );
+        let hook = cmd.hooks_path.join("stop");
+        let mode =
+            std::os::unix::fs::PermissionsExt::mode(&std::fs::metadata(&hook)?.permissions());
+        assert_eq!(mode & 0o111, 0, "example hooks are not enabled");
+
+        // A second run changes nothing, unless forced.
+        std::fs::write(&cmd.config_path, "focus_duration = \"50m\"\n")?;
+        let output = cmd.execute(&InitCommandArgs::default())?;
+        assert!(output.lines().all(|line| line.starts_with("Kept ")));
+        assert_eq!(
+            std::fs::read_to_string(&cmd.config_path)?,
+            "focus_duration = \"50m\"\n"
+        );
+
+        let output = cmd.execute(&InitCommandArgs { force: true })?;
+        assert!(output.lines().all(|line| line.starts_with("Overwrote ")));
+        assert_eq!(
+            std::fs::read_to_string(&cmd.config_path)?,
+            DEFAULT_CONFIG_TEMPLATE
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn init_keeps_an_existing_json_config() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("pomodoro-init-{}", Uuid::now_v7()));
+        let cmd = InitCommand {
+            config_path: dir.join("config.toml"),
+            hooks_path: dir.join("hooks"),
+        };
+        let json = dir.join("config.json");
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(&json, r#"{"focus_duration": "50m"}"#)?;
+
+        let output = cmd.execute(&InitCommandArgs { force: true })?;
+        assert_eq!(
+            output.lines().next(),
+            Some(
+                format!(
+                    "Kept {} (not creating {})",
+                    json.display(),
+                    cmd.config_path.display()
+                )
+                .as_str()
+            )
+        );
+        assert!(!cmd.config_path.exists());
+        assert_eq!(
+            std::fs::read_to_string(&json)?,
+            r#"{"focus_duration": "50m"}"#
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    // --- CommandOutcome ---
+
+    #[test]
+    fn strict_rejects_outcome_without_action() {
+        let outcome = CommandOutcome::new(CommandAction::None, "No active session found.".into());
+        let error = outcome.strict(true).expect_err("nothing changed");
+        assert_eq!(error.to_string(), "No active session found.");
+    }
+
+    #[test]
+    fn strict_accepts_outcome_with_action() -> Result<()> {
+        let outcome = CommandOutcome::new(CommandAction::Paused, "Paused.".into());
+        assert_eq!(outcome.strict(true)?.action, CommandAction::Paused);
+
+        let outcome = CommandOutcome::new(CommandAction::None, "Nothing.".into());
+        assert_eq!(outcome.strict(false)?.action, CommandAction::None);
+        Ok(())
+    }
+
+    // --- LogCommand ---
+
+    #[test]
+    fn log_valid_transition_appends_event() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+
+        let cmd = LogCommand {
+            runner: None,
+            querier,
+        };
+        let args = &LogCommandArgs {
+            kind: LogEventKind::Paused,
+            output: CommandOutput::Text,
+        };
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::Paused);
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Paused),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn log_invalid_transition_fails() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+
+        let cmd = LogCommand {
+            runner: None,
+            querier,
+        };
+        let args = &LogCommandArgs {
+            kind: LogEventKind::Resumed,
+            output: CommandOutput::Text,
+        };
+        let error = cmd
+            .execute(args)
+            .expect_err("a running session cannot resume");
+        assert_eq!(
+            error.to_string(),
+            "Cannot log a resumed event after started"
+        );
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Started),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    // --- PauseCommand / ResumeCommand ---
+
+    #[test]
+    fn pause_then_resume_records_paused_and_resumed() -> Result<()> {
+        let db = setup()?;
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+
+        let cmd = PauseCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+        };
+        let outcome = cmd.execute()?;
+        assert_eq!(outcome.action, CommandAction::Paused);
+        assert_eq!(outcome.message, "Paused the focus session.");
+
+        let cmd = ResumeCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+        };
+        let outcome = cmd.execute()?;
+        assert_eq!(outcome.action, CommandAction::Resumed);
+        assert_eq!(outcome.message, "Resumed the focus session.");
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Resumed),
+            1 => assert_eq!(event.kind, SessionEventKind::Paused),
+            2 => assert_eq!(event.kind, SessionEventKind::Started),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn pause_and_resume_do_nothing_in_the_wrong_state() -> Result<()> {
+        let db = setup()?;
+
+        let pause = PauseCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+        };
+        let resume = ResumeCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+        };
+        assert_eq!(pause.execute()?.message, "No session to pause.");
+        assert_eq!(resume.execute()?.message, "No session to resume.");
+
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+        let outcome = resume.execute()?;
+        assert_eq!(outcome.action, CommandAction::None);
+        assert_eq!(outcome.message, "The focus session is already running.");
+
+        seed_event(&db, |session| vec![SessionEvent::paused(session.id)])?;
+        let outcome = pause.execute()?;
+        assert_eq!(outcome.action, CommandAction::None);
+        assert_eq!(outcome.message, "The focus session is already paused.");
+
+        seed_event(&db, |session| vec![SessionEvent::completed(session.id)])?;
+        let outcome = resume.execute()?;
+        assert_eq!(outcome.action, CommandAction::None);
+        assert_eq!(outcome.message, "The focus session already completed.");
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Completed),
+            1 => assert_eq!(event.kind, SessionEventKind::Paused),
+            2 => assert_eq!(event.kind, SessionEventKind::Started),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    // --- CancelCommand ---
+
+    #[test]
+    fn cancel_aborts_the_running_or_paused_session() -> Result<()> {
+        let db = setup()?;
+        let cmd = CancelCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+        };
+
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+        let outcome = cmd.execute()?;
+        assert_eq!(outcome.action, CommandAction::Aborted);
+        assert_eq!(outcome.message, "Cancelled the focus session.");
+
+        seed_event(&db, |session| vec![SessionEvent::paused(session.id)])?;
+        assert_eq!(cmd.execute()?.action, CommandAction::Aborted);
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Aborted),
+            1 => assert_eq!(event.kind, SessionEventKind::Paused),
+            2 => assert_eq!(event.kind, SessionEventKind::Aborted),
+            3 => assert_eq!(event.kind, SessionEventKind::Started),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn cancel_does_nothing_without_an_active_session() -> Result<()> {
+        let db = setup()?;
+        let cmd = CancelCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+        };
+
+        let outcome = cmd.execute()?;
+        assert_eq!(outcome.action, CommandAction::None);
+        assert_eq!(outcome.message, "No session to cancel.");
+
+        seed_event(&db, |session| vec![SessionEvent::aborted(session.id)])?;
+        let outcome = cmd.execute()?;
+        assert_eq!(outcome.action, CommandAction::None);
+        assert_eq!(outcome.message, "The focus session was already aborted.");
+
+        seed_event(&db, |session| vec![SessionEvent::completed(session.id)])?;
+        let outcome = cmd.execute()?;
+        assert_eq!(outcome.action, CommandAction::None);
+        assert_eq!(outcome.message, "The focus session already completed.");
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Completed),
+            1 => assert_eq!(event.kind, SessionEventKind::Aborted),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    // --- ExtendCommand ---
+
+    #[test]
+    fn extend_adds_time_without_changing_elapsed_time() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        // Ran for 10 minutes, then was paused.
+        seed_event(&db, |session| {
+            vec![
+                SessionEvent {
+                    created_at: Utc::now() - Duration::minutes(10),
+                    ..SessionEvent::started(session.id)
+                },
+                SessionEvent::paused(session.id),
+            ]
+        })?;
+        let session = querier.list_sessions(&ListSessionsArgs::first())?.remove(0);
+        let params = &SessionSummaryArgs {
+            session_id: &session.id,
+        };
+        let before = querier.session_summary(params)?;
+
+        let cmd = ExtendCommand { querier };
+        let args = &ExtendCommandArgs {
+            duration: "10m".parse()?,
+            output: CommandOutput::Text,
+        };
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::Extended);
+        assert_eq!(outcome.remaining_secs, 25 * 60);
+        assert_eq!(
+            outcome.message,
+            "Extended the focus session by 10m; 25m left."
+        );
+
+        let querier = Querier::new(db.connection());
+        let after = querier.session_summary(params)?;
+        assert_eq!(after.elapsed, before.elapsed);
+        assert_eq!(after.final_state, Some(SessionEventKind::Paused));
+        let session = querier.get_session_by_id(&GetSessionByIdArgs {
+            session_id: &session.id,
+        })?;
+        assert_eq!(session.planned_duration, Duration::minutes(35));
+        assert_eq!(session.planned_human.as_deref(), Some("35m"));
+        Ok(())
+    }
+
+    #[test]
+    fn extend_refuses_a_session_that_ended() -> Result<()> {
+        let db = setup()?;
+        let cmd = ExtendCommand {
+            querier: Querier::new(db.connection()),
+        };
+        let args = &ExtendCommandArgs {
+            duration: "10m".parse()?,
+            output: CommandOutput::Text,
+        };
+
+        let error = cmd.execute(args).unwrap_err();
+        assert_eq!(error.to_string(), "No session to extend.");
+
+        seed_event(&db, |session| vec![SessionEvent::aborted(session.id)])?;
+        let error = cmd.execute(args).unwrap_err();
+        assert_eq!(error.to_string(), "The focus session was already aborted.");
+
+        seed_event(&db, |session| vec![SessionEvent::completed(session.id)])?;
+        let error = cmd.execute(args).unwrap_err();
+        assert_eq!(error.to_string(), "The focus session already completed.");
+        Ok(())
+    }
+
+    #[test]
+    fn extend_refuses_to_last_longer_than_a_day() -> Result<()> {
+        let db = setup()?;
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+        let cmd = ExtendCommand {
+            querier: Querier::new(db.connection()),
+        };
+        let args = &ExtendCommandArgs {
+            duration: "1000000y".parse()?,
+            output: CommandOutput::Text,
+        };
+
+        let error = cmd.execute(args).unwrap_err();
+        assert_eq!(error.to_string(), "A session can last at most 24h.");
+        Ok(())
+    }
+
+    // --- InterruptCommand ---
+
+    #[test]
+    fn interrupt_records_an_interruption_without_changing_the_state() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        // Ran for 10 minutes, and still runs.
+        seed_event(&db, |session| {
+            vec![SessionEvent {
+                created_at: Utc::now() - Duration::minutes(10),
+                ..SessionEvent::started(session.id)
+            }]
+        })?;
+        let session = querier.list_sessions(&ListSessionsArgs::first())?.remove(0);
+        let params = &SessionSummaryArgs {
+            session_id: &session.id,
+        };
+        let before = querier.session_summary(params)?;
+
+        let cmd = InterruptCommand {
+            runner: None,
+            querier,
+        };
+        let args = &InterruptCommandArgs {
+            external: true,
+            note: Some("phone call".to_string()),
+            ..Default::default()
+        };
+        let outcome = cmd.execute(args)?;
+
+        assert_eq!(outcome.action, CommandAction::Interrupted);
+        assert_eq!(
+            outcome.message,
+            "Recorded an external interruption of the focus session."
+        );
+
+        let querier = Querier::new(db.connection());
+        let after = querier.session_summary(params)?;
+        assert_eq!(after.final_state, Some(SessionEventKind::Started));
+        assert_eq!(after.num_interruptions, 1);
+        // The session kept running: the interruption did not stop the clock.
+        assert!(after.elapsed >= before.elapsed);
+        assert!(after.elapsed - before.elapsed < Duration::seconds(5));
+
+        let params = &ListSessionEventsArgs {
+            interruptions: true,
+            ..ListSessionEventsArgs::first()
+        };
+        let event = querier.list_session_events(params)?.remove(0);
+        assert_eq!(event.kind, SessionEventKind::Interrupted);
+        assert_eq!(
+            event.interruption_source,
+            Some(InterruptionSource::External)
+        );
+        assert_eq!(event.note.as_deref(), Some("phone call"));
+        Ok(())
+    }
+
+    #[test]
+    fn status_counts_interruptions() -> Result<()> {
+        let db = setup()?;
+        seed_event(&db, |session| {
+            vec![
+                SessionEvent::started(session.id),
+                SessionEvent::interrupted(session.id, InterruptionSource::Internal, None),
+                SessionEvent::interrupted(session.id, InterruptionSource::External, None),
+            ]
+        })?;
+
+        let cmd = StatusCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+            config: ProgramConfig::default(),
+        };
+        let status = cmd.execute()?;
+        assert!(matches!(status.state, SessionState::Running));
+        assert_eq!(status.interruption_count, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn interrupt_does_nothing_without_an_active_session() -> Result<()> {
+        let db = setup()?;
+        let cmd = InterruptCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+        };
+        let args = &InterruptCommandArgs::default();
+
+        let outcome = cmd.execute(args)?;
+        assert_eq!(outcome.action, CommandAction::None);
+        assert_eq!(outcome.message, "No session to interrupt.");
+
+        seed_event(&db, |session| vec![SessionEvent::completed(session.id)])?;
+        let outcome = cmd.execute(args)?;
+        assert_eq!(outcome.action, CommandAction::None);
+        assert_eq!(outcome.message, "The focus session already completed.");
+        for_each_event(&db, |_, event| {
+            assert_ne!(event.kind, SessionEventKind::Interrupted)
+        })?;
+        Ok(())
+    }
+
+    // --- SuspendCommand / ResumeFromSuspendCommand ---
+
+    #[test]
+    fn suspend_when_session_is_started_pauses_session() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+
+        let cmd = SuspendCommand {
+            runner: None,
+            querier,
+        };
+        cmd.execute()?;
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Paused),
+            1 => assert_eq!(event.kind, SessionEventKind::Started),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn suspend_when_session_is_paused_does_nothing() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| vec![SessionEvent::paused(session.id)])?;
+
+        let cmd = SuspendCommand {
+            runner: None,
+            querier,
+        };
+        cmd.execute()?;
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Paused),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn resume_from_suspend_after_suspend_resumes_session() -> Result<()> {
+        let db = setup()?;
+
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+
+        let cmd = SuspendCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+        };
+        cmd.execute()?;
+
+        let cmd = ResumeFromSuspendCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+        };
+        cmd.execute()?;
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Resumed),
+            1 => assert_eq!(event.kind, SessionEventKind::Paused),
+            2 => assert_eq!(event.kind, SessionEventKind::Started),
+            _ => panic!("unexpected event at index {index}"),
+        })?;
+
+        // The suspend marker is consumed by the resume.
+        let querier = Querier::new(db.connection());
+        let params = GetMetaArgs {
+            key: MetaKey::SuspendedEventId,
+        };
+        assert_eq!(querier.get_meta(&params)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn resume_from_suspend_after_manual_pause_does_nothing() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            // Session was paused by the user, not by a suspend.
+            vec![
+                SessionEvent::started(session.id),
+                SessionEvent::paused(session.id),
+            ]
+        })?;
+
+        let cmd = ResumeFromSuspendCommand {
+            runner: None,
+            querier,
+        };
+        cmd.execute()?;
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Paused),
+            1 => assert_eq!(event.kind, SessionEventKind::Started),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    // --- StatusCommand ---
+
+    #[test]
+    fn status_when_session_is_already_completed_does_not_insert_another_completed() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            // Session was already completed — status must not insert a second completed event.
+            vec![
+                SessionEvent::started(session.id),
+                SessionEvent::completed(session.id),
+            ]
+        })?;
+
+        let cmd = StatusCommand {
+            runner: None,
+            querier,
+            config: ProgramConfig::default(),
+        };
+        let args = &StatusCommandArgs::default();
+        let status = cmd.execute()?;
+        cmd.render(&status, args)?;
+
+        for_each_event(&db, |index, event| match index {
+            0 => assert_eq!(event.kind, SessionEventKind::Completed),
+            1 => assert_eq!(event.kind, SessionEventKind::Started),
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn status_completes_session_within_auto_complete_tolerance() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        // 1s left of the default 25-minute session.
+        seed_event(&db, |session| {
+            vec![SessionEvent {
+                created_at: Utc::now() - Duration::seconds(1500 - 1),
+                ..SessionEvent::started(session.id)
+            }]
         })?;
-        for event in f(&session) {
-            querier.insert_session_event(&InsertSessionEventArgs {
-                session_event: &event,
-            })?;
-        }
+
+        let cmd = StatusCommand {
+            runner: None,
+            querier,
+            config: ProgramConfig {
+                auto_complete_tolerance: std::time::Duration::from_secs(2),
+                ..ProgramConfig::default()
+            },
+        };
+        let status = cmd.execute()?;
+
+        assert!(matches!(status.state, SessionState::Completed));
+        assert_eq!(status.remaining_secs, 0);
+        for_each_event(&db, |index, event| match index {
+            0 => {
+                assert_eq!(event.kind, SessionEventKind::Completed);
+                assert_eq!(event.source, Some(CompletionSource::Auto));
+            }
+            _ => panic!("unexpected event at index {index}"),
+        })
+    }
+
+    #[test]
+    fn status_reports_totals_only_when_included() -> Result<()> {
+        let db = setup()?;
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+
+        let cmd = StatusCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+            config: ProgramConfig::default(),
+        };
+        let mut status = cmd.execute()?;
+        let value = serde_json::to_value(&status)?;
+        assert!(value.get("total_sessions").is_none());
+        assert!(value.get("total_events").is_none());
+
+        cmd.include_totals(&mut status)?;
+        let value = serde_json::to_value(&status)?;
+        assert_eq!(value["total_sessions"], 1);
+        assert_eq!(value["total_events"], 1);
         Ok(())
     }
 
-    /// Fetch all session events and invoke `f(index, event)` for each one.
-    ///
-    /// Events are ordered by `created_at DESC`, so index `0` is always the most
-    /// recent event. Use this to make per-event assertions without manually
-    /// fetching or enumerating the list.
-    fn for_each_event<F>(db: &Database, f: F) -> Result<()>
-    where
-        F: Fn(usize, &SessionEvent),
-    {
-        let querier = Querier::new(db.connection());
-        let args = &ListSessionEventsArgs::default();
-        let result = querier.list_session_events(args)?;
-        for (index, event) in result.iter().enumerate() {
-            f(index, event);
-        }
+    #[test]
+    fn status_uses_session_duration_instead_of_config() -> Result<()> {
+        let db = setup()?;
+        let config = ProgramConfig::default();
+
+        let cmd = StartCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+            config: config.clone(),
+        };
+        let args = StartCommandArgs {
+            duration: Some("50m".parse()?),
+            ..Default::default()
+        };
+        cmd.execute(&args.with_config(&config))?;
+
+        let cmd = StatusCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+            config,
+        };
+        let status = cmd.execute()?;
+
+        assert!(matches!(status.state, SessionState::Running));
+        assert_eq!(status.planned_secs, 50 * 60);
+        // Measured against the 50 minutes, not the configured 25.
+        assert!(status.remaining_secs > 49 * 60);
         Ok(())
     }
 
-    // --- StartCommand ---
+    #[test]
+    fn status_reports_break_overdue_after_reminder() -> Result<()> {
+        let db = setup()?;
+
+        seed_event(&db, |session| {
+            vec![
+                SessionEvent {
+                    created_at: Utc::now() - Duration::minutes(55),
+                    ..SessionEvent::started(session.id)
+                },
+                SessionEvent {
+                    created_at: Utc::now() - Duration::minutes(30),
+                    ..SessionEvent::completed(session.id)
+                },
+            ]
+        })?;
+
+        let status = |break_reminder_after: u64| {
+            let cmd = StatusCommand {
+                runner: None,
+                querier: Querier::new(db.connection()),
+                config: ProgramConfig {
+                    break_reminder_after: std::time::Duration::from_secs(break_reminder_after),
+                    ..ProgramConfig::default()
+                },
+            };
+            cmd.execute()
+        };
+
+        assert!(status(10 * 60)?.break_overdue);
+        assert!(!status(60 * 60)?.break_overdue);
+        // Disabled by default.
+        assert!(!status(0)?.break_overdue);
+        Ok(())
+    }
 
     #[test]
-    fn start_with_no_prior_events_starts_new_session() -> Result<()> {
+    fn status_reports_no_break_overdue_after_a_break() -> Result<()> {
         let db = setup()?;
         let querier = Querier::new(db.connection());
+        let session = querier.insert_session(&InsertSessionArgs {
+            session: &Session {
+                kind: SessionKind::Break,
+                ..Session::default()
+            },
+        })?;
+        let session_event = &SessionEvent {
+            created_at: Utc::now() - Duration::minutes(30),
+            ..SessionEvent::completed(session.id)
+        };
+        querier.insert_session_event(&InsertSessionEventArgs { session_event })?;
 
-        let cmd = StartCommand {
+        let cmd = StatusCommand {
             runner: None,
             querier,
+            config: ProgramConfig {
+                break_reminder_after: std::time::Duration::from_secs(10 * 60),
+                ..ProgramConfig::default()
+            },
         };
-        let args = &StartCommandArgs::default();
-        cmd.execute(args)?;
 
+        assert!(!cmd.execute()?.break_overdue);
+        Ok(())
+    }
+
+    #[test]
+    fn status_reports_a_session_running_past_stale_after_as_stale() -> Result<()> {
+        let db = setup()?;
+
+        seed_event(&db, |session| {
+            vec![SessionEvent {
+                created_at: Utc::now() - Duration::hours(9),
+                ..SessionEvent::started(session.id)
+            }]
+        })?;
+
+        let status = |stale_after: u64, auto_complete: bool| {
+            let cmd = StatusCommand {
+                runner: None,
+                querier: Querier::new(db.connection()),
+                config: ProgramConfig {
+                    stale_after: std::time::Duration::from_secs(stale_after),
+                    auto_complete,
+                    ..ProgramConfig::default()
+                },
+            };
+            cmd.execute()
+        };
+
+        // Left running for 9 hours.
+        let result = status(8 * 3600, false)?;
+        assert!(matches!(result.state, SessionState::Running));
+        assert!(result.stale);
+        assert!(!status(10 * 3600, false)?.stale);
+        assert!(!status(0, false)?.stale, "0s disables the check");
+
+        // Once completed, the session is no longer reported as stale.
+        let result = status(8 * 3600, true)?;
+        assert!(matches!(result.state, SessionState::Completed));
+        assert!(!result.stale);
+        Ok(())
+    }
+
+    #[test]
+    fn status_with_auto_break_starts_one_break_after_focus_completes() -> Result<()> {
+        let db = setup()?;
+        seed_event(&db, |session| {
+            vec![SessionEvent {
+                created_at: Utc::now() - Duration::minutes(30),
+                ..SessionEvent::started(session.id)
+            }]
+        })?;
+
+        let cmd = StatusCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+            config: ProgramConfig {
+                auto_break: true,
+                ..ProgramConfig::default()
+            },
+        };
+        // The completion is reported; the break shows from the next call on.
+        let status = cmd.execute()?;
+        assert!(matches!(status.state, SessionState::Completed));
+        assert!(matches!(
+            status.kind,
+            StatusKind::Session(SessionKind::Focus)
+        ));
+
+        let status = cmd.execute()?;
+        assert!(matches!(status.state, SessionState::Running));
+        assert!(matches!(
+            status.kind,
+            StatusKind::Session(SessionKind::Break)
+        ));
+        assert_eq!(status.planned_secs, 5 * 60);
+
+        let querier = Querier::new(db.connection());
+        let sessions = querier.list_sessions(&ListSessionsArgs::default())?;
+        assert_eq!(sessions.len(), 2);
         for_each_event(&db, |index, event| match index {
             0 => assert_eq!(event.kind, SessionEventKind::Started),
+            1 => assert_eq!(event.kind, SessionEventKind::Completed),
+            2 => assert_eq!(event.kind, SessionEventKind::Started),
             _ => panic!("unexpected event at index {index}"),
         })
     }
 
     #[test]
-    fn start_when_session_is_started_does_nothing() -> Result<()> {
+    fn status_with_auto_break_does_not_chain_after_a_break() -> Result<()> {
         let db = setup()?;
         let querier = Querier::new(db.connection());
+        let session = &Session {
+            kind: SessionKind::Break,
+            planned_duration: Duration::minutes(5),
+            ..Session::default()
+        };
+        let session = querier.insert_session(&InsertSessionArgs { session })?;
+        let session_event = &SessionEvent {
+            created_at: Utc::now() - Duration::minutes(10),
+            ..SessionEvent::started(session.id)
+        };
+        querier.insert_session_event(&InsertSessionEventArgs { session_event })?;
+
+        let cmd = StatusCommand {
+            runner: None,
+            querier,
+            config: ProgramConfig {
+                auto_break: true,
+                ..ProgramConfig::default()
+            },
+        };
+        cmd.execute()?;
+        let status = cmd.execute()?;
+
+        assert!(matches!(status.state, SessionState::Completed));
+        assert!(matches!(
+            status.kind,
+            StatusKind::Session(SessionKind::Break)
+        ));
+        Ok(())
+    }
 
+    #[test]
+    fn status_without_auto_complete_keeps_an_expired_session_running() -> Result<()> {
+        let db = setup()?;
         seed_event(&db, |session| {
-            // Session is currently running — start should be a no-op.
-            vec![SessionEvent::started(session.id)]
+            vec![SessionEvent {
+                created_at: Utc::now() - Duration::minutes(30),
+                ..SessionEvent::started(session.id)
+            }]
         })?;
 
-        let cmd = StartCommand {
+        let cmd = StatusCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+            config: ProgramConfig {
+                auto_complete: false,
+                ..ProgramConfig::default()
+            },
+        };
+        let status = cmd.execute()?;
+
+        assert!(matches!(status.state, SessionState::Running));
+        assert_eq!(status.remaining_secs, 0);
+        assert!((5 * 60..=5 * 60 + 1).contains(&status.overtime_secs));
+        for_each_event(&db, |index, event| {
+            if index == 0 {
+                assert_eq!(event.kind, SessionEventKind::Started);
+            }
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn status_reports_no_overtime_before_planned_duration() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            vec![SessionEvent {
+                created_at: Utc::now() - Duration::minutes(5),
+                ..SessionEvent::started(session.id)
+            }]
+        })?;
+
+        let cmd = StatusCommand {
             runner: None,
             querier,
+            config: ProgramConfig::default(),
         };
-        let args = &StartCommandArgs::default();
-        cmd.execute(args)?;
+        let status = cmd.execute()?;
 
-        for_each_event(&db, |index, event| match index {
-            0 => assert_eq!(event.kind, SessionEventKind::Started),
-            _ => panic!("unexpected event at index {index}"),
-        })
+        assert!(matches!(status.state, SessionState::Running));
+        assert_eq!(status.overtime_secs, 0);
+        Ok(())
     }
 
     #[test]
-    fn start_when_session_is_resumed_does_nothing() -> Result<()> {
+    fn status_reports_overtime_past_planned_duration() -> Result<()> {
         let db = setup()?;
         let querier = Querier::new(db.connection());
 
+        // Nobody asked for the status until 100s after the 25-minute session ended.
         seed_event(&db, |session| {
-            // Session was resumed and is currently running — start should be a no-op.
-            vec![SessionEvent::resumed(session.id)]
+            vec![SessionEvent {
+                created_at: Utc::now() - Duration::seconds(1500 + 100),
+                ..SessionEvent::started(session.id)
+            }]
         })?;
 
-        let cmd = StartCommand {
+        let cmd = StatusCommand {
             runner: None,
             querier,
+            config: ProgramConfig::default(),
         };
-        let args = &StartCommandArgs::default();
-        cmd.execute(args)?;
+        let status = cmd.execute()?;
 
-        for_each_event(&db, |index, event| match index {
-            0 => assert_eq!(event.kind, SessionEventKind::Resumed),
-            _ => panic!("unexpected event at index {index}"),
-        })
+        assert!(matches!(status.state, SessionState::Completed));
+        assert_eq!(status.remaining_secs, 0);
+        assert!((100..=101).contains(&status.overtime_secs));
+        Ok(())
     }
 
     #[test]
-    fn start_when_session_is_paused_resumes_session() -> Result<()> {
+    fn status_reports_pause_count_and_paused_secs() -> Result<()> {
         let db = setup()?;
         let querier = Querier::new(db.connection());
 
+        // started 30m ago → paused at 10m → resumed at 15m → paused at 20m, still paused.
+        let now = Utc::now();
+        let at = |minutes: i64| now - Duration::minutes(30 - minutes);
         seed_event(&db, |session| {
-            // Session is paused — start should resume it.
-            vec![SessionEvent::paused(session.id)]
+            vec![
+                SessionEvent {
+                    created_at: at(0),
+                    ..SessionEvent::started(session.id)
+                },
+                SessionEvent {
+                    created_at: at(10),
+                    ..SessionEvent::paused(session.id)
+                },
+                SessionEvent {
+                    created_at: at(15),
+                    ..SessionEvent::resumed(session.id)
+                },
+                SessionEvent {
+                    created_at: at(20),
+                    ..SessionEvent::paused(session.id)
+                },
+            ]
         })?;
 
-        let cmd = StartCommand {
+        let cmd = StatusCommand {
             runner: None,
             querier,
+            config: ProgramConfig::default(),
         };
-        let args = &StartCommandArgs::default();
-        cmd.execute(args)?;
+        let status = cmd.execute()?;
 
-        for_each_event(&db, |index, event| match index {
-            0 => assert_eq!(event.kind, SessionEventKind::Resumed),
-            1 => assert_eq!(event.kind, SessionEventKind::Paused),
-            _ => panic!("unexpected event at index {index}"),
-        })
+        assert!(matches!(status.state, SessionState::Paused));
+        assert_eq!(status.pause_count, 2);
+        assert_eq!(status.paused_secs, (5 + 10) * 60);
+        Ok(())
     }
 
     #[test]
-    fn start_after_aborted_session_starts_new_session() -> Result<()> {
+    fn status_reports_wall_elapsed_secs_including_pauses() -> Result<()> {
         let db = setup()?;
         let querier = Querier::new(db.connection());
 
+        // started 30m ago → paused at 10m → resumed at 20m, still running.
+        let now = Utc::now();
+        let at = |minutes: i64| now - Duration::minutes(30 - minutes);
         seed_event(&db, |session| {
-            // Previous session was aborted — start should begin a new one.
-            vec![SessionEvent::aborted(session.id)]
+            vec![
+                SessionEvent {
+                    created_at: at(0),
+                    ..SessionEvent::started(session.id)
+                },
+                SessionEvent {
+                    created_at: at(10),
+                    ..SessionEvent::paused(session.id)
+                },
+                SessionEvent {
+                    created_at: at(20),
+                    ..SessionEvent::resumed(session.id)
+                },
+            ]
         })?;
 
-        let cmd = StartCommand {
+        let cmd = StatusCommand {
             runner: None,
             querier,
+            config: ProgramConfig::default(),
         };
-        let args = &StartCommandArgs::default();
-        cmd.execute(args)?;
+        let status = cmd.execute()?;
 
-        for_each_event(&db, |index, event| match index {
-            0 => assert_eq!(event.kind, SessionEventKind::Started),
-            1 => assert_eq!(event.kind, SessionEventKind::Aborted),
-            _ => panic!("unexpected event at index {index}"),
-        })
+        assert!((20 * 60..=20 * 60 + 1).contains(&status.elapsed_secs));
+        assert!((30 * 60..=30 * 60 + 1).contains(&status.wall_elapsed_secs));
+        assert!(status.wall_elapsed_secs > status.elapsed_secs);
+
+        // Once ended, the wall-clock time stops at the terminal event.
+        let session = cmd
+            .querier
+            .list_sessions(&ListSessionsArgs::first())?
+            .remove(0);
+        let session_event = &SessionEvent {
+            created_at: at(25),
+            ..SessionEvent::aborted(session.id)
+        };
+        cmd.querier
+            .insert_session_event(&InsertSessionEventArgs { session_event })?;
+        let status = cmd.execute()?;
+
+        assert_eq!(status.wall_elapsed_secs, 25 * 60);
+        Ok(())
     }
 
     #[test]
-    fn start_after_completed_session_starts_new_session() -> Result<()> {
+    fn status_reports_paused_for_secs_since_the_last_pause() -> Result<()> {
         let db = setup()?;
         let querier = Querier::new(db.connection());
 
+        // started 3h10m ago → paused at 5m → resumed at 7m → paused at 10m, still paused.
+        let now = Utc::now();
+        let at = |minutes: i64| now - Duration::minutes(190 - minutes);
         seed_event(&db, |session| {
-            // Previous session completed naturally — start should begin a new one.
-            vec![SessionEvent::completed(session.id)]
+            vec![
+                SessionEvent {
+                    created_at: at(0),
+                    ..SessionEvent::started(session.id)
+                },
+                SessionEvent {
+                    created_at: at(5),
+                    ..SessionEvent::paused(session.id)
+                },
+                SessionEvent {
+                    created_at: at(7),
+                    ..SessionEvent::resumed(session.id)
+                },
+                SessionEvent {
+                    created_at: at(10),
+                    ..SessionEvent::paused(session.id)
+                },
+            ]
         })?;
 
-        let cmd = StartCommand {
+        let cmd = StatusCommand {
             runner: None,
             querier,
+            config: ProgramConfig::default(),
         };
-        let args = &StartCommandArgs::default();
-        cmd.execute(args)?;
+        let status = cmd.execute()?;
+
+        assert!(matches!(status.state, SessionState::Paused));
+        assert!((3 * 3600..=3 * 3600 + 1).contains(&status.paused_for_secs));
+
+        // Resuming closes the pause, so the status no longer reports one.
+        let session = cmd
+            .querier
+            .list_sessions(&ListSessionsArgs::first())?
+            .remove(0);
+        let session_event = &SessionEvent::resumed(session.id);
+        cmd.querier
+            .insert_session_event(&InsertSessionEventArgs { session_event })?;
+        let status = cmd.execute()?;
+
+        assert!(matches!(status.state, SessionState::Running));
+        assert_eq!(status.paused_for_secs, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn status_with_running_session_renders_text_output() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| {
+            // Session is currently running — status should display it without auto-completing.
+            vec![SessionEvent::started(session.id)]
+        })?;
+
+        let cmd = StatusCommand {
+            runner: None,
+            querier,
+            config: ProgramConfig::default(),
+        };
+        let args = &StatusCommandArgs::default();
+        let status = cmd.execute()?;
+        cmd.render(&status, args)?;
 
+        // Session still has time remaining — no completed event should be inserted.
         for_each_event(&db, |index, event| match index {
             0 => assert_eq!(event.kind, SessionEventKind::Started),
-            1 => assert_eq!(event.kind, SessionEventKind::Completed),
             _ => panic!("unexpected event at index {index}"),
         })
     }
 
-    // --- StopCommand ---
-
     #[test]
-    fn stop_with_no_prior_events_does_nothing() -> Result<()> {
+    fn status_with_running_session_renders_json_output() -> Result<()> {
         let db = setup()?;
         let querier = Querier::new(db.connection());
 
-        let cmd = StopCommand {
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+
+        let cmd = StatusCommand {
             runner: None,
             querier,
+            config: ProgramConfig::default(),
         };
-        let args = &StopCommandArgs::default();
-        cmd.execute(args)?;
+        let args = &StatusCommandArgs {
+            output: StatusOutput::Json,
+            format: None,
+            ..Default::default()
+        };
+        let status = cmd.execute()?;
+        let output: serde_json::Value = serde_json::from_str(&cmd.render(&status, args)?)?;
+        assert_eq!(output["state"], "running");
+        Ok(())
+    }
 
-        for_each_event(&db, |index, _event| {
-            panic!("unexpected event at index {index}")
-        })
+    #[test]
+    fn status_with_running_session_renders_custom_text_format() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+
+        let cmd = StatusCommand {
+            runner: None,
+            querier,
+            config: ProgramConfig::default(),
+        };
+        let args = &StatusCommandArgs {
+            output: StatusOutput::Text,
+            format: Some("{{ remaining_secs }}s left".to_string()),
+            ..Default::default()
+        };
+        let status = cmd.execute()?;
+        let output = cmd.render(&status, args)?;
+        assert_eq!(output, format!("{}s left", status.remaining_secs));
+        Ok(())
     }
 
     #[test]
-    fn stop_when_session_is_started_pauses_session() -> Result<()> {
+    fn status_uses_configured_template_unless_format_is_passed() -> Result<()> {
         let db = setup()?;
         let querier = Querier::new(db.connection());
 
-        seed_event(&db, |session| {
-            // Session is currently running — stop should pause it.
-            vec![SessionEvent::started(session.id)]
-        })?;
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
 
-        let cmd = StopCommand {
+        let cmd = StatusCommand {
             runner: None,
             querier,
+            config: ProgramConfig {
+                status_template: Some("{{ kind }} is {{ state }}".to_string()),
+                ..ProgramConfig::default()
+            },
         };
-        let args = &StopCommandArgs::default();
-        cmd.execute(args)?;
+        let status = cmd.execute()?;
 
-        for_each_event(&db, |index, event| match index {
-            0 => assert_eq!(event.kind, SessionEventKind::Paused),
-            1 => assert_eq!(event.kind, SessionEventKind::Started),
-            _ => panic!("unexpected event at index {index}"),
-        })
+        let args = &StatusCommandArgs::default();
+        assert_eq!(cmd.render(&status, args)?, "focus is running");
+
+        let args = &StatusCommandArgs {
+            format: Some("{{ state }}".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(cmd.render(&status, args)?, "running");
+        Ok(())
     }
 
     #[test]
-    fn stop_when_session_is_started_with_reset_aborts_session() -> Result<()> {
+    fn status_reports_progress_of_a_half_elapsed_session() -> Result<()> {
         let db = setup()?;
-        let querier = Querier::new(db.connection());
-
+        // Half of the default 25 minutes elapsed.
         seed_event(&db, |session| {
-            // Session is currently running — stop --reset should abort it.
-            vec![SessionEvent::started(session.id)]
+            vec![SessionEvent {
+                created_at: Utc::now() - Duration::seconds(750),
+                ..SessionEvent::started(session.id)
+            }]
         })?;
 
-        let cmd = StopCommand {
+        let cmd = StatusCommand {
             runner: None,
-            querier,
+            querier: Querier::new(db.connection()),
+            config: ProgramConfig::default(),
         };
-        let args = &StopCommandArgs { reset: true };
-        cmd.execute(args)?;
+        let status = cmd.execute()?;
+        assert_eq!(status.percent, 50);
+        assert!((status.progress - 0.5).abs() < 0.01);
 
-        for_each_event(&db, |index, event| match index {
-            0 => assert_eq!(event.kind, SessionEventKind::Aborted),
-            1 => assert_eq!(event.kind, SessionEventKind::Started),
-            _ => panic!("unexpected event at index {index}"),
-        })
+        let args = &StatusCommandArgs {
+            output: StatusOutput::Json,
+            ..Default::default()
+        };
+        let json: serde_json::Value = serde_json::from_str(&cmd.render(&status, args)?)?;
+        assert_eq!(json["percent"], 50);
+
+        // Without a planned duration the session counts as done, not as NaN.
+        let mut status = SessionStatus::default();
+        status.update_progress();
+        assert_eq!((status.progress, status.percent), (1.0, 100));
+        Ok(())
     }
 
     #[test]
-    fn stop_when_session_is_resumed_pauses_session() -> Result<()> {
+    fn status_picks_the_configured_template_of_each_output() -> Result<()> {
         let db = setup()?;
-        let querier = Querier::new(db.connection());
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+        let cmd = StatusCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+            config: ProgramConfig {
+                status_template: Some("legacy".to_string()),
+                templates: TemplatesConfig {
+                    text: Some("text {{ state }}".to_string()),
+                    tmux: Some("tmux {{ state }}".to_string()),
+                },
+                ..ProgramConfig::default()
+            },
+        };
+        let status = cmd.execute()?;
 
-        seed_event(&db, |session| {
-            // Session was resumed and is running — stop should pause it.
-            vec![SessionEvent::resumed(session.id)]
-        })?;
+        let args = &StatusCommandArgs::default();
+        assert_eq!(cmd.render(&status, args)?, "text running");
 
-        let cmd = StopCommand {
-            runner: None,
-            querier,
+        let args = &StatusCommandArgs {
+            output: StatusOutput::Tmux,
+            ..Default::default()
         };
-        let args = &StopCommandArgs::default();
-        cmd.execute(args)?;
+        assert_eq!(
+            cmd.render(&status, args)?,
+            "#[fg=red]tmux running#[default]"
+        );
 
-        for_each_event(&db, |index, event| match index {
-            0 => assert_eq!(event.kind, SessionEventKind::Paused),
-            1 => assert_eq!(event.kind, SessionEventKind::Resumed),
-            _ => panic!("unexpected event at index {index}"),
-        })
+        // --format overrides the configured template of every output.
+        for output in [StatusOutput::Text, StatusOutput::Tmux] {
+            let args = &StatusCommandArgs {
+                output,
+                format: Some("{{ kind }}".to_string()),
+                ..Default::default()
+            };
+            assert!(cmd.render(&status, args)?.contains("focus"));
+        }
+        Ok(())
     }
 
     #[test]
-    fn stop_when_session_is_resumed_with_reset_aborts_session() -> Result<()> {
+    fn status_renders_template_file_passed_with_at() -> Result<()> {
         let db = setup()?;
-        let querier = Querier::new(db.connection());
-
-        seed_event(&db, |session| {
-            // Session was resumed and is running — stop --reset should abort it.
-            vec![SessionEvent::resumed(session.id)]
-        })?;
-
-        let cmd = StopCommand {
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+        let cmd = StatusCommand {
             runner: None,
-            querier,
+            querier: Querier::new(db.connection()),
+            config: ProgramConfig::default(),
         };
-        let args = &StopCommandArgs { reset: true };
-        cmd.execute(args)?;
+        let status = cmd.execute()?;
 
-        for_each_event(&db, |index, event| match index {
-            0 => assert_eq!(event.kind, SessionEventKind::Aborted),
-            1 => assert_eq!(event.kind, SessionEventKind::Resumed),
-            _ => panic!("unexpected event at index {index}"),
-        })
+        let path = std::env::temp_dir().join(format!("pomodoro-template-{}", Uuid::now_v7()));
+        std::fs::write(&path, "{{ kind }}\n{{ state }}")?;
+        let args = &StatusCommandArgs {
+            format: Some(format!("@{}", path.display())),
+            ..Default::default()
+        };
+        let output = cmd.render(&status, args);
+        std::fs::remove_file(&path)?;
+        assert_eq!(output?, "focus\nrunning");
+
+        // A missing file is an error rather than a template reading "@...".
+        let error = cmd.render(&status, args).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("Failed to read the template file {}", path.display())
+        );
+        Ok(())
     }
 
     #[test]
-    fn stop_when_session_is_paused_does_nothing() -> Result<()> {
+    fn expand_path_expands_tilde_and_variables() -> Result<()> {
+        let home = std::env::var("HOME")?;
+        let expected = PathBuf::from(format!("{home}/.config/status.jinja"));
+        assert_eq!(expand_path("~/.config/status.jinja")?, expected);
+        assert_eq!(expand_path("$HOME/.config/status.jinja")?, expected);
+        assert_eq!(expand_path("${HOME}/.config/status.jinja")?, expected);
+        // Only a leading tilde of the current user is expanded.
+        assert_eq!(expand_path("~other/a~b")?, PathBuf::from("~other/a~b"));
+
+        let error = expand_path("$POMODORO_UNSET_VARIABLE/status.jinja").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Environment variable POMODORO_UNSET_VARIABLE is not set"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn status_with_running_session_renders_json_path() -> Result<()> {
         let db = setup()?;
         let querier = Querier::new(db.connection());
 
-        seed_event(&db, |session| {
-            // Session is paused — stop should be a no-op.
-            vec![SessionEvent::paused(session.id)]
-        })?;
+        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
 
-        let cmd = StopCommand {
+        let cmd = StatusCommand {
             runner: None,
             querier,
+            config: ProgramConfig::default(),
         };
-        let args = &StopCommandArgs::default();
-        cmd.execute(args)?;
+        let args = &StatusCommandArgs {
+            json_path: Some("remaining_secs".to_string()),
+            ..Default::default()
+        };
+        let status = cmd.execute()?;
+        let output = cmd.render(&status, args)?;
+        assert_eq!(output, status.remaining_secs.to_string());
+        Ok(())
+    }
 
-        for_each_event(&db, |index, event| match index {
-            0 => assert_eq!(event.kind, SessionEventKind::Paused),
-            _ => panic!("unexpected event at index {index}"),
-        })
+    #[test]
+    fn status_template_renders_vars_next_to_status_fields() -> Result<()> {
+        let args = &StatusCommandArgs {
+            format: Some("{{ pane }} {{ kind }}".to_string()),
+            vars: vec![
+                ("pane".to_string(), "main".to_string()),
+                ("kind".to_string(), "shadowed".to_string()),
+            ],
+            ..Default::default()
+        };
+        let status = &SessionStatus::default();
+        let output = render_status(status, args, &ProgramConfig::default())?;
+        assert_eq!(output, "main none");
+
+        let args = &StatusCommandArgs {
+            output: StatusOutput::Tmux,
+            ..args.clone()
+        };
+        let output = render_status(status, args, &ProgramConfig::default())?;
+        assert_eq!(output, "main none");
+        Ok(())
     }
 
+    // --- SummaryCommand ---
+
     #[test]
-    fn stop_when_session_is_paused_with_reset_aborts_session() -> Result<()> {
+    fn summary_counts_sessions_completed_today() -> Result<()> {
         let db = setup()?;
         let querier = Querier::new(db.connection());
 
-        seed_event(&db, |session| {
-            // Session is paused — stop --reset should abort it.
-            vec![SessionEvent::paused(session.id)]
-        })?;
+        let two_days_ago = Utc::now() - Duration::days(2);
+        let seeds = [
+            (SessionKind::Focus, Utc::now()),
+            (SessionKind::Focus, Utc::now()),
+            (SessionKind::Break, Utc::now()),
+            // Completed before today — must not be counted.
+            (SessionKind::Focus, two_days_ago),
+        ];
+        for (kind, completed_at) in seeds {
+            let session = &Session {
+                kind,
+                ..Session::default()
+            };
+            querier.insert_session(&InsertSessionArgs { session })?;
+            let session_event = &SessionEvent {
+                created_at: completed_at,
+                ..SessionEvent::completed(session.id)
+            };
+            querier.insert_session_event(&InsertSessionEventArgs { session_event })?;
+        }
 
-        let cmd = StopCommand {
+        let cmd = SummaryCommand {
             runner: None,
             querier,
+            config: ProgramConfig::default(),
         };
-        let args = &StopCommandArgs { reset: true };
-        cmd.execute(args)?;
+        let digest = cmd.execute()?;
+        assert_eq!(digest.focus_count, 2);
+        assert_eq!(digest.focus_secs, 2 * 1500);
+        assert_eq!(digest.break_count, 1);
+        assert_eq!(digest.break_secs, 1500);
 
-        for_each_event(&db, |index, event| match index {
-            0 => assert_eq!(event.kind, SessionEventKind::Aborted),
-            1 => assert_eq!(event.kind, SessionEventKind::Paused),
-            _ => panic!("unexpected event at index {index}"),
-        })
+        let args = &SummaryCommandArgs::default();
+        let output = cmd.render(&digest, args)?;
+        assert_eq!(
+            output,
+            "Today: 2 focus (50m), 1 break; current: focus completed"
+        );
+        Ok(())
     }
 
     #[test]
-    fn stop_when_session_is_aborted_does_nothing() -> Result<()> {
+    fn summary_without_sessions_reports_zero() -> Result<()> {
         let db = setup()?;
         let querier = Querier::new(db.connection());
 
-        seed_event(&db, |session| {
-            // Session was aborted — stop should be a no-op.
-            vec![SessionEvent::aborted(session.id)]
-        })?;
-
-        let cmd = StopCommand {
+        let cmd = SummaryCommand {
             runner: None,
             querier,
+            config: ProgramConfig::default(),
         };
-        let args = &StopCommandArgs::default();
-        cmd.execute(args)?;
-
-        for_each_event(&db, |index, event| match index {
-            0 => assert_eq!(event.kind, SessionEventKind::Aborted),
-            _ => panic!("unexpected event at index {index}"),
-        })
+        let digest = cmd.execute()?;
+        let args = &SummaryCommandArgs::default();
+        let output = cmd.render(&digest, args)?;
+        assert_eq!(output, "Today: 0 focus (0m), 0 breaks; current: none");
+        Ok(())
     }
 
-    // --- StatusCommand ---
+    // --- StatsCommand ---
 
     #[test]
-    fn status_when_session_is_already_completed_does_not_insert_another_completed() -> Result<()> {
+    fn stats_counts_a_running_session_as_in_progress() -> Result<()> {
         let db = setup()?;
-        let querier = Querier::new(db.connection());
-
+        seed_event(&db, |session| vec![SessionEvent::completed(session.id)])?;
+        seed_event(&db, |session| vec![SessionEvent::completed(session.id)])?;
+        seed_event(&db, |session| vec![SessionEvent::aborted(session.id)])?;
+        // Out of time, but not completed until `status` records it.
         seed_event(&db, |session| {
-            // Session was already completed — status must not insert a second completed event.
-            vec![
-                SessionEvent::started(session.id),
-                SessionEvent::completed(session.id),
-            ]
+            vec![SessionEvent {
+                created_at: Utc::now() - Duration::hours(1),
+                ..SessionEvent::started(session.id)
+            }]
         })?;
 
-        let cmd = StatusCommand {
-            runner: None,
-            querier,
+        let cmd = StatsCommand {
+            querier: Querier::new(db.connection()),
         };
-        let args = &StatusCommandArgs::default();
-        cmd.execute(args)?;
+        let args = &StatsCommandArgs {
+            since: "7d".parse()?,
+            output: CommandOutput::Text,
+        };
+        let report = cmd.execute(args)?;
+        assert_eq!(report.completed, 2);
+        assert_eq!(report.aborted, 1);
+        assert_eq!(report.in_progress, 1);
+        assert_eq!(report.focus_secs, 2 * 25 * 60);
+        assert_eq!(
+            cmd.render(&report, args)?,
+            "Last 7d: 2 focus sessions completed (50m), 1 aborted, 1 in progress; 67% completed"
+        );
+        Ok(())
+    }
 
-        for_each_event(&db, |index, event| match index {
-            0 => assert_eq!(event.kind, SessionEventKind::Completed),
-            1 => assert_eq!(event.kind, SessionEventKind::Started),
-            _ => panic!("unexpected event at index {index}"),
-        })
+    #[test]
+    fn stats_renders_no_completion_rate_without_ended_sessions() -> Result<()> {
+        let db = setup()?;
+        let cmd = StatsCommand {
+            querier: Querier::new(db.connection()),
+        };
+        let args = &StatsCommandArgs {
+            since: "1d".parse()?,
+            output: CommandOutput::Json,
+        };
+        let output: serde_json::Value =
+            serde_json::from_str(&cmd.render(&cmd.execute(args)?, args)?)?;
+        assert_eq!(output["completed"], 0);
+        assert_eq!(output["completion_rate"], serde_json::Value::Null);
+        Ok(())
     }
 
     #[test]
-    fn status_with_running_session_renders_text_output() -> Result<()> {
+    fn stats_rejects_a_window_too_far_in_the_past() -> Result<()> {
         let db = setup()?;
-        let querier = Querier::new(db.connection());
+        let cmd = StatsCommand {
+            querier: Querier::new(db.connection()),
+        };
+        let args = &StatsCommandArgs {
+            since: "1000000y".parse()?,
+            output: CommandOutput::Text,
+        };
+        let err = cmd.execute(args).err().expect("the window overflows");
+        assert_eq!(err.to_string(), "--since is too far in the past");
+        Ok(())
+    }
+
+    // --- ContextCommand ---
+
+    #[test]
+    fn context_reports_active_session() -> Result<()> {
+        let db = setup()?;
+
+        let cmd = ContextCommand {
+            runner: None,
+            querier: Querier::new(db.connection()),
+            config_path: None,
+            database_path: None,
+        };
+        assert!(!cmd.execute()?.active_session);
+
+        seed_event(&db, |session| vec![SessionEvent::paused(session.id)])?;
 
+        let context = cmd.execute()?;
+        let args = &ContextCommandArgs {
+            output: CommandOutput::Json,
+        };
+        let output: serde_json::Value = serde_json::from_str(&cmd.render(&context, args)?)?;
+        assert_eq!(output["active_session"], true);
+        assert_eq!(output["session_count"], 1);
+        assert_eq!(output["session_event_count"], 1);
+        assert_eq!(output["database_path"], serde_json::Value::Null);
+        assert_eq!(output["hooks"], serde_json::json!([]));
+        Ok(())
+    }
+
+    // --- StatusCache ---
+
+    #[test]
+    fn status_cache_derives_remaining_from_ends_at() -> Result<()> {
+        let mut db = setup()?;
         seed_event(&db, |session| {
-            // Session is currently running — status should display it without auto-completing.
-            vec![SessionEvent::started(session.id)]
+            vec![SessionEvent {
+                created_at: Utc::now() - Duration::minutes(5),
+                ..SessionEvent::started(session.id)
+            }]
         })?;
 
-        let cmd = StatusCommand {
-            runner: None,
-            querier,
+        let path = std::env::temp_dir().join(format!("pomodoro-cache-{}.json", Uuid::now_v7()));
+        StatusCache::refresh(&mut db, &ProgramConfig::default(), &path)?;
+        let cache = StatusCache::read(&path)?;
+        std::fs::remove_file(&path).ok();
+
+        let config = &ProgramConfig {
+            auto_complete_tolerance: std::time::Duration::ZERO,
+            ..ProgramConfig::default()
         };
-        let args = &StatusCommandArgs::default();
-        cmd.execute(args)?;
+        let later = Utc::now() + Duration::minutes(10);
+        let status = cache.status_at(later, config).expect("time is left");
+        assert!(matches!(status.state, SessionState::Running));
+        assert!((9 * 60..=10 * 60).contains(&status.remaining_secs));
+        assert_eq!(status.elapsed_secs, 25 * 60 - status.remaining_secs);
+        Ok(())
+    }
 
-        // Session still has time remaining — no completed event should be inserted.
-        for_each_event(&db, |index, event| match index {
-            0 => assert_eq!(event.kind, SessionEventKind::Started),
-            _ => panic!("unexpected event at index {index}"),
-        })
+    #[test]
+    fn status_cache_expires_when_the_session_runs_out() {
+        let now = Utc::now();
+        let status = SessionStatus {
+            state: SessionState::Running,
+            planned_secs: 25 * 60,
+            remaining_secs: 60,
+            ..SessionStatus::default()
+        };
+        let cache = StatusCache::new(status, now);
+        let config = &ProgramConfig {
+            auto_complete_tolerance: std::time::Duration::ZERO,
+            ..ProgramConfig::default()
+        };
+        assert!(cache
+            .status_at(now + Duration::minutes(2), config)
+            .is_none());
     }
 
     #[test]
-    fn status_with_running_session_renders_json_output() -> Result<()> {
-        let db = setup()?;
-        let querier = Querier::new(db.connection());
+    fn status_cache_keeps_a_paused_status() {
+        let now = Utc::now();
+        let status = SessionStatus {
+            state: SessionState::Paused,
+            planned_secs: 25 * 60,
+            remaining_secs: 60,
+            ..SessionStatus::default()
+        };
+        let cache = StatusCache::new(status, now);
+        assert_eq!(cache.ends_at, None);
 
-        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+        let later = now + Duration::hours(1);
+        let status = cache.status_at(later, &ProgramConfig::default()).unwrap();
+        assert_eq!(status.remaining_secs, 60);
+        assert_eq!(status.paused_for_secs, 3600);
+        assert_eq!(status.paused_secs, 3600);
+        assert_eq!(status.wall_elapsed_secs, 3600);
+    }
 
-        let cmd = StatusCommand {
-            runner: None,
-            querier,
+    #[test]
+    fn status_cache_recomputes_flags_that_change_over_time() {
+        let now = Utc::now();
+        let config = &ProgramConfig {
+            break_reminder_after: std::time::Duration::from_secs(5 * 60),
+            stale_after: std::time::Duration::from_secs(30 * 60),
+            ..ProgramConfig::default()
         };
-        let args = &StatusCommandArgs {
-            output: StatusOutput::Json,
-            format: None,
+
+        // A running session turns stale while the cache is read.
+        let status = SessionStatus {
+            kind: StatusKind::Session(SessionKind::Focus),
+            state: SessionState::Running,
+            planned_secs: 2 * 3600,
+            elapsed_secs: 20 * 60,
+            remaining_secs: 100 * 60,
+            ..SessionStatus::default()
+        };
+        let cache = StatusCache::new(status, now);
+        let status = cache.status_at(now + Duration::minutes(20), config);
+        assert!(status.expect("time is left").stale);
+
+        // A completed focus session becomes overdue for a break.
+        let completed = || {
+            let status = SessionStatus {
+                kind: StatusKind::Session(SessionKind::Focus),
+                state: SessionState::Completed,
+                planned_secs: 25 * 60,
+                elapsed_secs: 25 * 60,
+                ended_at: Some(now),
+                ..SessionStatus::default()
+            };
+            StatusCache::new(status, now)
         };
-        cmd.execute(args)
+        assert!(!completed().status_at(now, config).unwrap().break_overdue);
+        let status = completed().status_at(now + Duration::minutes(10), config);
+        assert!(status.unwrap().break_overdue);
     }
 
+    // --- StatusKind ---
+
     #[test]
-    fn status_with_running_session_renders_custom_text_format() -> Result<()> {
-        let db = setup()?;
-        let querier = Querier::new(db.connection());
+    fn status_kind_serializes_as_lowercase_string() -> Result<()> {
+        let kinds = [
+            (StatusKind::None, "none"),
+            (StatusKind::Session(SessionKind::Focus), "focus"),
+            (StatusKind::Session(SessionKind::Break), "break"),
+        ];
+        for (kind, expected) in kinds {
+            assert_eq!(serde_json::to_value(&kind)?, expected);
+            assert_eq!(kind.to_string(), expected);
+        }
+        Ok(())
+    }
 
-        seed_event(&db, |session| vec![SessionEvent::started(session.id)])?;
+    // --- render_tmux ---
 
-        let cmd = StatusCommand {
-            runner: None,
-            querier,
+    #[test]
+    fn render_tmux_uses_kind_color_for_running_session() -> Result<()> {
+        let status = SessionStatus {
+            kind: StatusKind::Session(SessionKind::Focus),
+            state: SessionState::Running,
+            planned_secs: 1500,
+            elapsed_secs: 1,
+            remaining_secs: 1499,
+            ..SessionStatus::default()
         };
-        let args = &StatusCommandArgs {
-            output: StatusOutput::Text,
-            format: Some("{{ remaining_secs }}s left".to_string()),
+        let output = render_tmux(
+            &status,
+            &[],
+            DEFAULT_TMUX_TEMPLATE,
+            &ColorsConfig::default(),
+        )?;
+        assert_eq!(output, "#[fg=red]🍅 24:59#[default]");
+        Ok(())
+    }
+
+    #[test]
+    fn render_tmux_uses_ending_color_under_ten_percent() -> Result<()> {
+        let status = SessionStatus {
+            kind: StatusKind::Session(SessionKind::Focus),
+            state: SessionState::Running,
+            planned_secs: 1500,
+            elapsed_secs: 1400,
+            remaining_secs: 100,
+            ..SessionStatus::default()
         };
-        cmd.execute(args)
+        let output = render_tmux(
+            &status,
+            &[],
+            DEFAULT_TMUX_TEMPLATE,
+            &ColorsConfig::default(),
+        )?;
+        assert_eq!(output, "#[fg=magenta]🍅 01:40#[default]");
+        Ok(())
+    }
+
+    #[test]
+    fn render_tmux_leaves_missing_session_unstyled() -> Result<()> {
+        let status = SessionStatus::default();
+        let output = render_tmux(&status, &[], "idle", &ColorsConfig::default())?;
+        assert_eq!(output, "idle");
+        Ok(())
     }
 }
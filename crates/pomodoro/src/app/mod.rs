@@ -1,2 +1,4 @@
 pub mod cli;
 pub mod cmd;
+pub mod output;
+pub mod tmux;
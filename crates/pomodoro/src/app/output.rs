@@ -0,0 +1,223 @@
+use crate::app::cli::CommandOutput;
+use anyhow::{Context, Result};
+use minijinja::Environment;
+
+/// Renderer turns the data model of a command into its output, so that every
+/// command supports the same formats in the same way.
+///
+/// - [`CommandOutput::Json`]: the payload as pretty-printed JSON via `serde_json`.
+/// - [`CommandOutput::Text`]: the payload rendered with a user-supplied
+///   MiniJinja template, or else the command's own text formatting.
+#[derive(Debug, Clone, Copy)]
+pub struct Renderer<'t> {
+    /// The output format to render.
+    pub output: CommandOutput,
+    /// The template used for text output instead of the command's own formatting.
+    pub template: Option<&'t str>,
+}
+
+impl<'t> Renderer<'t> {
+    /// Create a renderer for `output` without a template.
+    pub fn new(output: CommandOutput) -> Self {
+        Self {
+            output,
+            template: None,
+        }
+    }
+
+    /// Render text output with `template` (e.g. from `--format`) when one is given.
+    pub fn with_template(mut self, template: Option<&'t str>) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Render `payload`, falling back to `text` for text output without a template.
+    pub fn render<P, F>(&self, payload: &P, text: F) -> Result<String>
+    where
+        P: serde::Serialize,
+        F: FnOnce(&P) -> Result<String>,
+    {
+        match (self.output, self.template) {
+            (CommandOutput::Json, _) => Ok(serde_json::to_string_pretty(payload)?),
+            (CommandOutput::Text, Some(template)) => render_template(template, payload),
+            (CommandOutput::Text, None) => text(payload),
+        }
+    }
+}
+
+/// How many instructions a user-supplied template may execute before
+/// [`render_template`] gives up on it; far more than any status line needs.
+pub const RENDER_FUEL: u64 = 100_000;
+
+/// Output emitted instead of a template that ran out of [`RENDER_FUEL`].
+pub const RENDER_FUEL_FALLBACK: &str = "template too expensive";
+
+/// Build the MiniJinja [`Environment`] shared by every command that renders a
+/// user-supplied template.
+///
+/// Templates come from the command line and the config file, so the
+/// environment has no loader (`include`, `import`, and `extends` always fail),
+/// a recursion limit well below MiniJinja's default, and stops rendering once
+/// [`RENDER_FUEL`] runs out.
+///
+/// Durations in seconds are formatted with the `hms` and `mmss` filters, e.g.
+/// `{{ remaining_secs | mmss }}`.
+pub fn environment() -> Environment<'static> {
+    let mut environment = Environment::new();
+    environment.set_recursion_limit(100);
+    environment.set_fuel(Some(RENDER_FUEL));
+    environment.add_filter("hms", hms);
+    environment.add_filter("mmss", mmss);
+    environment
+}
+
+/// Format `secs` as `HH:MM:SS`, e.g. `00:04:59`; the `hms` template filter.
+fn hms(secs: i64) -> String {
+    let sign = if secs < 0 { "-" } else { "" };
+    let secs = secs.unsigned_abs();
+    format!(
+        "{sign}{:02}:{:02}:{:02}",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+/// Format `secs` as `MM:SS`, e.g. `04:59`; the `mmss` template filter. The
+/// minutes are not wrapped into hours, so 90 minutes read `90:00`.
+fn mmss(secs: i64) -> String {
+    let sign = if secs < 0 { "-" } else { "" };
+    let secs = secs.unsigned_abs();
+    format!("{sign}{:02}:{:02}", secs / 60, secs % 60)
+}
+
+/// Render a user-supplied `template` against `context` with [`environment`],
+/// emitting [`RENDER_FUEL_FALLBACK`] if it runs out of [`RENDER_FUEL`], so
+/// that a pathological template cannot hang the command.
+pub fn render_template<S: serde::Serialize>(template: &str, context: &S) -> Result<String> {
+    match environment().render_str(template, context) {
+        Err(error) if error.kind() == minijinja::ErrorKind::OutOfFuel => {
+            Ok(RENDER_FUEL_FALLBACK.to_string())
+        }
+        output => Ok(output?),
+    }
+}
+
+/// Resolve a dotted `path` (e.g. `remaining_secs` or `config.focus_secs`) against
+/// `value`, descending one object key per segment.
+///
+/// This is intentionally not a full JSONPath/jq implementation: only object
+/// keys separated by `.` are supported. Returns an error naming the first
+/// segment that does not exist.
+pub fn json_path<'v>(value: &'v serde_json::Value, path: &str) -> Result<&'v serde_json::Value> {
+    path.split('.').try_fold(value, |value, key| {
+        value
+            .get(key)
+            .with_context(|| format!("No field '{key}' in JSON path '{path}'"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::cli::DEFAULT_TEXT_TEMPLATE;
+    use crate::app::cmd::SessionStatus;
+
+    // --- Renderer ---
+
+    #[test]
+    fn renderer_renders_json_text_and_templates() -> Result<()> {
+        let payload = serde_json::json!({ "kind": "focus", "remaining_secs": 60 });
+        let text = |payload: &serde_json::Value| Ok(format!("{} left", payload["remaining_secs"]));
+
+        let output = Renderer::new(CommandOutput::Json).render(&payload, text)?;
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&output)?, payload);
+
+        let output = Renderer::new(CommandOutput::Text).render(&payload, text)?;
+        assert_eq!(output, "60 left");
+
+        let output = Renderer::new(CommandOutput::Text)
+            .with_template(Some("{{ kind }}"))
+            .render(&payload, text)?;
+        assert_eq!(output, "focus");
+
+        // JSON ignores the template.
+        let output = Renderer::new(CommandOutput::Json)
+            .with_template(Some("{{ kind }}"))
+            .render(&payload, text)?;
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&output)?, payload);
+        Ok(())
+    }
+
+    // --- json_path ---
+
+    #[test]
+    fn json_path_extracts_top_level_field() -> Result<()> {
+        let value = serde_json::to_value(SessionStatus::default())?;
+        let field = json_path(&value, "kind")?;
+        assert_eq!(field, &serde_json::json!("none"));
+        Ok(())
+    }
+
+    #[test]
+    fn json_path_extracts_nested_field() -> Result<()> {
+        let value = serde_json::json!({ "config": { "focus_secs": 1500 } });
+        let field = json_path(&value, "config.focus_secs")?;
+        assert_eq!(field, &serde_json::json!(1500));
+        Ok(())
+    }
+
+    #[test]
+    fn json_path_fails_on_missing_field() -> Result<()> {
+        let value = serde_json::to_value(SessionStatus::default())?;
+        let result = json_path(&value, "config.focus_secs");
+        assert!(result.is_err(), "Should return error for a missing path");
+        Ok(())
+    }
+
+    // --- render_template ---
+
+    #[test]
+    fn render_template_renders_context() -> Result<()> {
+        let status = SessionStatus::default();
+        let output = render_template("{{ kind }} {{ state }}", &status)?;
+        assert_eq!(output, "none none");
+        Ok(())
+    }
+
+    #[test]
+    fn render_template_runs_out_of_fuel_on_expensive_template() -> Result<()> {
+        let template = "{% for i in range(10000) %}{% for j in range(10000) %}\
+                        {{ i * j }}{% endfor %}{% endfor %}";
+        let output = render_template(template, &SessionStatus::default())?;
+        assert_eq!(output, RENDER_FUEL_FALLBACK);
+        Ok(())
+    }
+
+    #[test]
+    fn render_template_formats_durations_with_filters() -> Result<()> {
+        let status = SessionStatus {
+            remaining_secs: 299,
+            elapsed_secs: 5400,
+            ..SessionStatus::default()
+        };
+        let template = "{{ remaining_secs | hms }} {{ remaining_secs | mmss }} \
+                        {{ elapsed_secs | hms }} {{ elapsed_secs | mmss }} {{ -61 | mmss }}";
+        let output = render_template(template, &status)?;
+        assert_eq!(output, "00:04:59 04:59 01:30:00 90:00 -01:01");
+
+        // The default template still renders the way it did before the filters.
+        let output = render_template(DEFAULT_TEXT_TEMPLATE, &status)?;
+        assert_eq!(output, "none | none | elapsed 90:00 | remaining 04:59");
+        Ok(())
+    }
+
+    #[test]
+    fn render_template_rejects_includes() {
+        let result = render_template("{% include 'other.txt' %}", &SessionStatus::default());
+        assert!(
+            result.is_err(),
+            "Templates must not be able to load other files"
+        );
+    }
+}
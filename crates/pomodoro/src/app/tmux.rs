@@ -0,0 +1,158 @@
+use anyhow::{Context, Result};
+use std::ffi::OsStr;
+use std::process::Command;
+
+/// The global tmux option `status --set-tmux-option` sets, for `#{@pomodoro}`
+/// in `status-right`.
+pub const STATUS_OPTION: &str = "@pomodoro";
+
+/// Ask tmux to redraw the status line of the current client so that a state
+/// change shows up immediately instead of on the next `status-interval` tick.
+///
+/// Does nothing outside of tmux (when `$TMUX` is unset or empty). Returns
+/// whether a refresh was requested.
+pub fn refresh_client() -> Result<bool> {
+    let tmux = std::env::var_os("TMUX");
+    refresh_client_with(tmux.as_deref(), |command| command.status().map(|_| ()))
+}
+
+/// Like [`refresh_client`], but with the `$TMUX` value and the process runner
+/// injected so the guard can be tested without a tmux server.
+pub fn refresh_client_with<F>(tmux: Option<&OsStr>, run: F) -> Result<bool>
+where
+    F: FnOnce(&mut Command) -> std::io::Result<()>,
+{
+    if tmux.is_none_or(OsStr::is_empty) {
+        return Ok(false);
+    }
+
+    let mut command = Command::new("tmux");
+    command.args(["refresh-client", "-S"]);
+    run(&mut command).context("Failed to refresh the tmux client")?;
+    Ok(true)
+}
+
+/// Set the global tmux option `name` to `value`, e.g. `@pomodoro`, so that the
+/// status line can read it with `#{@pomodoro}` instead of running a command.
+///
+/// Does nothing outside of tmux (when `$TMUX` is unset or empty). Returns
+/// whether the option was set; fails when tmux cannot be run or refuses it.
+pub fn set_option(name: &str, value: &str) -> Result<bool> {
+    let tmux = std::env::var_os("TMUX");
+    set_option_with(tmux.as_deref(), name, value, |command| {
+        let status = command.status()?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(std::io::Error::other(format!("tmux {status}")))
+        }
+    })
+}
+
+/// Like [`set_option`], but with the `$TMUX` value and the process runner
+/// injected so the guard can be tested without a tmux server.
+pub fn set_option_with<F>(tmux: Option<&OsStr>, name: &str, value: &str, run: F) -> Result<bool>
+where
+    F: FnOnce(&mut Command) -> std::io::Result<()>,
+{
+    if tmux.is_none_or(OsStr::is_empty) {
+        return Ok(false);
+    }
+
+    let mut command = Command::new("tmux");
+    command.args(["set-option", "-g", name, value]);
+    run(&mut command).with_context(|| format!("Failed to set the tmux option {name}"))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::ffi::OsString;
+
+    #[test]
+    fn refresh_client_runs_tmux_inside_tmux() -> Result<()> {
+        let invoked = RefCell::new(Vec::new());
+        let tmux = OsStr::new("/tmp/tmux-1000/default,1234,0");
+
+        let refreshed = refresh_client_with(Some(tmux), |command| {
+            let args = command.get_args().map(|a| a.to_owned()).collect::<Vec<_>>();
+            invoked
+                .borrow_mut()
+                .push((command.get_program().to_owned(), args));
+            Ok(())
+        })?;
+
+        assert!(refreshed);
+        assert_eq!(
+            invoked.into_inner(),
+            vec![("tmux".into(), vec!["refresh-client".into(), "-S".into()])]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn refresh_client_does_nothing_outside_tmux() -> Result<()> {
+        let invoked = RefCell::new(false);
+
+        for tmux in [None, Some(OsStr::new(""))] {
+            let refreshed = refresh_client_with(tmux, |_| {
+                *invoked.borrow_mut() = true;
+                Ok(())
+            })?;
+            assert!(!refreshed);
+        }
+
+        assert!(
+            !invoked.into_inner(),
+            "tmux must not run when $TMUX is unset"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn set_option_runs_tmux_inside_tmux() -> Result<()> {
+        let invoked = RefCell::new(Vec::new());
+        let tmux = OsStr::new("/tmp/tmux-1000/default,1234,0");
+
+        let set = set_option_with(Some(tmux), STATUS_OPTION, "🍅 24:59", |command| {
+            let args = command.get_args().map(|a| a.to_owned()).collect::<Vec<_>>();
+            invoked.borrow_mut().push(args);
+            Ok(())
+        })?;
+
+        assert!(set);
+        assert_eq!(
+            invoked.into_inner(),
+            vec![vec![
+                OsString::from("set-option"),
+                "-g".into(),
+                "@pomodoro".into(),
+                "🍅 24:59".into()
+            ]]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn set_option_does_nothing_outside_tmux() -> Result<()> {
+        for tmux in [None, Some(OsStr::new(""))] {
+            let set = set_option_with(tmux, STATUS_OPTION, "🍅 24:59", |_| {
+                panic!("tmux must not run when $TMUX is unset")
+            })?;
+            assert!(!set);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn set_option_fails_when_tmux_is_missing() {
+        let tmux = OsStr::new("/tmp/tmux-1000/default,1234,0");
+        let error = set_option_with(Some(tmux), STATUS_OPTION, "🍅 24:59", |_| {
+            Err(std::io::ErrorKind::NotFound.into())
+        })
+        .unwrap_err();
+        assert_eq!(error.to_string(), "Failed to set the tmux option @pomodoro");
+    }
+}
@@ -2,15 +2,44 @@ use crate::state::model::*;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::io::Write;
-use std::path::PathBuf;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+/// Example `start` hook written by `pomodoro init`. Everything but the shebang
+/// is commented out, and the file is left non-executable until the user enables it.
+pub const START_HOOK_TEMPLATE: &str = r##"#!/bin/sh
+# pomodoro start hook: runs when a session is started or resumed.
+# Enable it with `chmod +x` on this file.
+#
+# The session and the event arrive as JSON on stdin, e.g.
+#   {"session": {"kind": "focus", ...}, "session_event": {"kind": "started", ...}}
+
+# payload=$(cat)
+# kind=$(printf '%s' "$payload" | jq -r .session.kind)
+# notify-send pomodoro "The $kind session is running"
+"##;
+
+/// Example `stop` hook written by `pomodoro init`, see [`START_HOOK_TEMPLATE`].
+pub const STOP_HOOK_TEMPLATE: &str = r##"#!/bin/sh
+# pomodoro stop hook: runs when a session is paused, aborted, or completed.
+# Enable it with `chmod +x` on this file.
+#
+# The session and the event arrive as JSON on stdin, e.g.
+#   {"session": {"kind": "focus", ...}, "session_event": {"kind": "completed", ...}}
+
+# payload=$(cat)
+# event=$(printf '%s' "$payload" | jq -r .session_event.kind)
+# notify-send pomodoro "The session was $event"
+"##;
+
 /// Arguments passed to a hook script as a JSON payload over stdin.
 ///
 /// Both fields are serialized together so the hook receives full context
 /// about the session and the event that triggered it.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct SessionEventArgs {
     /// The session associated with the event.
     pub session: Session,
@@ -18,15 +47,46 @@ pub struct SessionEventArgs {
     pub session_event: SessionEvent,
 }
 
+/// A hook script location recognized by [`Runner`] and what was found there.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct HookInfo {
+    /// Path of the hook script.
+    pub path: PathBuf,
+    /// Whether a file exists at `path`.
+    pub exists: bool,
+    /// Whether that file has any execute bit set; a hook that is not
+    /// executable fails to spawn.
+    pub executable: bool,
+}
+
+impl HookInfo {
+    /// Inspect the file at `path`.
+    fn new(path: PathBuf) -> Self {
+        let metadata = std::fs::metadata(&path).ok().filter(|m| m.is_file());
+        Self {
+            exists: metadata.is_some(),
+            executable: metadata.is_some_and(|m| m.permissions().mode() & 0o111 != 0),
+            path,
+        }
+    }
+}
+
 /// Executes user-defined hook scripts when session state changes.
 ///
-/// Hook scripts live under `$XDG_CONFIG_HOME/pomodoro/hooks/` and are named
-/// after the event kind: `start` for [`SessionEventKind::Started`] /
-/// [`SessionEventKind::Resumed`], and `stop` for all other events.
-/// A missing hook file is silently ignored.
+/// Hook scripts live under `$XDG_CONFIG_HOME/pomodoro/hooks/`. For every
+/// event the first existing script of `<kind>/<event>` (e.g. `break/completed`),
+/// `<event>` (e.g. `completed`), and the grouped `start` / `stop` is run — see
+/// [`Runner::resolve`]. A missing hook file is silently ignored, unless the
+/// runner is [required](Runner::with_required).
+#[derive(Clone)]
 pub struct Runner {
     /// Absolute path to the hooks directory (`…/pomodoro/hooks/`).
     path: PathBuf,
+    /// Treat a missing hook file as an error instead of skipping it.
+    required: bool,
+    /// Hooks held back by [`Runner::notify`] until [`Runner::flush`]; `None`
+    /// unless the runner is [deferred](Runner::deferred). Shared by clones.
+    queue: Option<Arc<Mutex<Vec<SessionEventArgs>>>>,
 }
 
 impl Runner {
@@ -40,14 +100,39 @@ impl Runner {
             .context("Failed to determine configuration path")?
             .join("hooks");
 
-        Ok(Self { path })
+        Ok(Self {
+            path,
+            required: false,
+            queue: None,
+        })
+    }
+
+    /// Return the hooks directory.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Make a missing hook file an error (see `--require-hooks`), so a wrong
+    /// hooks path shows up while setting up hooks instead of doing nothing.
+    pub fn with_required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// Hold back the hooks passed to [`Runner::notify`] until
+    /// [`Runner::flush`] runs them, so that a hook only ever reports an event
+    /// once the transaction recording it committed.
+    pub fn deferred(mut self) -> Self {
+        self.queue = Some(Arc::default());
+        self
     }
 
     /// Run the hook script that corresponds to the event in `args`.
     ///
-    /// The script path is `<hooks_dir>/<name>` where `<name>` is `"start"` or
-    /// `"stop"` (see [`Runner::name`]). If no file exists at that path the
-    /// method returns `Ok(())` immediately.
+    /// The script is picked by [`Runner::resolve`]. If none of the candidate
+    /// files exists the method returns `Ok(None)` immediately, or an error when
+    /// the runner is required. Otherwise it returns the name of the hook that
+    /// ran, relative to the hooks directory (e.g. `start` or `break/completed`).
     ///
     /// When the script exists it is spawned as a child process with its stdin
     /// connected to a pipe and stdout suppressed. A JSON-serialized
@@ -56,14 +141,16 @@ impl Runner {
     ///
     /// # Errors
     ///
-    /// Returns an error if JSON serialization or process spawning fails.
-    /// Call sites that treat hooks as non-fatal should discard the error
-    /// with `.ok()`.
-    pub fn execute(&self, args: &SessionEventArgs) -> Result<()> {
-        let path = self.path.join(self.name(args));
-        if !path.exists() {
-            return Ok(());
-        }
+    /// Returns an error if the hook is required but missing, or if JSON
+    /// serialization or process spawning fails. Call sites that treat hooks as
+    /// non-fatal should use [`Runner::notify`] instead.
+    pub fn execute(&self, args: &SessionEventArgs) -> Result<Option<String>> {
+        let Some(path) = self.resolve(args) else {
+            let path = self.path.join(self.name(args));
+            anyhow::ensure!(!self.required, "Hook {} does not exist", path.display());
+            return Ok(None);
+        };
+        let name = self.hook_name(&path);
 
         let data = serde_json::to_string(args).context("Failed to serialize hook arguments")?;
 
@@ -96,17 +183,115 @@ impl Runner {
                 .context("Failed to write hook arguments")?;
         }
         // Drop `process` without wait() — child runs detached; stdin EOF was already sent.
-        Ok(())
+        Ok(Some(name))
+    }
+
+    /// Run the hook for `args` on behalf of a command.
+    ///
+    /// Hooks are non-fatal, so a broken hook never kills the session: every
+    /// error of [`Runner::execute`] is ignored, except a missing hook on a
+    /// required runner — that is a setup mistake the user asked to hear about.
+    ///
+    /// Returns the name of the hook that ran, as [`Runner::execute`] does.
+    /// A [deferred](Runner::deferred) runner only queues the hook and returns
+    /// the name of the hook that [`Runner::flush`] is going to run.
+    pub fn notify(&self, args: &SessionEventArgs) -> Result<Option<String>> {
+        if let Some(queue) = &self.queue {
+            let Some(path) = self.resolve(args) else {
+                let path = self.path.join(self.name(args));
+                anyhow::ensure!(!self.required, "Hook {} does not exist", path.display());
+                return Ok(None);
+            };
+            queue
+                .lock()
+                .expect("hook queue poisoned")
+                .push(args.clone());
+            return Ok(Some(self.hook_name(&path)));
+        }
+        match self.execute(args) {
+            Ok(name) => Ok(name),
+            Err(error) if self.required && self.resolve(args).is_none() => Err(error),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Run the hooks queued by a [deferred](Runner::deferred) runner, in the
+    /// order they were queued. Like [`Runner::notify`], a failing hook is ignored.
+    pub fn flush(&self) {
+        let Some(queue) = &self.queue else { return };
+        let queued = std::mem::take(&mut *queue.lock().expect("hook queue poisoned"));
+        for args in &queued {
+            self.execute(args).ok();
+        }
+    }
+
+    /// Drop the hooks queued by a [deferred](Runner::deferred) runner without
+    /// running them, e.g. because their transaction was rolled back.
+    pub fn discard(&self) {
+        if let Some(queue) = &self.queue {
+            queue.lock().expect("hook queue poisoned").clear();
+        }
+    }
+
+    /// List every hook location the runner looks at — the grouped `start` and
+    /// `stop`, one per event, and one per session kind and event — and
+    /// whether an executable script is installed there.
+    pub fn installed_hooks(&self) -> Vec<HookInfo> {
+        let events = [
+            SessionEventKind::Started,
+            SessionEventKind::Paused,
+            SessionEventKind::Resumed,
+            SessionEventKind::Completed,
+            SessionEventKind::Aborted,
+            SessionEventKind::Interrupted,
+        ]
+        .map(|event| event.to_string());
+
+        let mut paths = vec![self.path.join("start"), self.path.join("stop")];
+        paths.extend(events.iter().map(|event| self.path.join(event)));
+        for kind in [SessionKind::Focus, SessionKind::Break] {
+            let dir = self.path.join(kind.to_string());
+            paths.extend(events.iter().map(|event| dir.join(event)));
+        }
+        paths.into_iter().map(HookInfo::new).collect()
+    }
+
+    /// Find the hook script for `args`, from the most to the least specific:
+    ///
+    /// 1. `<hooks_dir>/<kind>/<event>`, e.g. `break/completed`;
+    /// 2. `<hooks_dir>/<event>`, e.g. `completed`;
+    /// 3. `<hooks_dir>/start` or `<hooks_dir>/stop` (see [`Runner::name`]).
+    ///
+    /// Returns `None` when none of them exists.
+    fn resolve(&self, args: &SessionEventArgs) -> Option<PathBuf> {
+        let kind = args.session.kind.to_string();
+        let event = args.session_event.kind.to_string();
+        [
+            self.path.join(kind).join(&event),
+            self.path.join(&event),
+            self.path.join(self.name(args)),
+        ]
+        .into_iter()
+        .find(|path| path.is_file())
+    }
+
+    /// Return the name of the hook at `path`, relative to the hooks directory
+    /// (e.g. `start` or `break/completed`).
+    fn hook_name(&self, path: &Path) -> String {
+        let name = path.strip_prefix(&self.path).unwrap_or(path);
+        name.display().to_string()
     }
 
     /// Map an event to the hook file name: `"start"` for started/resumed
-    /// events, `"stop"` for everything else.
+    /// events, `"stop"` for the events ending or pausing the session.
+    ///
+    /// An interruption changes neither, so it has no grouped hook and maps to
+    /// its own event name, `"interrupted"`.
     fn name(&self, args: &SessionEventArgs) -> &str {
-        let kind = &args.session_event.kind;
-        if matches!(kind, SessionEventKind::Started | SessionEventKind::Resumed) {
-            "start"
-        } else {
-            "stop"
+        match args.session_event.kind {
+            SessionEventKind::Started | SessionEventKind::Resumed => "start",
+            SessionEventKind::Interrupted => "interrupted",
+            _ => "stop",
         }
     }
 }
@@ -115,14 +300,17 @@ impl Runner {
 mod tests {
     use super::*;
     use std::fs;
-    use std::os::unix::fs::PermissionsExt;
     use uuid::Uuid;
 
     /// Create a [`Runner`] backed by a unique temporary hooks directory.
     fn setup() -> Result<Runner> {
         let path = std::env::temp_dir().join(format!("pomodoro-hook-{}", Uuid::now_v7()));
         fs::create_dir_all(&path)?;
-        Ok(Runner { path })
+        Ok(Runner {
+            path,
+            required: false,
+            queue: None,
+        })
     }
 
     /// Poll until `path` exists **and** has non-zero size, or a 500 ms deadline is reached.
@@ -147,6 +335,9 @@ mod tests {
     fn install_hook(runner: &Runner, name: &str) -> Result<PathBuf> {
         let script = runner.path.join(name);
         let output = runner.path.join(format!("{name}.json"));
+        if let Some(parent) = script.parent() {
+            fs::create_dir_all(parent)?;
+        }
         fs::write(&script, format!("#!/bin/sh\ncat > {}", output.display()))?;
         fs::set_permissions(&script, fs::Permissions::from_mode(0o755))?;
         Ok(output)
@@ -163,7 +354,8 @@ mod tests {
             session: session.clone(),
             session_event: session_event.clone(),
         };
-        runner.execute(&args)
+        assert_eq!(runner.execute(&args)?, None);
+        Ok(())
     }
 
     #[test]
@@ -175,7 +367,95 @@ mod tests {
             session: session.clone(),
             session_event: session_event.clone(),
         };
-        runner.execute(&args)
+        assert_eq!(runner.execute(&args)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_hook_returns_error_when_required() -> Result<()> {
+        let runner = setup()?.with_required(true);
+        let session = Session::default();
+        let session_event = SessionEvent::started(session.id);
+        let args = SessionEventArgs {
+            session: session.clone(),
+            session_event: session_event.clone(),
+        };
+
+        let error = runner
+            .execute(&args)
+            .expect_err("a required hook is missing");
+        assert!(error.to_string().ends_with("/start does not exist"));
+        assert!(runner.notify(&args).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn notify_ignores_missing_hook_when_not_required() -> Result<()> {
+        let runner = setup()?;
+        let session = Session::default();
+        let session_event = SessionEvent::paused(session.id);
+        let args = SessionEventArgs {
+            session: session.clone(),
+            session_event: session_event.clone(),
+        };
+        assert_eq!(runner.notify(&args)?, None);
+        Ok(())
+    }
+
+    // --- deferred hooks ---
+
+    #[test]
+    fn deferred_runner_runs_hooks_only_when_flushed() -> Result<()> {
+        let runner = setup()?.deferred();
+        let path = install_hook(&runner, "start")?;
+
+        let session = Session::default();
+        let session_event = SessionEvent::started(session.id);
+        let args = SessionEventArgs {
+            session: session.clone(),
+            session_event: session_event.clone(),
+        };
+        assert_eq!(runner.notify(&args)?.as_deref(), Some("start"));
+        assert!(!wait_for_file(&path), "hook ran before the flush");
+
+        // Discarded hooks never run, even when the runner is flushed later.
+        runner.discard();
+        runner.flush();
+        assert!(!wait_for_file(&path), "discarded hook ran");
+
+        runner.clone().notify(&args)?;
+        runner.flush();
+        assert!(wait_for_file(&path), "flushed hook was not invoked");
+        Ok(())
+    }
+
+    // --- installed hooks ---
+
+    #[test]
+    fn installed_hooks_reports_existing_and_executable_scripts() -> Result<()> {
+        let runner = setup()?;
+        install_hook(&runner, "start")?;
+        let script = runner.path.join("break/completed");
+        install_hook(&runner, "break/completed")?;
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o644))?;
+
+        let hooks = runner.installed_hooks();
+        let find = |name: &str| {
+            hooks
+                .iter()
+                .find(|hook| hook.path == runner.path.join(name))
+                .cloned()
+                .unwrap()
+        };
+
+        assert_eq!(hooks.len(), 20);
+        let start = find("start");
+        assert!(start.exists && start.executable);
+        let completed = find("break/completed");
+        assert!(completed.exists && !completed.executable);
+        let stop = find("stop");
+        assert!(!stop.exists && !stop.executable);
+        Ok(())
     }
 
     // --- hook routing ---
@@ -191,7 +471,7 @@ mod tests {
             session: session.clone(),
             session_event: session_event.clone(),
         };
-        runner.execute(&args)?;
+        assert_eq!(runner.execute(&args)?.as_deref(), Some("start"));
 
         assert!(
             wait_for_file(&path),
@@ -280,6 +560,102 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn kind_specific_hook_takes_precedence() -> Result<()> {
+        let runner = setup()?;
+        let specific = install_hook(&runner, "break/completed")?;
+        let generic = install_hook(&runner, "completed")?;
+        let grouped = install_hook(&runner, "stop")?;
+
+        let session = Session {
+            kind: SessionKind::Break,
+            ..Session::default()
+        };
+        let session_event = SessionEvent::completed(session.id);
+        let args = SessionEventArgs {
+            session: session.clone(),
+            session_event: session_event.clone(),
+        };
+        assert_eq!(runner.execute(&args)?.as_deref(), Some("break/completed"));
+
+        assert!(
+            wait_for_file(&specific),
+            "break/completed hook was not invoked for a completed break"
+        );
+        assert!(!generic.exists() && !grouped.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn event_hook_takes_precedence_over_grouped_hook() -> Result<()> {
+        let runner = setup()?;
+        // Only break sessions have a kind-specific hook.
+        let specific = install_hook(&runner, "break/completed")?;
+        let generic = install_hook(&runner, "completed")?;
+        let grouped = install_hook(&runner, "stop")?;
+
+        let session = Session::default();
+        let session_event = SessionEvent::completed(session.id);
+        let args = SessionEventArgs {
+            session: session.clone(),
+            session_event: session_event.clone(),
+        };
+        runner.execute(&args)?;
+
+        assert!(
+            wait_for_file(&generic),
+            "completed hook was not invoked for a completed focus session"
+        );
+        assert!(!specific.exists() && !grouped.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn grouped_hook_is_the_last_fallback() -> Result<()> {
+        let runner = setup()?;
+        let generic = install_hook(&runner, "completed")?;
+        let grouped = install_hook(&runner, "stop")?;
+
+        let session = Session::default();
+        let session_event = SessionEvent::paused(session.id);
+        let args = SessionEventArgs {
+            session: session.clone(),
+            session_event: session_event.clone(),
+        };
+        runner.execute(&args)?;
+
+        assert!(
+            wait_for_file(&grouped),
+            "stop hook was not invoked for a paused event without a paused hook"
+        );
+        assert!(!generic.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn interrupted_event_never_invokes_stop_hook() -> Result<()> {
+        let runner = setup()?;
+        let grouped = install_hook(&runner, "stop")?;
+
+        let session = Session::default();
+        let session_event =
+            SessionEvent::interrupted(session.id, InterruptionSource::External, None);
+        let args = SessionEventArgs {
+            session: session.clone(),
+            session_event: session_event.clone(),
+        };
+        assert_eq!(runner.execute(&args)?, None);
+
+        let path = install_hook(&runner, "interrupted")?;
+        assert_eq!(runner.execute(&args)?.as_deref(), Some("interrupted"));
+        assert!(
+            wait_for_file(&path),
+            "interrupted hook was not invoked for an interrupted event"
+        );
+        assert!(!grouped.exists());
+        Ok(())
+    }
+
     // --- JSON payload ---
 
     #[test]
@@ -4,20 +4,71 @@ mod state;
 
 use crate::app::cli::*;
 use crate::app::cmd::*;
+use crate::app::tmux;
 use crate::hook::run::*;
 use crate::state::query::*;
 use clap::Parser;
+use rusqlite::TransactionBehavior;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let program = Program::parse();
     let program_config = ProgramConfig::load().unwrap_or_default();
+    // POMODORO_DISABLED lets a wrapper switch the timer off (e.g. during
+    // meetings) without removing any key bindings.
+    let disabled = std::env::var_os("POMODORO_DISABLED").is_some_and(|value| !value.is_empty());
+
+    // Editing the configuration never touches the database, so handle it before
+    // opening one: its lock must not be held while the editor is open.
+    if let ProgramCommand::EditConfig = program.command {
+        let command = EditConfigCommand {
+            path: ProgramConfig::path()?,
+            editor: EditConfigCommand::editor(),
+        };
+        println!("{}", command.execute()?);
+        return Ok(());
+    }
+
+    // Likewise, scaffolding the configuration and hooks needs no database.
+    if let ProgramCommand::Init(args) = &program.command {
+        let command = InitCommand {
+            config_path: ProgramConfig::toml_path()?,
+            hooks_path: Runner::try_new()?.path().to_path_buf(),
+        };
+        println!("{}", command.execute(args)?);
+        return Ok(());
+    }
+
+    // `status --cached` answers from the status cache without opening the
+    // database, unless there is no cache or the running session needs completing.
+    // A guard with --assert-running always reads the database.
+    if let ProgramCommand::Status(args) = &program.command {
+        if args.cached && !args.debug && !args.assert_running && !disabled {
+            let cache = StatusCache::read(&StatusCache::path()?).ok();
+            let now = chrono::Utc::now();
+            if let Some(status) = cache.and_then(|c| c.status_at(now, &program_config)) {
+                let output = render_status(&status, args, &program_config)?;
+                if args.no_newline || output.is_empty() {
+                    print!("{}", output);
+                    std::io::stdout().flush()?;
+                } else {
+                    println!("{}", output);
+                }
+                return Ok(());
+            }
+        }
+    }
 
     // Create the hook runner unless --no-hooks was passed.
     let runner = if program.no_hooks {
         None
     } else {
-        Some(Runner::try_new()?)
+        Some(Runner::try_new()?.with_required(program.require_hooks))
     };
+    // The hooks fired inside the transaction wait for it to commit.
+    let hooks = runner.clone().map(Runner::deferred);
 
     // Open (or create) the database. --in-memory uses an ephemeral SQLite
     // database that vanishes when the process exits; useful for testing and
@@ -26,32 +77,367 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Database::open_in_memory()?
     } else {
         Database::open()?
-    };
+    }
+    .with_retry_policy(RetryPolicy {
+        max_retries: program_config.lock_retries,
+        ..RetryPolicy::default()
+    });
     // Migrate the datbase prior to its usage.
     database.migrate()?;
 
-    // Wrap the entire command in a single transaction so that any partial
-    // failure (e.g. session inserted but event write fails) rolls back cleanly.
-    let tx = database.transaction()?;
-    let querier = Querier::new(&tx);
+    // Run the entire command in a single transaction so that any partial
+    // failure (e.g. session inserted but event write fails) rolls back cleanly,
+    // and that a command losing a race for the lock is retried as a whole.
+    // Commands that only read defer locking, so that they never hold up a
+    // writer. So does `status`, which is polled the most: when it records a
+    // completion and loses the race for the write lock, it is retried as well.
+    let behavior = match program.command {
+        ProgramCommand::Status(_)
+        | ProgramCommand::Context(_)
+        | ProgramCommand::Stats(_)
+        | ProgramCommand::Notes(_)
+        | ProgramCommand::Replay(_)
+        | ProgramCommand::List(_) => TransactionBehavior::Deferred,
+        _ => TransactionBehavior::Immediate,
+    };
+    // Arguments of a `start --wait` invocation, handled once the transaction is committed.
+    let mut wait = None;
+    // Arguments of a `serve` invocation, served once the transaction is committed.
+    let mut serve = None;
+    // Arguments of a `watch` invocation, watched once the transaction is committed.
+    let mut watch = None;
+    // `status --no-newline` prints the output without the trailing newline.
+    let mut newline = true;
+    // Whether a session is running, for `status --assert-running`.
+    let mut running = None;
+    // Every command that may record an event refreshes the status cache.
+    let refresh_cache = program_config.status_cache
+        && !program.in_memory
+        && matches!(
+            program.command,
+            ProgramCommand::Start(_)
+                | ProgramCommand::Stop(_)
+                | ProgramCommand::Cancel(_)
+                | ProgramCommand::Pause(_)
+                | ProgramCommand::Resume(_)
+                | ProgramCommand::Extend(_)
+                | ProgramCommand::Interrupt(_)
+                | ProgramCommand::Status(_)
+                | ProgramCommand::Summary(_)
+                | ProgramCommand::Log(_)
+                | ProgramCommand::Gc(_)
+                | ProgramCommand::FocusOn(_)
+                | ProgramCommand::Suspend(_)
+                | ProgramCommand::ResumeFromSuspend(_)
+        );
+    // Changes made before the command, e.g. by the migrations, do not count
+    // towards refreshing tmux.
+    let changes = database.total_changes();
 
-    match program.command {
-        ProgramCommand::Start(args) => {
-            let args = args.with_config(&program_config);
-            let command = StartCommand { runner, querier };
-            command.execute(&args)?
+    let output = database.with_transaction(behavior, |querier| {
+        // A retry starts over, so drop the hooks of the attempt rolled back.
+        if let Some(hooks) = &hooks {
+            hooks.discard();
         }
-        ProgramCommand::Stop(args) => {
-            let command = StopCommand { runner, querier };
-            command.execute(&args)?
-        }
-        ProgramCommand::Status(args) => {
-            let command = StatusCommand { runner, querier };
-            command.execute(&args)?
+        let output = match &program.command {
+            ProgramCommand::Start(_)
+            | ProgramCommand::Stop(_)
+            | ProgramCommand::Cancel(_)
+            | ProgramCommand::Pause(_)
+            | ProgramCommand::Resume(_)
+            | ProgramCommand::Extend(_)
+            | ProgramCommand::Interrupt(_)
+            | ProgramCommand::Log(_)
+            | ProgramCommand::Suspend(_)
+            | ProgramCommand::ResumeFromSuspend(_)
+            | ProgramCommand::Note(_)
+            | ProgramCommand::Gc(_)
+            | ProgramCommand::FocusOn(_)
+                if disabled =>
+            {
+                "pomodoro disabled".to_string()
+            }
+            ProgramCommand::Start(args) => {
+                let command = StartCommand {
+                    runner: hooks.clone(),
+                    querier,
+                    config: program_config.clone(),
+                };
+                let args = args.clone().with_until(chrono::Local::now())?;
+                let outcome = command.execute(&args)?.strict(program.strict)?;
+                for warning in &outcome.warnings {
+                    eprintln!("warning: {}", warning);
+                }
+                let mut lines = Vec::new();
+                if !args.quiet {
+                    lines.push(outcome.render(args.output, args.format.as_deref())?);
+                }
+                if args.print_ends_at {
+                    lines.extend(outcome.ends_at_rfc3339());
+                }
+                let output = lines.join("\n");
+                newline = !output.is_empty();
+                if args.wait {
+                    wait = Some((args, runner.clone()));
+                }
+                output
+            }
+            ProgramCommand::Stop(args) => {
+                let command = StopCommand {
+                    runner: hooks.clone(),
+                    querier,
+                };
+                let outcome = command.execute(args)?.strict(program.strict)?;
+                outcome.render(args.output, args.format.as_deref())?
+            }
+            ProgramCommand::Cancel(args) => {
+                let command = CancelCommand {
+                    runner: hooks.clone(),
+                    querier,
+                };
+                let outcome = command.execute()?.strict(program.strict)?;
+                outcome.render(args.output, None)?
+            }
+            ProgramCommand::Pause(args) => {
+                let command = PauseCommand {
+                    runner: hooks.clone(),
+                    querier,
+                };
+                let outcome = command.execute()?.strict(program.strict)?;
+                outcome.render(args.output, None)?
+            }
+            ProgramCommand::Resume(args) => {
+                let command = ResumeCommand {
+                    runner: hooks.clone(),
+                    querier,
+                };
+                let outcome = command.execute()?.strict(program.strict)?;
+                outcome.render(args.output, None)?
+            }
+            ProgramCommand::Extend(args) => {
+                let command = ExtendCommand { querier };
+                let outcome = command.execute(args)?;
+                outcome.render(args.output, None)?
+            }
+            ProgramCommand::Interrupt(args) => {
+                let command = InterruptCommand {
+                    runner: hooks.clone(),
+                    querier,
+                };
+                let outcome = command.execute(args)?.strict(program.strict)?;
+                outcome.render(args.output, None)?
+            }
+            ProgramCommand::Status(args) => {
+                let command = StatusCommand {
+                    runner: hooks.clone(),
+                    querier,
+                    config: ProgramConfig {
+                        auto_complete: program_config.auto_complete && !args.no_auto_complete,
+                        ..program_config.clone()
+                    },
+                };
+                let mut status = if disabled {
+                    SessionStatus::disabled()
+                } else {
+                    command.execute()?
+                };
+                if args.debug {
+                    command.include_totals(&mut status)?;
+                }
+                if status.stale {
+                    eprintln!(
+                        "warning: the {} session ran for over {}; was it left running?",
+                        status.kind,
+                        humantime::format_duration(program_config.stale_after)
+                    );
+                }
+                if args.assert_running {
+                    running = Some(matches!(status.state, SessionState::Running));
+                }
+                // Nothing is left to print once `--set-tmux-option` handed the
+                // status to tmux, or for a quiet `--assert-running`.
+                let output = if args.assert_running && !args.verbose {
+                    String::new()
+                } else {
+                    command.render(&status, args)?
+                };
+                newline = !args.no_newline && !output.is_empty();
+                output
+            }
+            ProgramCommand::Summary(args) => {
+                let command = SummaryCommand {
+                    runner: hooks.clone(),
+                    querier,
+                    config: program_config.clone(),
+                };
+                let digest = command.execute()?;
+                command.render(&digest, args)?
+            }
+            ProgramCommand::Stats(args) => {
+                let command = StatsCommand { querier };
+                let report = command.execute(args)?;
+                command.render(&report, args)?
+            }
+            ProgramCommand::Context(args) => {
+                let command = ContextCommand {
+                    runner: hooks.clone(),
+                    querier,
+                    config_path: ProgramConfig::path().ok(),
+                    database_path: (!program.in_memory).then(Database::path).transpose()?,
+                };
+                let context = command.execute()?;
+                command.render(&context, args)?
+            }
+            ProgramCommand::Serve(args) => {
+                serve = Some(args);
+                format!("Listening on {}", args.socket.display())
+            }
+            ProgramCommand::Watch(args) => {
+                // Every status line is printed by the watch itself.
+                watch = Some(args);
+                newline = false;
+                String::new()
+            }
+            ProgramCommand::FocusOn(args) => {
+                let command = FocusOnCommand { querier };
+                command.execute(args)?
+            }
+            ProgramCommand::Note(args) => {
+                let command = NoteCommand { querier };
+                command.execute(args)?
+            }
+            ProgramCommand::Notes(args) => {
+                let command = NotesCommand { querier };
+                let notes = command.execute(args)?;
+                command.render(&notes, args)?
+            }
+            ProgramCommand::Replay(args) => {
+                let command = ReplayCommand { querier };
+                let replay = command.execute(args)?;
+                command.render(&replay, args)?
+            }
+            ProgramCommand::List(args) => {
+                let command = ListCommand { querier };
+                let records = command.execute(args)?;
+                let output = command.render(&records, args)?;
+                newline = !output.is_empty();
+                output
+            }
+            ProgramCommand::Gc(args) => {
+                let command = GcCommand { querier };
+                command.execute(args)?
+            }
+            ProgramCommand::EditConfig => {
+                unreachable!("edit-config is handled before the database")
+            }
+            ProgramCommand::Init(_) => unreachable!("init is handled before the database"),
+            ProgramCommand::Log(args) => {
+                let command = LogCommand {
+                    runner: hooks.clone(),
+                    querier,
+                };
+                command.execute(args)?.render(args.output, None)?
+            }
+            ProgramCommand::Suspend(args) => {
+                let command = SuspendCommand {
+                    runner: hooks.clone(),
+                    querier,
+                };
+                let outcome = command.execute()?.strict(program.strict)?;
+                outcome.render(args.output, None)?
+            }
+            ProgramCommand::ResumeFromSuspend(args) => {
+                let command = ResumeFromSuspendCommand {
+                    runner: hooks.clone(),
+                    querier,
+                };
+                let outcome = command.execute()?.strict(program.strict)?;
+                outcome.render(args.output, None)?
+            }
+        };
+        Ok(output)
+    })?;
+
+    // Whether the command wrote anything, before the cache refresh reads the status.
+    let changed = database.total_changes() > changes;
+
+    // Only report the events to the hooks once they are durable.
+    if let Some(hooks) = &hooks {
+        hooks.flush();
+    }
+
+    // The command already committed, so a stale cache is not worth failing over.
+    if refresh_cache {
+        let refreshed = StatusCache::path()
+            .and_then(|path| StatusCache::refresh(&mut database, &program_config, &path));
+        if let Err(error) = refreshed {
+            eprintln!("warning: {:#}", error);
         }
     }
 
-    tx.commit()?;
+    // Bind before reporting, so that "Listening" means clients can connect.
+    let serve = serve
+        .map(|args| ServeCommand::bind(&args.socket).map(|listener| (args, listener)))
+        .transpose()?;
+
+    // Only report the outcome once it is durable.
+    if newline {
+        println!("{}", output);
+    } else {
+        print!("{}", output);
+        std::io::stdout().flush()?;
+    }
+
+    // Only a command that changed something redraws the status line. `status`
+    // is what the tmux status line runs, but it only writes when it completes
+    // a session, so refreshing after that never loops.
+    let refresh_tmux = program.refresh_tmux || program_config.refresh_tmux;
+    if refresh_tmux && changed {
+        // A failed refresh only delays the redraw — never fail the command.
+        tmux::refresh_client().ok();
+    }
+
+    // Fail the guard quietly: the exit code is the answer.
+    if running == Some(false) {
+        std::process::exit(1);
+    }
+
+    // Block outside of the transaction so that other invocations can still
+    // pause, resume, or abort the session we are waiting on.
+    if let Some((args, runner)) = wait {
+        let mut command = WaitCommand {
+            runner,
+            config: program_config.clone(),
+            database: &mut database,
+        };
+        command.execute(&args)?;
+    }
+
+    // Like waiting, serving runs outside of the transaction: every request
+    // reads the database in a transaction of its own.
+    if let Some((args, listener)) = serve {
+        let command = ServeCommand {
+            config: program_config.clone(),
+            disabled,
+            database: Mutex::new(&mut database),
+        };
+        command.execute(&listener, args)?;
+    }
+
+    // Watching runs outside of the transaction as well. SIGINT only stops the
+    // loop, so that a watch interrupted with Ctrl-C exits successfully.
+    if let Some(args) = watch {
+        let stop = Arc::new(AtomicBool::new(false));
+        let handler = Arc::clone(&stop);
+        ctrlc::set_handler(move || handler.store(true, Ordering::Relaxed))?;
+        let mut command = WatchCommand {
+            config: program_config,
+            disabled,
+            database: &mut database,
+        };
+        command.execute(std::io::stdout(), args, &stop)?;
+    }
+
     // We are done!
     Ok(())
 }
@@ -0,0 +1,226 @@
+use anyhow::{Context, Result};
+use rusqlite::{Connection, Transaction, TransactionBehavior};
+
+/// Migration upgrades the schema from the previous version to `version`.
+pub struct Migration {
+    /// Schema version of the database once the migration is applied.
+    pub version: i64,
+    /// SQL script that performs the migration, embedded at compile time.
+    pub script: &'static str,
+    /// Columns as `(table, column, definition)` that are added after the
+    /// script unless they exist, for tables the script only creates when
+    /// missing (see [`add_column_if_missing`]).
+    pub columns: &'static [(&'static str, &'static str, &'static str)],
+}
+
+/// MIGRATIONS in the order they are applied. A migration is never edited once
+/// released: change the schema by appending a new one instead.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        script: include_str!("migrations/0001_initial.sql"),
+        // Databases created before the schema was versioned are at version 0
+        // as well, but already hold the tables, possibly without the columns
+        // that were added to them later.
+        columns: &[
+            ("session", "planned_human", "TEXT"),
+            ("session_event", "completion_source", "TEXT"),
+        ],
+    },
+    Migration {
+        version: 2,
+        script: include_str!("migrations/0002_session_tag.sql"),
+        columns: &[],
+    },
+    Migration {
+        version: 3,
+        script: include_str!("migrations/0003_session_event_interruption.sql"),
+        columns: &[],
+    },
+];
+
+/// Apply every pending migration of [`MIGRATIONS`] to `conn`.
+///
+/// The version of the last migration is the version of the schema this binary
+/// creates and understands, stored in the database as `PRAGMA user_version`.
+/// See [`apply`].
+pub fn migrate(conn: &Connection) -> Result<()> {
+    apply(conn, MIGRATIONS)
+}
+
+/// Apply the migrations of `migrations` newer than the database's
+/// `user_version`, in order.
+///
+/// Each migration runs in a transaction of its own together with the bump of
+/// `user_version`, so a failing migration leaves the database at the previous
+/// version rather than half-migrated. The version is read inside that
+/// transaction, so that two processes migrating at once never apply the same
+/// migration twice.
+///
+/// Fails without touching the database when it was migrated by a newer binary,
+/// since writing to a schema this binary does not know could corrupt it.
+fn apply(conn: &Connection, migrations: &[Migration]) -> Result<()> {
+    let latest = migrations.last().map_or(0, |migration| migration.version);
+    loop {
+        // The caller holds no other transaction on this connection while migrating.
+        let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)
+            .context("Failed to start transaction")?;
+        let version: i64 = tx
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .context("Failed to read the database version")?;
+        anyhow::ensure!(
+            version <= latest,
+            "The database is newer than this binary (schema version {version}, \
+             supported up to {latest}); upgrade pomodoro to use it"
+        );
+
+        let Some(migration) = migrations.iter().find(|m| m.version > version) else {
+            return Ok(());
+        };
+
+        tx.execute_batch(migration.script).with_context(|| {
+            format!(
+                "Failed to migrate database to version {}",
+                migration.version
+            )
+        })?;
+        for (table, column, definition) in migration.columns {
+            add_column_if_missing(&tx, table, column, definition)?;
+        }
+        tx.pragma_update(None, "user_version", migration.version)
+            .context("Failed to update the database version")?;
+        tx.commit().context("Failed to commit transaction")?;
+    }
+}
+
+/// Add `column` to `table` unless it already exists.
+///
+/// Only additive, nullable columns may be added this way, so that existing
+/// rows stay valid without a data migration.
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    let exists: bool = conn
+        .query_row(
+            "SELECT EXISTS (SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2)",
+            [table, column],
+            |row| row.get(0),
+        )
+        .context("Failed to inspect database schema")?;
+
+    if !exists {
+        conn.execute_batch(&format!(
+            "ALTER TABLE {table} ADD COLUMN {column} {definition}"
+        ))
+        .context("Failed to migrate database")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Version of the last migration, which a fresh database ends up at.
+    const LATEST_VERSION: i64 = MIGRATIONS.len() as i64;
+
+    /// Return the `user_version` of `conn`.
+    fn version(conn: &Connection) -> Result<i64> {
+        Ok(conn.pragma_query_value(None, "user_version", |row| row.get(0))?)
+    }
+
+    /// Return the SQL of every table and index in `conn`, i.e. its schema.
+    fn schema(conn: &Connection) -> Result<Vec<String>> {
+        let query = "SELECT sql FROM sqlite_master WHERE sql IS NOT NULL ORDER BY name";
+        let mut operation = conn.prepare(query)?;
+        let rows = operation.query_map([], |row| row.get(0))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    #[test]
+    fn migrations_are_numbered_consecutively() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, index as i64 + 1);
+        }
+    }
+
+    #[test]
+    fn migrate_brings_a_fresh_database_to_the_latest_version() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        migrate(&conn)?;
+
+        assert_eq!(version(&conn)?, LATEST_VERSION);
+        // The newest migration's column is there.
+        conn.execute_batch("SELECT tag FROM session")?;
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_twice_is_idempotent() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        migrate(&conn)?;
+        let before = schema(&conn)?;
+
+        migrate(&conn)?;
+
+        assert_eq!(schema(&conn)?, before);
+        assert_eq!(version(&conn)?, LATEST_VERSION);
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_applies_only_pending_migrations() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        apply(&conn, &MIGRATIONS[..1])?;
+        assert_eq!(version(&conn)?, 1);
+
+        // Re-running migration 1 would fail, since it is not applied again.
+        let migrations = &[
+            Migration {
+                version: 1,
+                script: "CREATE TABLE first (id INTEGER);",
+                columns: &[],
+            },
+            Migration {
+                version: 2,
+                script: "CREATE TABLE second (id INTEGER);",
+                columns: &[],
+            },
+        ];
+        apply(&conn, migrations)?;
+
+        assert_eq!(version(&conn)?, 2);
+        conn.execute_batch("SELECT id FROM second")?;
+        assert!(conn.execute_batch("SELECT id FROM first").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn failed_migration_keeps_the_previous_version() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        let migrations = &[
+            Migration {
+                version: 1,
+                script: "CREATE TABLE first (id INTEGER);",
+                columns: &[],
+            },
+            Migration {
+                version: 2,
+                script: "CREATE TABLE second (id INTEGER); SELECT * FROM missing;",
+                columns: &[],
+            },
+        ];
+
+        let error = apply(&conn, migrations).expect_err("migration 2 must fail");
+        assert_eq!(error.to_string(), "Failed to migrate database to version 2");
+
+        // Migration 1 is committed; migration 2 is rolled back as a whole.
+        assert_eq!(version(&conn)?, 1);
+        conn.execute_batch("SELECT id FROM first")?;
+        assert!(conn.execute_batch("SELECT id FROM second").is_err());
+        Ok(())
+    }
+}
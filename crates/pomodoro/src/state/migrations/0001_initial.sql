@@ -0,0 +1,46 @@
+-- sqlfluff:dialect:sqlite
+-- sqlfluff:max_line_length:1024
+-- sqlfluff:rules:capitalisation.keywords:capitalisation_policy:upper
+
+-- Session represents a single pomodoro session, which has a unique ID, a type
+-- (e.g., "focus" or "break"), a duration in seconds, and a timestamp for when
+-- it was created. The session_id is the primary key, and the planned_secs must
+-- be greater than 0. The optional planned_human keeps the duration as the user
+-- wrote it (e.g. "1h30m") for display; all calculations use planned_secs.
+CREATE TABLE IF NOT EXISTS session (
+    session_id TEXT PRIMARY KEY,
+    session_kind TEXT NOT NULL,
+    planned_secs INTEGER NOT NULL CHECK (planned_secs > 0),
+    created_at INTEGER NOT NULL,
+    planned_human TEXT
+);
+
+-- Session events are used to track the state of a session, such as when it
+-- starts, is paused, or ends. This allows us to reconstruct the session's
+-- history and determine its current state. Completed events record in
+-- completion_source whether the session ran out ("auto") or was completed
+-- early by the user ("manual"); it is NULL for every other event.
+CREATE TABLE IF NOT EXISTS session_event (
+    session_event_id TEXT PRIMARY KEY,
+    session_event_kind TEXT NOT NULL,
+    session_id TEXT NOT NULL REFERENCES session (session_id) ON DELETE CASCADE,
+    created_at INTEGER NOT NULL,
+    completion_source TEXT
+);
+
+-- Meta stores small key/value facts about the timer that do not belong to a
+-- single session or event, such as which pause was caused by a system
+-- suspend. Keys are defined by the application.
+CREATE TABLE IF NOT EXISTS meta (
+    meta_key TEXT PRIMARY KEY,
+    meta_value TEXT NOT NULL
+);
+
+-- Notes are free-form, timestamped journal entries kept alongside the
+-- sessions. They are not tied to a session, so a note survives the session
+-- that was running when it was written.
+CREATE TABLE IF NOT EXISTS note (
+    note_id TEXT PRIMARY KEY,
+    note_text TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
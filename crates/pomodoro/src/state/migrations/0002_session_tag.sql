@@ -0,0 +1,7 @@
+-- sqlfluff:dialect:sqlite
+-- sqlfluff:max_line_length:1024
+-- sqlfluff:rules:capitalisation.keywords:capitalisation_policy:upper
+
+-- The optional tag is a free-form label of the session, e.g. the project
+-- worked on, set with `start --tag`.
+ALTER TABLE session ADD COLUMN tag TEXT;
@@ -0,0 +1,10 @@
+-- sqlfluff:dialect:sqlite
+-- sqlfluff:max_line_length:1024
+-- sqlfluff:rules:capitalisation.keywords:capitalisation_policy:upper
+
+-- Interrupted events record in interruption_source whether the user was
+-- interrupted by themselves ("internal") or by someone else ("external"), and
+-- in note what the interruption was about, if anything; both are NULL for
+-- every other event.
+ALTER TABLE session_event ADD COLUMN interruption_source TEXT;
+ALTER TABLE session_event ADD COLUMN note TEXT;
@@ -1,4 +1,5 @@
 use chrono::{DateTime, Duration, Utc};
+use rusqlite::types::ValueRef;
 use rusqlite::Row;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
@@ -63,6 +64,98 @@ impl rusqlite::types::ToSql for SessionKind {
     }
 }
 
+/// How a [`SessionEventKind::Completed`] event came about, so that statistics
+/// can tell a session that ran its full length from one finished early.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum CompletionSource {
+    /// The session ran out of time and was completed by the timer.
+    Auto,
+    /// The user completed the session, e.g. with `stop --complete`.
+    Manual,
+}
+
+impl Display for CompletionSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::Manual => write!(f, "manual"),
+        }
+    }
+}
+
+impl TryFrom<&str> for CompletionSource {
+    type Error = String;
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        match value {
+            "auto" => Ok(Self::Auto),
+            "manual" => Ok(Self::Manual),
+            other => Err(format!("unknown completion source: {other}")),
+        }
+    }
+}
+
+impl rusqlite::types::FromSql for CompletionSource {
+    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        let value = value.as_str()?;
+        CompletionSource::try_from(value)
+            .map_err(|e| rusqlite::types::FromSqlError::Other(e.into()))
+    }
+}
+
+impl rusqlite::types::ToSql for CompletionSource {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        Ok(self.to_string().into())
+    }
+}
+
+/// Where a [`SessionEventKind::Interrupted`] event came from, following the
+/// Pomodoro Technique's distinction between internal and external interruptions.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum InterruptionSource {
+    /// The user interrupted themselves, e.g. by a sudden urge to check mail.
+    Internal,
+    /// Someone or something else interrupted the user, e.g. a phone call.
+    External,
+}
+
+impl Display for InterruptionSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Internal => write!(f, "internal"),
+            Self::External => write!(f, "external"),
+        }
+    }
+}
+
+impl TryFrom<&str> for InterruptionSource {
+    type Error = String;
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        match value {
+            "internal" => Ok(Self::Internal),
+            "external" => Ok(Self::External),
+            other => Err(format!("unknown interruption source: {other}")),
+        }
+    }
+}
+
+impl rusqlite::types::FromSql for InterruptionSource {
+    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        let value = value.as_str()?;
+        InterruptionSource::try_from(value)
+            .map_err(|e| rusqlite::types::FromSqlError::Other(e.into()))
+    }
+}
+
+impl rusqlite::types::ToSql for InterruptionSource {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        Ok(self.to_string().into())
+    }
+}
+
 /// A single timed pomodoro session — either a focus or break interval.
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub struct Session {
@@ -77,6 +170,10 @@ pub struct Session {
         deserialize_with = "deserialize_duration_from_secs"
     )]
     pub planned_duration: Duration,
+    /// Planned duration as the user wrote it (e.g. `"1h30m"`), kept for display only.
+    pub planned_human: Option<String>,
+    /// Free-form label of the session (e.g. `"writing"`), set with `start --tag`.
+    pub tag: Option<String>,
     /// Timestamp when the session was created.
     pub created_at: DateTime<Utc>,
 }
@@ -88,6 +185,8 @@ impl Default for Session {
             id: Uuid::now_v7(),
             kind: SessionKind::Focus,
             planned_duration: Duration::seconds(1500),
+            planned_human: None,
+            tag: None,
             created_at: Utc::now(),
         }
     }
@@ -98,14 +197,41 @@ impl FromRow for Session {
         Ok(Self {
             id: row.get("session_id")?,
             kind: row.get("session_kind")?,
-            planned_duration: Duration::seconds(row.get("planned_secs")?),
+            planned_duration: Duration::seconds(planned_secs(row)?),
+            planned_human: row.get("planned_human")?,
+            tag: row.get("tag")?,
             created_at: row.get("created_at")?,
         })
     }
 }
 
+/// Read the `planned_secs` column of `row`, accepting an integer or a numeric
+/// string.
+///
+/// The column is declared `INTEGER`, but a database written by another version
+/// of the schema may hold a different storage class. Anything that is not a
+/// whole number of seconds fails with an error naming the column, rather than
+/// rusqlite's generic type mismatch.
+fn planned_secs(row: &Row) -> rusqlite::Result<i64> {
+    let index = row.as_ref().column_index("planned_secs")?;
+    let value = row.get_ref(index)?;
+    let secs = match value {
+        ValueRef::Integer(secs) => Some(secs),
+        ValueRef::Text(text) => std::str::from_utf8(text)
+            .ok()
+            .and_then(|text| text.trim().parse().ok()),
+        _ => None,
+    };
+
+    secs.ok_or_else(|| {
+        let kind = value.data_type();
+        let message = format!("unexpected planned_secs type: {kind} is not a number of seconds");
+        rusqlite::Error::FromSqlConversionFailure(index, kind, message.into())
+    })
+}
+
 /// The kind of event recorded against a [`Session`], representing each transition
-/// in the session state machine.
+/// in the session state machine, and interruptions noted along the way.
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 #[serde(rename_all = "lowercase")]
 pub enum SessionEventKind {
@@ -149,6 +275,14 @@ pub enum SessionEventKind {
     /// transitions to the `completed` state and no further events
     /// may be recorded.
     Completed,
+
+    /// Indicates that the user was interrupted while the session was
+    /// running or paused.
+    ///
+    /// Unlike every other kind, this is not a transition: the session
+    /// stays in the state it was in, and elapsed time is unaffected.
+    /// See [`SessionEventKind::is_transition`].
+    Interrupted,
 }
 
 impl Display for SessionEventKind {
@@ -159,10 +293,42 @@ impl Display for SessionEventKind {
             Self::Paused => write!(f, "paused"),
             Self::Aborted => write!(f, "aborted"),
             Self::Completed => write!(f, "completed"),
+            Self::Interrupted => write!(f, "interrupted"),
         }
     }
 }
 
+impl SessionEventKind {
+    /// Whether an event of this kind changes the session state, i.e. every
+    /// kind but [`SessionEventKind::Interrupted`]. The state of a session is
+    /// the kind of its most recent transition.
+    pub fn is_transition(&self) -> bool {
+        !matches!(self, Self::Interrupted)
+    }
+
+    /// Whether an event of this kind may be recorded after `previous`, the most
+    /// recent event of the same session (`None` when it has no events yet).
+    ///
+    /// Encodes the session state machine: a session starts once, then
+    /// alternates between running and paused until it is aborted or completed.
+    /// It may be interrupted any time in between. `previous` must be the most
+    /// recent transition (see [`SessionEventKind::is_transition`]).
+    pub fn can_follow(&self, previous: Option<&SessionEventKind>) -> bool {
+        matches!(
+            (previous, self),
+            (None, Self::Started)
+                | (
+                    Some(Self::Started | Self::Resumed),
+                    Self::Paused | Self::Aborted | Self::Completed | Self::Interrupted
+                )
+                | (
+                    Some(Self::Paused),
+                    Self::Resumed | Self::Aborted | Self::Completed | Self::Interrupted
+                )
+        )
+    }
+}
+
 impl TryFrom<&str> for SessionEventKind {
     type Error = String;
 
@@ -173,6 +339,7 @@ impl TryFrom<&str> for SessionEventKind {
             "paused" => Ok(Self::Paused),
             "aborted" => Ok(Self::Aborted),
             "completed" => Ok(Self::Completed),
+            "interrupted" => Ok(Self::Interrupted),
             other => Err(format!("unknown session event kind: {other}")),
         }
     }
@@ -194,17 +361,25 @@ impl rusqlite::types::ToSql for SessionEventKind {
 
 /// An event recorded against a [`Session`], representing a single state transition.
 ///
-/// Events are emitted when a session is started, paused, resumed, aborted, or completed.
+/// Events are emitted when a session is started, paused, resumed, aborted,
+/// completed, or interrupted.
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub struct SessionEvent {
     /// Unique identifier for the event (UUID v7).
     pub id: Uuid,
-    /// The type of event (started, paused, resumed, aborted, completed).
+    /// The type of event (started, paused, resumed, aborted, completed, interrupted).
     pub kind: SessionEventKind,
     /// Foreign key referencing the parent session.
     pub session_id: Uuid,
-    /// Timestamp when the event was recorded.
+    /// Timestamp when the event was recorded. Stored as text with fractional
+    /// seconds, so sub-millisecond precision survives a round-trip.
     pub created_at: DateTime<Utc>,
+    /// How a completed event came about; `None` for every other event.
+    pub source: Option<CompletionSource>,
+    /// Where an interrupted event came from; `None` for every other event.
+    pub interruption_source: Option<InterruptionSource>,
+    /// What an interrupted event was about, if the user said so.
+    pub note: Option<String>,
 }
 
 /// Returns a [`SessionEventKind::Started`] event with a freshly generated ID,
@@ -216,6 +391,9 @@ impl Default for SessionEvent {
             kind: SessionEventKind::Started,
             session_id: Uuid::default(),
             created_at: Utc::now(),
+            source: None,
+            interruption_source: None,
+            note: None,
         }
     }
 }
@@ -227,6 +405,9 @@ impl FromRow for SessionEvent {
             kind: row.get("session_event_kind")?,
             session_id: row.get("session_id")?,
             created_at: row.get("created_at")?,
+            source: row.get("completion_source")?,
+            interruption_source: row.get("interruption_source")?,
+            note: row.get("note")?,
         })
     }
 }
@@ -282,9 +463,254 @@ impl SessionEvent {
         Self {
             session_id,
             kind: SessionEventKind::Completed,
+            source: Some(CompletionSource::Auto),
             ..Self::default()
         }
     }
+
+    /// Creates a [`SessionEventKind::Completed`] event that the user asked for
+    /// before the session reached its planned duration.
+    pub fn completed_manually(session_id: Uuid) -> Self {
+        Self {
+            source: Some(CompletionSource::Manual),
+            ..Self::completed(session_id)
+        }
+    }
+
+    /// Creates a [`SessionEventKind::Interrupted`] event for the given session,
+    /// with an optional `note` on what the interruption was about.
+    pub fn interrupted(session_id: Uuid, source: InterruptionSource, note: Option<String>) -> Self {
+        Self {
+            session_id,
+            kind: SessionEventKind::Interrupted,
+            interruption_source: Some(source),
+            note,
+            ..Default::default()
+        }
+    }
+}
+
+/// Keys of the application-defined facts stored in the `meta` table.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MetaKey {
+    /// ID of the [`SessionEventKind::Paused`] event recorded by `pomodoro suspend`.
+    ///
+    /// Lets `pomodoro resume-from-suspend` tell a suspend apart from a manual pause.
+    SuspendedEventId,
+    /// ID of the [`Session`] pinned by `pomodoro focus-on`.
+    ///
+    /// Commands act on the pinned session instead of the most recent one until it ends.
+    PinnedSessionId,
+}
+
+impl Display for MetaKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SuspendedEventId => write!(f, "suspended_event_id"),
+            Self::PinnedSessionId => write!(f, "pinned_session_id"),
+        }
+    }
+}
+
+impl rusqlite::types::ToSql for MetaKey {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        Ok(self.to_string().into())
+    }
+}
+
+/// The number of completed sessions of one [`SessionKind`] and their combined
+/// planned duration, as returned by
+/// [`Querier::count_completed_sessions`](crate::state::query::Querier::count_completed_sessions).
+#[derive(Clone, PartialEq, Debug)]
+pub struct SessionCount {
+    /// The kind of the counted sessions.
+    pub kind: SessionKind,
+    /// Number of completed sessions.
+    pub num_sessions: u32,
+    /// Sum of the planned durations of the completed sessions.
+    pub planned_duration: Duration,
+}
+
+impl FromRow for SessionCount {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            kind: row.get("session_kind")?,
+            num_sessions: row.get("num_sessions")?,
+            planned_duration: Duration::seconds(planned_secs(row)?),
+        })
+    }
+}
+
+/// Aggregates over the focus sessions started in a window, as returned by
+/// [`Querier::aggregate_stats`](crate::state::query::Querier::aggregate_stats).
+///
+/// A session counts by its latest event: one still running or paused is in
+/// progress, not completed.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SessionStats {
+    /// Number of focus sessions started in the window.
+    pub num_sessions: u32,
+    /// Number of those sessions that were completed.
+    pub num_completed: u32,
+    /// Number of those sessions that were aborted.
+    pub num_aborted: u32,
+    /// Number of interruptions recorded during those sessions.
+    pub num_interruptions: u32,
+    /// Sum of the planned durations of the completed sessions.
+    pub focus_duration: Duration,
+}
+
+impl SessionStats {
+    /// Number of sessions that neither completed nor were aborted yet.
+    pub fn num_in_progress(&self) -> u32 {
+        self.num_sessions - self.num_completed - self.num_aborted
+    }
+}
+
+impl FromRow for SessionStats {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            num_sessions: row.get("num_sessions")?,
+            num_completed: row.get("num_completed")?,
+            num_aborted: row.get("num_aborted")?,
+            num_interruptions: row.get("num_interruptions")?,
+            focus_duration: Duration::seconds(planned_secs(row)?),
+        })
+    }
+}
+
+/// A free-form, timestamped journal entry written with `pomodoro note`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Note {
+    /// Unique identifier for the note (UUID v7).
+    pub id: Uuid,
+    /// The text of the note.
+    pub text: String,
+    /// Timestamp when the note was written.
+    pub created_at: DateTime<Utc>,
+}
+
+impl Note {
+    /// Creates a note with the given text, a fresh ID, and the current timestamp.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            text: text.into(),
+            created_at: Utc::now(),
+        }
+    }
+}
+
+impl FromRow for Note {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("note_id")?,
+            text: row.get("note_text")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+/// Statistics derived for a single [`Session`] by replaying its event log.
+///
+/// Built by [`Querier::session_summary`](crate::state::query::Querier::session_summary)
+/// so that every command computes elapsed time the same way.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SessionSummary {
+    /// The summarized session.
+    pub session: Session,
+    /// Total time spent in the running state, excluding pauses.
+    pub elapsed: Duration,
+    /// Number of [`SessionEventKind::Paused`] events recorded for the session.
+    pub num_pauses: u32,
+    /// Number of [`SessionEventKind::Interrupted`] events recorded for the session.
+    pub num_interruptions: u32,
+    /// Total time spent paused, including a pause that is still open.
+    pub paused: Duration,
+    /// Kind of the most recent transition, or `None` when the session has none.
+    pub final_state: Option<SessionEventKind>,
+    /// Timestamp of the first [`SessionEventKind::Started`] event, if any.
+    pub started_at: Option<DateTime<Utc>>,
+    /// Timestamp of the terminal (aborted or completed) event, if any.
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Timestamp of the [`SessionEventKind::Paused`] event of a pause that is
+    /// still open, if any.
+    pub paused_since: Option<DateTime<Utc>>,
+}
+
+impl SessionSummary {
+    /// Replay `events` (ordered newest first, as returned by the event queries)
+    /// and accumulate the summary for `session`.
+    ///
+    /// Each `Started`/`Resumed` event opens a running interval and each `Paused`
+    /// event opens a paused interval; both are closed by the next transition.
+    /// An interval still open after the last event is measured up to `now`.
+    /// Interruptions are only counted, since they do not change the state.
+    pub fn replay(session: Session, events: &[SessionEvent], now: DateTime<Utc>) -> Self {
+        let mut started_at = None;
+        let mut paused_at = None;
+        let mut elapsed = Duration::zero();
+        let mut paused = Duration::zero();
+        let mut num_pauses = 0;
+        let mut num_interruptions = 0;
+        let mut first_started_at = None;
+        let mut ended_at = None;
+
+        for event in events.iter().rev() {
+            let kind = &event.kind;
+            if !kind.is_transition() {
+                num_interruptions += 1;
+                continue;
+            }
+            // Close the paused range, if any
+            if let Some(since_pause) = paused_at.take() {
+                paused += event.created_at - since_pause;
+            }
+            // Find the start and end of each range
+            if matches!(kind, SessionEventKind::Started | SessionEventKind::Resumed) {
+                started_at = Some(event.created_at);
+            } else if let Some(since_start) = started_at.take() {
+                elapsed += event.created_at - since_start;
+            }
+            if matches!(kind, SessionEventKind::Paused) {
+                paused_at = Some(event.created_at);
+            }
+
+            match kind {
+                SessionEventKind::Started => {
+                    first_started_at = first_started_at.or(Some(event.created_at))
+                }
+                SessionEventKind::Paused => num_pauses += 1,
+                SessionEventKind::Aborted | SessionEventKind::Completed => {
+                    ended_at = Some(event.created_at)
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(since_start) = started_at {
+            elapsed += now - since_start;
+        }
+        if let Some(since_pause) = paused_at {
+            paused += now - since_pause;
+        }
+
+        Self {
+            session,
+            elapsed,
+            num_pauses,
+            num_interruptions,
+            paused,
+            final_state: events
+                .iter()
+                .map(|e| &e.kind)
+                .find(|kind| kind.is_transition())
+                .cloned(),
+            started_at: first_started_at,
+            ended_at,
+            paused_since: paused_at,
+        }
+    }
 }
 
 fn serialize_duration_as_secs<S>(d: &Duration, s: S) -> std::result::Result<S::Ok, S::Error>
@@ -306,6 +732,23 @@ where
 mod tests {
     use super::*;
 
+    #[test]
+    fn session_from_row_accepts_numeric_text_planned_secs() -> rusqlite::Result<()> {
+        let conn = rusqlite::Connection::open_in_memory()?;
+        let session = conn.query_row(
+            "SELECT ?1 AS session_id,
+                    'focus' AS session_kind,
+                    ' 1500 ' AS planned_secs,
+                    NULL AS planned_human,
+                    NULL AS tag,
+                    ?2 AS created_at",
+            rusqlite::params![Uuid::now_v7(), Utc::now()],
+            Session::from_row,
+        )?;
+        assert_eq!(session.planned_duration, Duration::seconds(1500));
+        Ok(())
+    }
+
     #[test]
     fn session_kind_try_from_unknown_returns_error() {
         let result = SessionKind::try_from("unknown");
@@ -319,4 +762,85 @@ mod tests {
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "unknown session event kind: unknown");
     }
+
+    #[test]
+    fn replay_accumulates_closed_and_open_pauses() {
+        let session = Session::default();
+        let at = |minutes| session.created_at + Duration::minutes(minutes);
+
+        // started → paused at 10m → resumed at 15m → paused at 20m, still paused at 30m.
+        let events = [
+            (SessionEvent::started(session.id), 0),
+            (SessionEvent::paused(session.id), 10),
+            (SessionEvent::resumed(session.id), 15),
+            (SessionEvent::paused(session.id), 20),
+        ]
+        .into_iter()
+        .rev()
+        .map(|(event, minutes)| SessionEvent {
+            created_at: at(minutes),
+            ..event
+        })
+        .collect::<Vec<_>>();
+
+        let summary = SessionSummary::replay(session.clone(), &events, at(30));
+        assert_eq!(summary.num_pauses, 2);
+        assert_eq!(summary.paused, Duration::minutes(5 + 10));
+        assert_eq!(summary.elapsed, Duration::minutes(10 + 5));
+        assert_eq!(summary.paused_since, Some(at(20)));
+    }
+
+    #[test]
+    fn replay_counts_interruptions_without_changing_state_or_time() {
+        let session = Session::default();
+        let at = |minutes| session.created_at + Duration::minutes(minutes);
+        let interrupted = |source| SessionEvent::interrupted(session.id, source, None);
+
+        // started → interrupted at 5m → paused at 10m → interrupted at 12m, still paused at 30m.
+        let events = [
+            (SessionEvent::started(session.id), 0),
+            (interrupted(InterruptionSource::Internal), 5),
+            (SessionEvent::paused(session.id), 10),
+            (interrupted(InterruptionSource::External), 12),
+        ]
+        .into_iter()
+        .rev()
+        .map(|(event, minutes)| SessionEvent {
+            created_at: at(minutes),
+            ..event
+        })
+        .collect::<Vec<_>>();
+
+        let summary = SessionSummary::replay(session.clone(), &events, at(30));
+        assert_eq!(summary.num_interruptions, 2);
+        assert_eq!(summary.num_pauses, 1);
+        assert_eq!(summary.elapsed, Duration::minutes(10));
+        assert_eq!(summary.paused, Duration::minutes(20));
+        assert_eq!(summary.final_state, Some(SessionEventKind::Paused));
+    }
+
+    #[test]
+    fn session_event_kind_can_follow_valid_transitions() {
+        use SessionEventKind::*;
+        assert!(Started.can_follow(None));
+        assert!(Paused.can_follow(Some(&Started)));
+        assert!(Resumed.can_follow(Some(&Paused)));
+        assert!(Completed.can_follow(Some(&Resumed)));
+        assert!(Aborted.can_follow(Some(&Paused)));
+        assert!(Interrupted.can_follow(Some(&Started)));
+        assert!(Interrupted.can_follow(Some(&Paused)));
+    }
+
+    #[test]
+    fn session_event_kind_cannot_follow_invalid_transitions() {
+        use SessionEventKind::*;
+        assert!(!Paused.can_follow(None));
+        assert!(!Started.can_follow(Some(&Paused)));
+        assert!(!Resumed.can_follow(Some(&Started)));
+        assert!(!Paused.can_follow(Some(&Paused)));
+        assert!(!Resumed.can_follow(Some(&Completed)));
+        assert!(!Completed.can_follow(Some(&Aborted)));
+        assert!(!Interrupted.can_follow(None));
+        assert!(!Interrupted.can_follow(Some(&Completed)));
+    }
 }
@@ -1,14 +1,17 @@
-use crate::state::model::{FromRow, Session, SessionEvent};
+use crate::state::migrations;
+use crate::state::model::{
+    FromRow, MetaKey, Note, Session, SessionCount, SessionEvent, SessionEventKind, SessionKind,
+    SessionStats, SessionSummary,
+};
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use regex::Regex;
-use rusqlite::{named_params, Connection, Transaction, TransactionBehavior};
+use rusqlite::{named_params, Connection, OptionalExtension, Transaction, TransactionBehavior};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 use uuid::Uuid;
 
-/// DATABASE_SCHEMA for the database, embedded at compile time from `schema.sql`.
-const DATABASE_SCHEMA: &str = include_str!("schema.sql");
-
 /// Named SQL queries parsed from the embedded `query.sql` file.
 ///
 /// Populated once on first access. Each query in `query.sql` is delimited by
@@ -43,26 +46,86 @@ static DATABASE_QUERY: LazyLock<HashMap<String, String>> = LazyLock::new(|| {
 /// Database manages the SQLite connection lifecycle: opening, migrating, and
 /// vending [`Querier`] handles for executing queries.
 ///
-/// Every write operation should go through [`Database::transaction`] so that
+/// Every write operation should go through [`Database::transaction_with`] so that
 /// partial failures roll back automatically.
 pub struct Database {
     conn: Connection,
+    retry: RetryPolicy,
+    /// Whether a crashed process left a hot journal behind when the database
+    /// was opened, so that [`Database::migrate`] verifies it first.
+    recovering: bool,
+}
+
+/// RetryPolicy controls how [`Database::transaction_with`] retries when another
+/// connection holds the write lock (`SQLITE_BUSY` / `SQLITE_LOCKED`).
+///
+/// The wait starts at `backoff` and doubles after every attempt.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// How many times to retry before giving up.
+    pub max_retries: u32,
+    /// How long to wait before the first retry.
+    pub backoff: std::time::Duration,
+}
+
+/// Returns 5 retries starting at 10ms, i.e. roughly 300ms of waiting in total.
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            backoff: std::time::Duration::from_millis(10),
+        }
+    }
 }
 
 impl Database {
     /// Open a connection to the SQLite database.
+    ///
+    /// When a crashed process left its rollback journal (`state.db-journal`)
+    /// behind, the database is verified with [`Database::check`] by
+    /// [`Database::migrate`].
     pub fn open() -> Result<Self> {
-        let path = xdg::BaseDirectories::with_prefix("pomodoro")
-            .place_state_file("state.db")
-            .context("Failed to determine database path")?;
+        let path = Self::path()?;
+        let mut journal = path.clone().into_os_string();
+        journal.push("-journal");
+        let recovering = Path::new(&journal).exists();
         let conn = Connection::open(path).context("Failed to open database connection")?;
-        Ok(Self { conn })
+        Ok(Self {
+            conn,
+            retry: RetryPolicy::default(),
+            recovering,
+        })
+    }
+
+    /// Return the path of the SQLite database file opened by [`Database::open`],
+    /// `$XDG_STATE_HOME/pomodoro/state.db`, creating its parent directory.
+    pub fn path() -> Result<PathBuf> {
+        xdg::BaseDirectories::with_prefix("pomodoro")
+            .place_state_file("state.db")
+            .context("Failed to determine database path")
     }
 
     /// Open a connection to the in-memory SQLite database.
     pub fn open_in_memory() -> Result<Self> {
         let conn = Connection::open_in_memory().context("Failed to open database connection")?;
-        Ok(Self { conn })
+        Ok(Self {
+            conn,
+            retry: RetryPolicy::default(),
+            recovering: false,
+        })
+    }
+
+    /// Replace the [`RetryPolicy`] used by [`Database::transaction_with`].
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Return the number of rows inserted, updated, or deleted through this
+    /// connection since it was opened, rolled back ones included; comparing
+    /// two readings tells whether a command wrote anything in between.
+    pub fn total_changes(&self) -> u64 {
+        self.conn.total_changes()
     }
 
     /// Return a reference to the underlying connection.
@@ -74,33 +137,169 @@ impl Database {
         &self.conn
     }
 
-    /// Begin a SQLite transaction and return it.
+    /// Verify that the database is consistent by running `PRAGMA quick_check`.
+    ///
+    /// If a previous process crashed mid-transaction, its rollback journal
+    /// (`state.db-journal`) is left behind as a "hot" journal. SQLite rolls it
+    /// back on the first read of the database, and this check is that first
+    /// read, so any recovery happens here instead of in the middle of a
+    /// command. The check waits for a lock held by another connection like
+    /// [`Database::transaction_with`] does. Returns an error describing the
+    /// problems when the check reports anything other than `ok`.
+    pub fn check(&self) -> Result<()> {
+        let quick_check = || -> rusqlite::Result<Vec<String>> {
+            let mut operation = self.conn.prepare("PRAGMA quick_check")?;
+            let iterator = operation.query_map([], |row| row.get::<_, String>(0))?;
+            iterator.collect()
+        };
+        let (result, retries) = self.retrying(quick_check);
+        let problems: Vec<String> = result
+            .with_context(|| match retries {
+                0 => "Failed to check database".to_string(),
+                _ => format!("Failed to check database after {retries} retries"),
+            })?
+            .into_iter()
+            .filter(|problem| problem != "ok")
+            .collect();
+
+        if !problems.is_empty() {
+            anyhow::bail!("Database consistency check failed: {}", problems.join("; "));
+        }
+        Ok(())
+    }
+
+    /// Begin a SQLite transaction with the given `behavior` and return it.
     ///
     /// Pass `&*tx` (or rely on deref coercion with `&tx`) to [`Querier::new`] to
     /// execute queries within the transaction. The caller must call
     /// [`Transaction::commit`] explicitly; dropping without committing rolls back.
-    pub fn transaction(&mut self) -> Result<Transaction<'_>> {
-        self.conn
-            .transaction_with_behavior(TransactionBehavior::Immediate)
-            .context("Failed to start transaction")
+    ///
+    /// When another connection holds the write lock, beginning the transaction
+    /// is retried with exponential backoff according to the [`RetryPolicy`].
+    ///
+    /// [`TransactionBehavior::Immediate`] takes the write lock upfront, so a
+    /// transaction that writes can never fail halfway because another
+    /// connection wrote first; but it waits for, and blocks, every other
+    /// writer, even when it ends up only reading.
+    /// [`TransactionBehavior::Deferred`] takes no lock until the first
+    /// statement and then only a read lock, so it never contends with other
+    /// readers. Use it for transactions that only read: one that writes after
+    /// all can fail with a busy error instead of being retried.
+    pub fn transaction_with(&mut self, behavior: TransactionBehavior) -> Result<Transaction<'_>> {
+        // `&mut self` guarantees no other transaction is open on this connection.
+        let (result, retries) = self.retrying(|| Transaction::new_unchecked(&self.conn, behavior));
+        result.with_context(|| match retries {
+            0 => "Failed to start transaction".to_string(),
+            _ => format!("Failed to start transaction after {retries} retries"),
+        })
+    }
+
+    /// Run `f` until it succeeds, fails with an error other than a busy one,
+    /// or the [`RetryPolicy`] runs out of retries. Returns the last result
+    /// together with the number of retries.
+    fn retrying<T>(
+        &self,
+        mut f: impl FnMut() -> rusqlite::Result<T>,
+    ) -> (rusqlite::Result<T>, u32) {
+        let mut backoff = self.retry.backoff;
+        let mut retries = 0;
+        loop {
+            match f() {
+                Err(error) if is_busy(&error) && retries < self.retry.max_retries => {
+                    std::thread::sleep(backoff);
+                    backoff *= 2;
+                    retries += 1;
+                }
+                result => return (result, retries),
+            }
+        }
+    }
+
+    /// Run `f` with a [`Querier`] in a transaction with the given `behavior`,
+    /// commit the transaction, and return what `f` returned.
+    ///
+    /// Unlike [`Database::transaction_with`], which only retries beginning the
+    /// transaction, the whole transaction is retried according to the
+    /// [`RetryPolicy`] when beginning it, `f`, or committing it fails because
+    /// another connection holds the lock: it is rolled back and `f` runs again
+    /// from scratch. Any other error rolls the transaction back and is returned.
+    ///
+    /// `f` may therefore run more than once, so its effects outside of the
+    /// database must wait for the commit: hooks are queued by a
+    /// [deferred](crate::hook::run::Runner::deferred) runner and only run once
+    /// this returns. An immediate transaction holds the write lock from the
+    /// start, so `f` only runs again when the commit keeps waiting for readers.
+    pub fn with_transaction<T>(
+        &mut self,
+        behavior: TransactionBehavior,
+        mut f: impl FnMut(Querier) -> Result<T>,
+    ) -> Result<T> {
+        let mut backoff = self.retry.backoff;
+        let mut retries = 0;
+        loop {
+            // `&mut self` guarantees no other transaction is open on this connection.
+            let result = Transaction::new_unchecked(&self.conn, behavior)
+                .context("Failed to start transaction")
+                .and_then(|tx| {
+                    let value = f(Querier::new(&tx))?;
+                    tx.commit().context("Failed to commit transaction")?;
+                    Ok(value)
+                });
+            match result {
+                Err(error)
+                    if error
+                        .chain()
+                        .filter_map(|cause| cause.downcast_ref::<rusqlite::Error>())
+                        .any(is_busy)
+                        && retries < self.retry.max_retries =>
+                {
+                    std::thread::sleep(backoff);
+                    backoff *= 2;
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
     }
 
-    /// Apply the embedded SQL schema, creating all tables if they do not already exist.
+    /// Apply the pending schema migrations, creating all tables in a new database.
+    ///
+    /// Safe to call on an existing database — only the migrations newer than
+    /// its `user_version` are applied (see [`migrations::migrate`]). Must be
+    /// called once after opening before any queries are executed.
     ///
-    /// Safe to call on an existing database — the schema uses `CREATE TABLE IF NOT EXISTS`
-    /// semantics. Must be called once after opening before any queries are executed.
+    /// Fails without touching the database when it was migrated by a newer
+    /// binary, since writing to a schema this binary does not know could
+    /// corrupt it.
+    ///
+    /// A database that was left behind with a hot journal is verified with
+    /// [`Database::check`] first, so that a damaged one is never migrated.
     pub fn migrate(&self) -> Result<()> {
-        self.conn
-            .execute_batch(DATABASE_SCHEMA)
-            .context("Failed to migrate database")
+        if self.recovering {
+            self.check()?;
+        }
+        migrations::migrate(&self.conn)
     }
 }
 
+/// Whether `error` means that another connection holds the lock
+/// (`SQLITE_BUSY` / `SQLITE_LOCKED`), i.e. that trying again may succeed.
+fn is_busy(error: &rusqlite::Error) -> bool {
+    matches!(
+        error,
+        rusqlite::Error::SqliteFailure(error, _) if matches!(
+            error.code,
+            rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked
+        )
+    )
+}
+
 /// Querier executes SQL queries against a borrowed [`Connection`].
 ///
 /// The lifetime `'q` is the lifetime of the underlying connection or transaction.
 /// Construct one via [`Querier::new`], passing either a plain `&Connection` or
 /// `&*transaction` (possible because [`Transaction`] derefs to [`Connection`]).
+#[derive(Clone)]
 pub struct Querier<'q> {
     conn: &'q Connection,
 }
@@ -113,7 +312,7 @@ impl<'q> Querier<'q> {
     /// `Connection`:
     ///
     /// ```ignore
-    /// let tx = database.transaction()?;
+    /// let tx = database.transaction_with(TransactionBehavior::Immediate)?;
     /// let querier = Querier::new(&tx);
     /// // ... execute queries ...
     /// tx.commit()?;
@@ -139,6 +338,8 @@ impl<'q> Querier<'q> {
                     ":session_id": args.session.id,
                     ":session_kind": args.session.kind,
                     ":planned_secs": args.session.planned_duration.num_seconds(),
+                    ":planned_human": args.session.planned_human,
+                    ":tag": args.session.tag,
                     ":created_at": args.session.created_at,
                 },
                 Session::from_row,
@@ -171,6 +372,32 @@ impl<'q> Querier<'q> {
         Ok(session)
     }
 
+    /// Replace the planned duration of a session, e.g. to extend it; its
+    /// events, and thus the elapsed time, are left untouched.
+    pub fn update_session_planned_duration(
+        &self,
+        args: &UpdateSessionPlannedDurationArgs,
+    ) -> Result<()> {
+        let query = DATABASE_QUERY
+            .get("update_session_planned_duration")
+            .context("Failed to get query")?;
+
+        let mut operation = self
+            .conn
+            .prepare(query)
+            .context("Failed to prepare query")?;
+
+        operation
+            .execute(named_params! {
+                ":session_id": args.session.id,
+                ":planned_secs": args.session.planned_duration.num_seconds(),
+                ":planned_human": args.session.planned_human,
+            })
+            .context("Failed to execute query")?;
+
+        Ok(())
+    }
+
     /// Retrieve a paginated list of sessions ordered by `session_id DESC` (newest first).
     pub fn list_sessions(&self, args: &ListSessionsArgs) -> Result<Vec<Session>> {
         let query = DATABASE_QUERY
@@ -185,6 +412,7 @@ impl<'q> Querier<'q> {
         let iterator = operation
             .query_map(
                 named_params! {
+                    ":session_kind": args.kind,
                     ":limit": args.limit,
                     ":offset": args.offset,
                 },
@@ -219,6 +447,9 @@ impl<'q> Querier<'q> {
                     ":session_event_kind": args.session_event.kind,
                     ":session_id": args.session_event.session_id,
                     ":created_at": args.session_event.created_at,
+                    ":completion_source": args.session_event.source,
+                    ":interruption_source": args.session_event.interruption_source,
+                    ":note": args.session_event.note,
                 },
                 SessionEvent::from_row,
             )
@@ -266,6 +497,9 @@ impl<'q> Querier<'q> {
             .query_map(
                 named_params! {
                     ":session_id": args.session_id,
+                    ":after_id": args.after_id,
+                    ":session_event_kind": args.kind,
+                    ":interruptions": args.interruptions,
                     ":limit": args.limit,
                     ":offset": args.offset,
                 },
@@ -281,176 +515,648 @@ impl<'q> Querier<'q> {
 
         Ok(collection)
     }
-}
 
-/// Arguments for [`Querier::insert_session`].
-#[derive(Debug)]
-pub struct InsertSessionArgs<'s> {
-    /// The session to persist.
-    pub session: &'s Session,
-}
+    /// Move a session event to the `created_at` of `args.session_event`.
+    ///
+    /// Only the timestamp is rewritten; the kind and session of an event never change.
+    pub fn update_session_event(&self, args: &UpdateSessionEventArgs) -> Result<()> {
+        let query = DATABASE_QUERY
+            .get("update_session_event")
+            .context("Failed to get query")?;
 
-/// Arguments for [`Querier::get_session_by_id`].
-#[derive(Debug)]
-pub struct GetSessionByIdArgs<'u> {
-    /// The UUID of the session to look up.
-    pub session_id: &'u Uuid,
-}
+        let mut operation = self
+            .conn
+            .prepare(query)
+            .context("Failed to prepare query")?;
 
-/// Arguments for [`Querier::list_sessions`].
-#[derive(Debug)]
-pub struct ListSessionsArgs {
-    /// Maximum number of rows to return.
-    pub limit: Option<u32>,
-    /// Number of rows to skip before returning results.
-    pub offset: Option<u32>,
-}
+        operation
+            .execute(named_params! {
+                ":session_event_id": args.session_event.id,
+                ":created_at": args.session_event.created_at,
+            })
+            .context("Failed to execute query")?;
 
-impl ListSessionEventsArgs {
-    /// Returns args that fetch only the single most recent session event.
-    ///
-    /// Equivalent to `LIMIT 1` with no offset, ordered by `session_event_id DESC`.
-    /// Use this when you only need the current state of the session (i.e. the
-    /// latest event in the log).
-    pub fn first() -> Self {
-        Self {
-            session_id: None,
-            limit: Some(1),
-            offset: None,
-        }
+        Ok(())
     }
 
-    /// Returns args that fetch the complete event history for a single session.
-    ///
-    /// Equivalent to `WHERE session_id = <id>` with no `LIMIT` or offset,
-    /// ordered by `session_event_id DESC` (most recent event first).
-    /// Use this when you need every event recorded against a known session.
-    pub fn with_session_id(session_id: Uuid) -> Self {
-        Self {
-            session_id: Some(session_id),
-            limit: Some(u32::MAX),
-            offset: None,
-        }
+    /// Remove a session event. Removing an event that does not exist is not an error.
+    pub fn delete_session_event(&self, args: &DeleteSessionEventArgs) -> Result<()> {
+        let query = DATABASE_QUERY
+            .get("delete_session_event")
+            .context("Failed to get query")?;
+
+        let mut operation = self
+            .conn
+            .prepare(query)
+            .context("Failed to prepare query")?;
+
+        operation
+            .execute(named_params! {
+                ":session_event_id": args.session_event_id,
+            })
+            .context("Failed to execute query")?;
+
+        Ok(())
     }
-}
 
-impl ListSessionsArgs {
-    /// Returns args that fetch only the single most recent session.
+    /// Fetch a session and its full event log and replay it into a [`SessionSummary`].
     ///
-    /// Equivalent to `LIMIT 1` with no offset, ordered by `session_id DESC`.
-    /// Use this when you only need the latest session record.
-    pub fn first() -> Self {
-        Self {
-            limit: Some(1),
-            offset: None,
-        }
+    /// A session that is still running accumulates elapsed time up to now.
+    pub fn session_summary(&self, args: &SessionSummaryArgs) -> Result<SessionSummary> {
+        self.session_summary_at(args, Utc::now())
     }
-}
 
-/// Returns args with no limit and no offset, fetching all sessions.
-impl Default for ListSessionsArgs {
-    fn default() -> Self {
-        Self {
-            limit: None,
-            offset: None,
-        }
+    /// Like [`Querier::session_summary`], but with an interval still open
+    /// measured up to `now`.
+    pub fn session_summary_at(
+        &self,
+        args: &SessionSummaryArgs,
+        now: DateTime<Utc>,
+    ) -> Result<SessionSummary> {
+        let params = GetSessionByIdArgs {
+            session_id: args.session_id,
+        };
+        let session = self.get_session_by_id(&params)?;
+
+        let params = ListSessionEventsArgs {
+            interruptions: true,
+            ..ListSessionEventsArgs::with_session_id(session.id)
+        };
+        let session_events = self.list_session_events(&params)?;
+
+        Ok(SessionSummary::replay(session, &session_events, now))
     }
-}
 
-/// Arguments for [`Querier::insert_session_event`].
-#[derive(Debug)]
-pub struct InsertSessionEventArgs<'e> {
-    /// The session_event to persist.
-    pub session_event: &'e SessionEvent,
-}
+    /// Count the sessions completed since `args.since`, grouped by kind.
+    ///
+    /// Kinds without any completed session are omitted from the result.
+    pub fn count_completed_sessions(
+        &self,
+        args: &CountCompletedSessionsArgs,
+    ) -> Result<Vec<SessionCount>> {
+        let query = DATABASE_QUERY
+            .get("count_completed_sessions")
+            .context("Failed to get query")?;
 
-/// Arguments for [`Querier::get_session_event_by_id`].
-#[cfg(test)]
-#[derive(Debug)]
-pub struct GetSessionEventByIdArgs<'u> {
-    /// The UUID of the session_event to look up.
-    pub session_event_id: &'u Uuid,
-}
+        let mut operation = self
+            .conn
+            .prepare(query)
+            .context("Failed to prepare query")?;
 
-/// Arguments for [`Querier::list_session_events`].
-#[derive(Debug)]
-pub struct ListSessionEventsArgs {
-    /// Restrict results to events belonging to this session; `None` returns events for all sessions.
-    pub session_id: Option<Uuid>,
-    /// Maximum number of rows to return.
-    pub limit: Option<u32>,
-    /// Number of rows to skip before returning results.
-    pub offset: Option<u32>,
-}
+        let iterator = operation
+            .query_map(
+                named_params! {
+                    ":since": args.since,
+                },
+                SessionCount::from_row,
+            )
+            .context("Failed to execute query")?;
 
-/// Returns the most recent session event (limit 1, offset 0).
-impl Default for ListSessionEventsArgs {
-    fn default() -> Self {
-        Self {
-            session_id: None,
-            limit: Some(1),
-            offset: Some(0),
+        let mut collection = Vec::new();
+        for item in iterator {
+            let count = item.context("Failed to map query result")?;
+            collection.push(count);
         }
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    /// Open an in-memory database, apply the schema, and return it.
-    ///
-    /// Used by every test in this module as the starting point for a clean,
-    /// isolated database that is discarded when the test completes.
-    fn setup() -> Result<Database> {
-        let database = Database::open_in_memory().context("Failed to open database")?;
-        database.migrate().context("Failed to migrate database")?;
-        Ok(database)
+        Ok(collection)
     }
 
-    #[test]
-    fn insert_session_returns_persisted_session() -> Result<()> {
-        let database = setup()?;
-        let querier = Querier::new(database.connection());
+    /// Aggregate the focus sessions started since `args.since` by their
+    /// latest event (see [`SessionStats`]).
+    pub fn aggregate_stats(&self, args: &AggregateStatsArgs) -> Result<SessionStats> {
+        let query = DATABASE_QUERY
+            .get("aggregate_stats")
+            .context("Failed to get query")?;
 
-        let session = &Session::default();
-        let args = &InsertSessionArgs { session };
-        let session = querier.insert_session(args)?;
-        assert_eq!(
-            args.session, &session,
-            "Inserted session should match the input session"
-        );
+        let mut operation = self
+            .conn
+            .prepare(query)
+            .context("Failed to prepare query")?;
 
-        Ok(())
+        let stats = operation
+            .query_one(
+                named_params! {
+                    ":since": args.since,
+                },
+                SessionStats::from_row,
+            )
+            .context("Failed to execute query")?;
+
+        Ok(stats)
     }
 
-    #[test]
-    fn get_session_by_id_returns_matching_session() -> Result<()> {
-        let database = setup()?;
-        let querier = Querier::new(database.connection());
+    /// Count the focus sessions completed since the most recent long break,
+    /// i.e. the most recent break planned for at least
+    /// `args.long_break_duration`, or else since the first session.
+    pub fn count_focus_since_long_break(&self, args: &CountFocusSinceLongBreakArgs) -> Result<u64> {
+        let query = DATABASE_QUERY
+            .get("count_focus_since_long_break")
+            .context("Failed to get query")?;
 
-        let session = &Session::default();
-        let args = &InsertSessionArgs { session };
-        let session = querier.insert_session(args)?;
+        let mut operation = self
+            .conn
+            .prepare(query)
+            .context("Failed to prepare query")?;
 
-        let args = &GetSessionByIdArgs {
-            session_id: &session.id,
-        };
-        let session = querier.get_session_by_id(args)?;
-        assert_eq!(
-            args.session_id, &session.id,
-            "Retrieved session ID should match the requested ID"
-        );
+        let count = operation
+            .query_one(
+                named_params! {
+                    ":long_break_secs": args.long_break_duration.num_seconds(),
+                },
+                |row| row.get::<_, i64>("num_sessions"),
+            )
+            .context("Failed to execute query")?;
 
-        Ok(())
+        // COUNT(*) is never negative.
+        Ok(count.unsigned_abs())
     }
 
-    #[test]
-    fn get_session_by_id_fails_when_not_found() -> Result<()> {
-        let database = setup()?;
-        let querier = Querier::new(database.connection());
+    /// Count the sessions matching `args` without fetching them.
+    pub fn count_sessions(&self, args: &CountSessionsArgs) -> Result<u64> {
+        let query = DATABASE_QUERY
+            .get("count_sessions")
+            .context("Failed to get query")?;
+
+        let mut operation = self
+            .conn
+            .prepare(query)
+            .context("Failed to prepare query")?;
+
+        let count = operation
+            .query_one(
+                named_params! {
+                    ":session_kind": args.kind,
+                },
+                |row| row.get::<_, i64>("num_sessions"),
+            )
+            .context("Failed to execute query")?;
+
+        // COUNT(*) is never negative.
+        Ok(count.unsigned_abs())
+    }
+
+    /// Count the session events matching `args` without fetching them.
+    pub fn count_session_events(&self, args: &CountSessionEventsArgs) -> Result<u64> {
+        let query = DATABASE_QUERY
+            .get("count_session_events")
+            .context("Failed to get query")?;
+
+        let mut operation = self
+            .conn
+            .prepare(query)
+            .context("Failed to prepare query")?;
+
+        let count = operation
+            .query_one(
+                named_params! {
+                    ":session_id": args.session_id,
+                },
+                |row| row.get::<_, i64>("num_session_events"),
+            )
+            .context("Failed to execute query")?;
+
+        // COUNT(*) is never negative.
+        Ok(count.unsigned_abs())
+    }
+
+    /// Insert a new note row and return the persisted [`Note`].
+    pub fn insert_note(&self, args: &InsertNoteArgs) -> Result<Note> {
+        let query = DATABASE_QUERY
+            .get("insert_note")
+            .context("Failed to get query")?;
+
+        let mut operation = self
+            .conn
+            .prepare(query)
+            .context("Failed to prepare query")?;
+
+        let note = operation
+            .query_one(
+                named_params! {
+                    ":note_id": args.note.id,
+                    ":note_text": args.note.text,
+                    ":created_at": args.note.created_at,
+                },
+                Note::from_row,
+            )
+            .context("Failed to execute query")?;
+
+        Ok(note)
+    }
+
+    /// List notes in the order they were written, optionally only those
+    /// written at or after `args.since`.
+    pub fn list_notes(&self, args: &ListNotesArgs) -> Result<Vec<Note>> {
+        let query = DATABASE_QUERY
+            .get("list_notes")
+            .context("Failed to get query")?;
+
+        let mut operation = self
+            .conn
+            .prepare(query)
+            .context("Failed to prepare query")?;
+
+        let iterator = operation
+            .query_map(
+                named_params! {
+                    ":since": args.since,
+                },
+                Note::from_row,
+            )
+            .context("Failed to execute query")?;
+
+        let mut collection = Vec::new();
+        for item in iterator {
+            let note = item.context("Failed to map query result")?;
+            collection.push(note);
+        }
+
+        Ok(collection)
+    }
+
+    /// Retrieve the value stored under a meta key, or `None` when it is unset.
+    pub fn get_meta(&self, args: &GetMetaArgs) -> Result<Option<String>> {
+        let query = DATABASE_QUERY
+            .get("get_meta")
+            .context("Failed to get query")?;
+
+        let mut operation = self
+            .conn
+            .prepare(query)
+            .context("Failed to prepare query")?;
+
+        let value = operation
+            .query_one(
+                named_params! {
+                    ":meta_key": args.key,
+                },
+                |row| row.get("meta_value"),
+            )
+            .optional()
+            .context("Failed to execute query")?;
+
+        Ok(value)
+    }
+
+    /// Store `value` under a meta key, replacing any previous value.
+    pub fn set_meta(&self, args: &SetMetaArgs) -> Result<()> {
+        let query = DATABASE_QUERY
+            .get("set_meta")
+            .context("Failed to get query")?;
+
+        let mut operation = self
+            .conn
+            .prepare(query)
+            .context("Failed to prepare query")?;
+
+        operation
+            .execute(named_params! {
+                ":meta_key": args.key,
+                ":meta_value": args.value,
+            })
+            .context("Failed to execute query")?;
+
+        Ok(())
+    }
+
+    /// Remove a meta key. Removing a key that is not set is not an error.
+    pub fn delete_meta(&self, args: &DeleteMetaArgs) -> Result<()> {
+        let query = DATABASE_QUERY
+            .get("delete_meta")
+            .context("Failed to get query")?;
+
+        let mut operation = self
+            .conn
+            .prepare(query)
+            .context("Failed to prepare query")?;
+
+        operation
+            .execute(named_params! {
+                ":meta_key": args.key,
+            })
+            .context("Failed to execute query")?;
+
+        Ok(())
+    }
+}
+
+/// Arguments for [`Querier::count_sessions`].
+#[derive(Debug, Default)]
+pub struct CountSessionsArgs {
+    /// Only count sessions of this kind; `None` counts every session.
+    pub kind: Option<SessionKind>,
+}
+
+/// Arguments for [`Querier::count_session_events`].
+#[derive(Debug, Default)]
+pub struct CountSessionEventsArgs {
+    /// Only count events of this session; `None` counts the events of every session.
+    pub session_id: Option<Uuid>,
+}
+
+/// Arguments for [`Querier::count_completed_sessions`].
+#[derive(Debug)]
+pub struct CountCompletedSessionsArgs {
+    /// Only sessions completed at or after this instant are counted.
+    pub since: DateTime<Utc>,
+}
+
+/// Arguments for [`Querier::count_focus_since_long_break`].
+#[derive(Debug)]
+pub struct CountFocusSinceLongBreakArgs {
+    /// The shortest planned duration of a break that counts as a long one.
+    pub long_break_duration: chrono::Duration,
+}
+
+/// Arguments for [`Querier::aggregate_stats`].
+#[derive(Debug)]
+pub struct AggregateStatsArgs {
+    /// Only sessions started at or after this instant are aggregated.
+    pub since: DateTime<Utc>,
+}
+
+/// Arguments for [`Querier::insert_note`].
+#[derive(Debug)]
+pub struct InsertNoteArgs<'n> {
+    /// The note to persist.
+    pub note: &'n Note,
+}
+
+/// Arguments for [`Querier::list_notes`].
+#[derive(Debug, Default)]
+pub struct ListNotesArgs {
+    /// Only notes written at or after this instant are listed; `None` lists all notes.
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Arguments for [`Querier::get_meta`].
+#[derive(Debug)]
+pub struct GetMetaArgs {
+    /// The key to look up.
+    pub key: MetaKey,
+}
+
+/// Arguments for [`Querier::set_meta`].
+#[derive(Debug)]
+pub struct SetMetaArgs<'v> {
+    /// The key to store the value under.
+    pub key: MetaKey,
+    /// The value to store.
+    pub value: &'v str,
+}
+
+/// Arguments for [`Querier::delete_meta`].
+#[derive(Debug)]
+pub struct DeleteMetaArgs {
+    /// The key to remove.
+    pub key: MetaKey,
+}
+
+/// Arguments for [`Querier::session_summary`].
+#[derive(Debug)]
+pub struct SessionSummaryArgs<'u> {
+    /// The UUID of the session to summarize.
+    pub session_id: &'u Uuid,
+}
+
+/// Arguments for [`Querier::insert_session`].
+#[derive(Debug)]
+pub struct InsertSessionArgs<'s> {
+    /// The session to persist.
+    pub session: &'s Session,
+}
+
+/// Arguments for [`Querier::get_session_by_id`].
+#[derive(Debug)]
+pub struct GetSessionByIdArgs<'u> {
+    /// The UUID of the session to look up.
+    pub session_id: &'u Uuid,
+}
+
+/// Arguments for [`Querier::update_session_planned_duration`].
+#[derive(Debug)]
+pub struct UpdateSessionPlannedDurationArgs<'s> {
+    /// The session to update, carrying its new `planned_duration` and `planned_human`.
+    pub session: &'s Session,
+}
+
+/// Arguments for [`Querier::list_sessions`].
+#[derive(Debug)]
+pub struct ListSessionsArgs {
+    /// Restrict results to sessions of this kind; `None` returns sessions of every kind.
+    pub kind: Option<SessionKind>,
+    /// Maximum number of rows to return.
+    pub limit: Option<u32>,
+    /// Number of rows to skip before returning results.
+    pub offset: Option<u32>,
+}
+
+impl ListSessionEventsArgs {
+    /// Returns args that fetch only the single most recent session event.
+    ///
+    /// Equivalent to `LIMIT 1` with no offset, ordered by `session_event_id DESC`.
+    /// Commands go by the current session instead, so this is left to tests
+    /// that need the latest event in the log.
+    #[cfg(test)]
+    pub fn first() -> Self {
+        Self {
+            session_id: None,
+            after_id: None,
+            kind: None,
+            interruptions: false,
+            limit: Some(1),
+            offset: None,
+        }
+    }
+
+    /// Returns args that fetch the complete event history for a single session.
+    ///
+    /// Equivalent to `WHERE session_id = <id>` with no `LIMIT` or offset,
+    /// ordered by `session_event_id DESC` (most recent event first).
+    /// Use this when you need every event recorded against a known session.
+    pub fn with_session_id(session_id: Uuid) -> Self {
+        Self {
+            session_id: Some(session_id),
+            after_id: None,
+            kind: None,
+            interruptions: false,
+            limit: Some(u32::MAX),
+            offset: None,
+        }
+    }
+}
+
+impl ListSessionsArgs {
+    /// Returns args that fetch only the single most recent session.
+    ///
+    /// Equivalent to `LIMIT 1` with no offset, ordered by `session_id DESC`.
+    /// Use this when you only need the latest session record.
+    pub fn first() -> Self {
+        Self {
+            kind: None,
+            limit: Some(1),
+            offset: None,
+        }
+    }
+}
+
+/// Returns args with no limit and no offset, fetching all sessions.
+impl Default for ListSessionsArgs {
+    fn default() -> Self {
+        Self {
+            kind: None,
+            limit: None,
+            offset: None,
+        }
+    }
+}
+
+/// Arguments for [`Querier::insert_session_event`].
+#[derive(Debug)]
+pub struct InsertSessionEventArgs<'e> {
+    /// The session_event to persist.
+    pub session_event: &'e SessionEvent,
+}
+
+/// Arguments for [`Querier::update_session_event`].
+#[derive(Debug)]
+pub struct UpdateSessionEventArgs<'e> {
+    /// The session_event to move, carrying its new `created_at`.
+    pub session_event: &'e SessionEvent,
+}
+
+/// Arguments for [`Querier::delete_session_event`].
+#[derive(Debug)]
+pub struct DeleteSessionEventArgs<'u> {
+    /// The UUID of the session_event to remove.
+    pub session_event_id: &'u Uuid,
+}
+
+/// Arguments for [`Querier::get_session_event_by_id`].
+#[cfg(test)]
+#[derive(Debug)]
+pub struct GetSessionEventByIdArgs<'u> {
+    /// The UUID of the session_event to look up.
+    pub session_event_id: &'u Uuid,
+}
+
+/// Arguments for [`Querier::list_session_events`].
+#[derive(Debug)]
+pub struct ListSessionEventsArgs {
+    /// Restrict results to events belonging to this session; `None` returns events for all sessions.
+    pub session_id: Option<Uuid>,
+    /// Cursor: only return events recorded after this event. Event IDs are UUID v7, so they sort
+    /// in creation order; `None` disables the cursor.
+    pub after_id: Option<Uuid>,
+    /// Restrict results to events of this kind; `None` returns events of every kind.
+    pub kind: Option<SessionEventKind>,
+    /// Include [`SessionEventKind::Interrupted`] events. They are left out
+    /// otherwise, so that the most recent event is the session's state.
+    pub interruptions: bool,
+    /// Maximum number of rows to return.
+    pub limit: Option<u32>,
+    /// Number of rows to skip before returning results.
+    pub offset: Option<u32>,
+}
+
+/// Returns the most recent session event (limit 1, offset 0).
+impl Default for ListSessionEventsArgs {
+    fn default() -> Self {
+        Self {
+            session_id: None,
+            after_id: None,
+            kind: None,
+            interruptions: false,
+            limit: Some(1),
+            offset: Some(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::migrations::MIGRATIONS;
+    use crate::state::model::{
+        CompletionSource, InterruptionSource, SessionEventKind, SessionKind,
+    };
+
+    /// Open an in-memory database, apply the schema, and return it.
+    ///
+    /// Used by every test in this module as the starting point for a clean,
+    /// isolated database that is discarded when the test completes.
+    fn setup() -> Result<Database> {
+        let database = Database::open_in_memory().context("Failed to open database")?;
+        database.migrate().context("Failed to migrate database")?;
+        Ok(database)
+    }
+
+    #[test]
+    fn insert_session_returns_persisted_session() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let session = &Session::default();
+        let args = &InsertSessionArgs { session };
+        let session = querier.insert_session(args)?;
+        assert_eq!(
+            args.session, &session,
+            "Inserted session should match the input session"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_session_by_id_returns_matching_session() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let session = &Session::default();
+        let args = &InsertSessionArgs { session };
+        let session = querier.insert_session(args)?;
+
+        let args = &GetSessionByIdArgs {
+            session_id: &session.id,
+        };
+        let session = querier.get_session_by_id(args)?;
+        assert_eq!(
+            args.session_id, &session.id,
+            "Retrieved session ID should match the requested ID"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn update_session_planned_duration_replaces_planned_duration() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let session = &Session::default();
+        let session = querier.insert_session(&InsertSessionArgs { session })?;
+        let extended = &Session {
+            planned_duration: chrono::Duration::seconds(2100),
+            planned_human: Some("35m".to_string()),
+            ..session.clone()
+        };
+        querier.update_session_planned_duration(&UpdateSessionPlannedDurationArgs {
+            session: extended,
+        })?;
 
-        let session = &Session::default();
+        let args = &GetSessionByIdArgs {
+            session_id: &session.id,
+        };
+        assert_eq!(&querier.get_session_by_id(args)?, extended);
+        Ok(())
+    }
+
+    #[test]
+    fn get_session_by_id_fails_when_not_found() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let session = &Session::default();
         let args = &GetSessionByIdArgs {
             session_id: &session.id,
         };
@@ -464,7 +1170,169 @@ mod tests {
     }
 
     #[test]
-    fn list_sessions_returns_inserted_session() -> Result<()> {
+    fn list_sessions_returns_inserted_session() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let session = &Session::default();
+        let args = &InsertSessionArgs { session };
+        let session = querier.insert_session(args)?;
+
+        let args = &ListSessionsArgs::first();
+        let result = querier.list_sessions(args)?;
+        assert_eq!(
+            result.len(),
+            1,
+            "Should return exactly one session after single insert"
+        );
+        assert_eq!(
+            &session, &result[0],
+            "Listed session should match the created session"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn insert_session_event_returns_persisted_event() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let session = &Session::default();
+        let args = &InsertSessionArgs { session };
+        let session = querier.insert_session(args)?;
+
+        let session_event = &SessionEvent {
+            session_id: session.id,
+            ..SessionEvent::default()
+        };
+        let args = &InsertSessionEventArgs { session_event };
+        let session_event = querier.insert_session_event(args)?;
+        assert_eq!(
+            args.session_event, &session_event,
+            "Inserted session event should match the input session event"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_session_event_by_id_returns_matching_event() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let session = &Session::default();
+        let args = &InsertSessionArgs { session };
+        querier.insert_session(args)?;
+
+        let session_event = &SessionEvent {
+            session_id: session.id,
+            ..SessionEvent::default()
+        };
+        let args = &InsertSessionEventArgs { session_event };
+        let session_event = querier.insert_session_event(args)?;
+
+        let args = &GetSessionEventByIdArgs {
+            session_event_id: &session_event.id,
+        };
+        let session_event = querier.get_session_event_by_id(args)?;
+        assert_eq!(
+            args.session_event_id, &session_event.id,
+            "Retrieved session ID should match the requested ID"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn session_event_created_at_keeps_millisecond_precision() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let session = &Session::default();
+        let args = &InsertSessionArgs { session };
+        querier.insert_session(args)?;
+
+        let created_at = Utc::now();
+        let session_events = [
+            SessionEvent {
+                created_at,
+                ..SessionEvent::started(session.id)
+            },
+            SessionEvent {
+                created_at: created_at + chrono::Duration::milliseconds(1),
+                ..SessionEvent::paused(session.id)
+            },
+        ];
+
+        for session_event in &session_events {
+            let args = &InsertSessionEventArgs { session_event };
+            querier.insert_session_event(args)?;
+
+            let args = &GetSessionEventByIdArgs {
+                session_event_id: &session_event.id,
+            };
+            let stored = querier.get_session_event_by_id(args)?;
+            assert_eq!(
+                stored.created_at, session_event.created_at,
+                "Stored timestamp should round-trip without losing precision"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_session_event_by_id_fails_when_not_found() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let session_event = &SessionEvent::default();
+        let args = &GetSessionEventByIdArgs {
+            session_event_id: &session_event.id,
+        };
+        let result = querier.get_session_event_by_id(args);
+        assert!(
+            result.is_err(),
+            "Should return error when session event not found"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn list_session_events_returns_inserted_event() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let session = &Session::default();
+        let args = &InsertSessionArgs { session };
+        querier.insert_session(args)?;
+
+        let session_event = &SessionEvent {
+            session_id: session.id,
+            ..SessionEvent::default()
+        };
+        let args = &InsertSessionEventArgs { session_event };
+        let session_event = querier.insert_session_event(args)?;
+
+        let args = &ListSessionEventsArgs::first();
+        let result = querier.list_session_events(args)?;
+        assert_eq!(
+            result.len(),
+            1,
+            "Should return exactly one session event after single insert"
+        );
+        assert_eq!(
+            &session_event, &result[0],
+            "Listed session event should match the created session event"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn session_summary_replays_pause_and_completion() -> Result<()> {
         let database = setup()?;
         let querier = Querier::new(database.connection());
 
@@ -472,23 +1340,44 @@ mod tests {
         let args = &InsertSessionArgs { session };
         let session = querier.insert_session(args)?;
 
-        let args = &ListSessionsArgs::first();
-        let result = querier.list_sessions(args)?;
+        // started → paused after 10m → resumed 5m later → completed 15m later.
+        let started_at = session.created_at;
+        let offsets = [
+            (SessionEvent::started(session.id), 0),
+            (SessionEvent::paused(session.id), 10),
+            (SessionEvent::resumed(session.id), 15),
+            (SessionEvent::completed(session.id), 30),
+        ];
+        for (session_event, minutes) in offsets {
+            let session_event = &SessionEvent {
+                created_at: started_at + chrono::Duration::minutes(minutes),
+                ..session_event
+            };
+            querier.insert_session_event(&InsertSessionEventArgs { session_event })?;
+        }
+
+        let args = &SessionSummaryArgs {
+            session_id: &session.id,
+        };
+        let summary = querier.session_summary(args)?;
+        assert_eq!(summary.session, session);
         assert_eq!(
-            result.len(),
-            1,
-            "Should return exactly one session after single insert"
+            summary.elapsed,
+            chrono::Duration::minutes(25),
+            "Elapsed time should exclude the paused interval"
         );
+        assert_eq!(summary.num_pauses, 1);
+        assert_eq!(summary.final_state, Some(SessionEventKind::Completed));
         assert_eq!(
-            &session, &result[0],
-            "Listed session should match the created session"
+            summary.ended_at,
+            Some(started_at + chrono::Duration::minutes(30))
         );
 
         Ok(())
     }
 
     #[test]
-    fn insert_session_event_returns_persisted_event() -> Result<()> {
+    fn session_summary_without_events_has_no_final_state() -> Result<()> {
         let database = setup()?;
         let querier = Querier::new(database.connection());
 
@@ -496,94 +1385,768 @@ mod tests {
         let args = &InsertSessionArgs { session };
         let session = querier.insert_session(args)?;
 
-        let session_event = &SessionEvent {
-            session_id: session.id,
-            ..SessionEvent::default()
+        let args = &SessionSummaryArgs {
+            session_id: &session.id,
         };
-        let args = &InsertSessionEventArgs { session_event };
-        let session_event = querier.insert_session_event(args)?;
+        let summary = querier.session_summary(args)?;
+        assert_eq!(summary.elapsed, chrono::Duration::zero());
+        assert_eq!(summary.num_pauses, 0);
+        assert_eq!(summary.final_state, None);
+        assert_eq!(summary.ended_at, None);
+        assert_eq!(summary.paused_since, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn update_and_delete_session_event_rewrite_the_log() -> Result<()> {
+        let db = setup()?;
+        let querier = Querier::new(db.connection());
+
+        let session = querier.insert_session(&InsertSessionArgs {
+            session: &Session::default(),
+        })?;
+        let started = querier.insert_session_event(&InsertSessionEventArgs {
+            session_event: &SessionEvent::started(session.id),
+        })?;
+        let paused = querier.insert_session_event(&InsertSessionEventArgs {
+            session_event: &SessionEvent::paused(session.id),
+        })?;
+
+        let moved = SessionEvent {
+            created_at: started.created_at + chrono::Duration::seconds(2),
+            ..started.clone()
+        };
+        querier.update_session_event(&UpdateSessionEventArgs {
+            session_event: &moved,
+        })?;
+        querier.delete_session_event(&DeleteSessionEventArgs {
+            session_event_id: &paused.id,
+        })?;
+
+        let events =
+            querier.list_session_events(&ListSessionEventsArgs::with_session_id(session.id))?;
+        assert_eq!(events, vec![moved]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn count_completed_sessions_skips_unfinished_and_older_sessions() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let since = Utc::now() - chrono::Duration::hours(1);
+        let seeds = [
+            (
+                SessionEvent::completed as fn(Uuid) -> SessionEvent,
+                Utc::now(),
+            ),
+            (SessionEvent::aborted, Utc::now()),
+            (SessionEvent::completed, since - chrono::Duration::hours(1)),
+        ];
+        for (event, created_at) in seeds {
+            let session = &Session::default();
+            querier.insert_session(&InsertSessionArgs { session })?;
+            let session_event = &SessionEvent {
+                created_at,
+                ..event(session.id)
+            };
+            querier.insert_session_event(&InsertSessionEventArgs { session_event })?;
+        }
+
+        let args = &CountCompletedSessionsArgs { since };
+        let counts = querier.count_completed_sessions(args)?;
         assert_eq!(
-            args.session_event, &session_event,
-            "Inserted session event should match the input session event"
+            counts,
+            vec![SessionCount {
+                kind: SessionKind::Focus,
+                num_sessions: 1,
+                planned_duration: chrono::Duration::seconds(1500),
+            }]
         );
 
         Ok(())
     }
 
     #[test]
-    fn get_session_event_by_id_returns_matching_event() -> Result<()> {
+    fn count_focus_since_long_break_stops_at_the_last_long_break() -> Result<()> {
         let database = setup()?;
         let querier = Querier::new(database.connection());
 
-        let session = &Session::default();
-        let args = &InsertSessionArgs { session };
-        querier.insert_session(args)?;
+        let seeds = [
+            (
+                SessionKind::Focus,
+                25,
+                SessionEvent::completed as fn(Uuid) -> SessionEvent,
+            ),
+            (SessionKind::Break, 15, SessionEvent::completed),
+            (SessionKind::Focus, 25, SessionEvent::completed),
+            (SessionKind::Break, 5, SessionEvent::completed),
+            (SessionKind::Focus, 25, SessionEvent::aborted),
+            (SessionKind::Focus, 25, SessionEvent::completed),
+        ];
+        for (kind, minutes, event) in seeds {
+            let session = &Session {
+                kind,
+                planned_duration: chrono::Duration::minutes(minutes),
+                ..Session::default()
+            };
+            querier.insert_session(&InsertSessionArgs { session })?;
+            let session_event = &event(session.id);
+            querier.insert_session_event(&InsertSessionEventArgs { session_event })?;
+            let session_event =
+                &SessionEvent::interrupted(session.id, InterruptionSource::Internal, None);
+            querier.insert_session_event(&InsertSessionEventArgs { session_event })?;
+        }
 
-        let session_event = &SessionEvent {
-            session_id: session.id,
-            ..SessionEvent::default()
+        let args = &CountFocusSinceLongBreakArgs {
+            long_break_duration: chrono::Duration::minutes(15),
         };
-        let args = &InsertSessionEventArgs { session_event };
-        let session_event = querier.insert_session_event(args)?;
-
-        let args = &GetSessionEventByIdArgs {
-            session_event_id: &session_event.id,
+        assert_eq!(querier.count_focus_since_long_break(args)?, 2);
+        let args = &CountFocusSinceLongBreakArgs {
+            long_break_duration: chrono::Duration::minutes(30),
         };
-        let session_event = querier.get_session_event_by_id(args)?;
+        assert_eq!(querier.count_focus_since_long_break(args)?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_stats_counts_running_sessions_as_in_progress() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let since = Utc::now() - chrono::Duration::hours(1);
+        let seeds = [
+            (
+                SessionKind::Focus,
+                Utc::now(),
+                SessionEvent::completed as fn(Uuid) -> SessionEvent,
+            ),
+            (SessionKind::Focus, Utc::now(), SessionEvent::aborted),
+            (SessionKind::Focus, Utc::now(), SessionEvent::paused),
+            (SessionKind::Break, Utc::now(), SessionEvent::completed),
+            (
+                SessionKind::Focus,
+                since - chrono::Duration::hours(1),
+                SessionEvent::completed,
+            ),
+        ];
+        for (kind, created_at, event) in seeds {
+            let session = &Session {
+                kind,
+                created_at,
+                ..Session::default()
+            };
+            querier.insert_session(&InsertSessionArgs { session })?;
+            let mut session_events = vec![SessionEvent::started(session.id), event(session.id)];
+            // An interruption does not end the paused session: it stays in progress.
+            if session_events[1].kind == SessionEventKind::Paused {
+                let source = InterruptionSource::Internal;
+                session_events.push(SessionEvent::interrupted(session.id, source, None));
+            }
+            for session_event in &session_events {
+                querier.insert_session_event(&InsertSessionEventArgs { session_event })?;
+            }
+        }
+
+        let stats = querier.aggregate_stats(&AggregateStatsArgs { since })?;
         assert_eq!(
-            args.session_event_id, &session_event.id,
-            "Retrieved session ID should match the requested ID"
+            stats,
+            SessionStats {
+                num_sessions: 3,
+                num_completed: 1,
+                num_aborted: 1,
+                num_interruptions: 1,
+                focus_duration: chrono::Duration::seconds(1500),
+            }
         );
+        assert_eq!(stats.num_in_progress(), 1);
+
+        // An empty window aggregates to zero rather than NULL.
+        let since = Utc::now() + chrono::Duration::hours(1);
+        let stats = querier.aggregate_stats(&AggregateStatsArgs { since })?;
+        assert_eq!(stats.num_sessions, 0);
+        assert_eq!(stats.focus_duration, chrono::Duration::zero());
 
         Ok(())
     }
 
     #[test]
-    fn get_session_event_by_id_fails_when_not_found() -> Result<()> {
+    fn count_sessions_and_events_after_inserts() -> Result<()> {
         let database = setup()?;
         let querier = Querier::new(database.connection());
 
-        let session_event = &SessionEvent::default();
-        let args = &GetSessionEventByIdArgs {
-            session_event_id: &session_event.id,
+        assert_eq!(querier.count_sessions(&CountSessionsArgs::default())?, 0);
+
+        let kinds = [SessionKind::Focus, SessionKind::Break, SessionKind::Focus];
+        let mut sessions = Vec::new();
+        for kind in kinds {
+            let session = &Session {
+                kind,
+                ..Session::default()
+            };
+            let session = querier.insert_session(&InsertSessionArgs { session })?;
+            for session_event in [
+                &SessionEvent::started(session.id),
+                &SessionEvent::paused(session.id),
+            ] {
+                querier.insert_session_event(&InsertSessionEventArgs { session_event })?;
+            }
+            sessions.push(session);
+        }
+
+        assert_eq!(querier.count_sessions(&CountSessionsArgs::default())?, 3);
+        let args = &CountSessionsArgs {
+            kind: Some(SessionKind::Focus),
         };
-        let result = querier.get_session_event_by_id(args);
-        assert!(
-            result.is_err(),
-            "Should return error when session event not found"
-        );
+        assert_eq!(querier.count_sessions(args)?, 2);
 
+        let args = &CountSessionEventsArgs::default();
+        assert_eq!(querier.count_session_events(args)?, 6);
+        let args = &CountSessionEventsArgs {
+            session_id: Some(sessions[0].id),
+        };
+        assert_eq!(querier.count_session_events(args)?, 2);
         Ok(())
     }
 
     #[test]
-    fn list_session_events_returns_inserted_event() -> Result<()> {
+    fn list_notes_filters_by_since() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let since = Utc::now() - chrono::Duration::hours(1);
+        let old = &Note {
+            created_at: since - chrono::Duration::hours(1),
+            ..Note::new("yesterday")
+        };
+        let first = &Note::new("finished the parser");
+        let second = &Note::new("reviewed the backlog");
+        for note in [old, first, second] {
+            querier.insert_note(&InsertNoteArgs { note })?;
+        }
+
+        let notes = querier.list_notes(&ListNotesArgs { since: Some(since) })?;
+        assert_eq!(notes, vec![first.clone(), second.clone()]);
+
+        let notes = querier.list_notes(&ListNotesArgs::default())?;
+        assert_eq!(notes.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn set_meta_then_get_meta_returns_latest_value() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let key = MetaKey::SuspendedEventId;
+        querier.set_meta(&SetMetaArgs {
+            key,
+            value: "first",
+        })?;
+        querier.set_meta(&SetMetaArgs {
+            key,
+            value: "second",
+        })?;
+
+        let value = querier.get_meta(&GetMetaArgs { key })?;
+        assert_eq!(value.as_deref(), Some("second"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn delete_meta_unsets_the_key() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let key = MetaKey::SuspendedEventId;
+        querier.set_meta(&SetMetaArgs {
+            key,
+            value: "value",
+        })?;
+        querier.delete_meta(&DeleteMetaArgs { key })?;
+
+        let value = querier.get_meta(&GetMetaArgs { key })?;
+        assert_eq!(value, None, "Deleted key should no longer be set");
+
+        Ok(())
+    }
+
+    #[test]
+    fn list_session_events_after_id_returns_only_newer_events() -> Result<()> {
         let database = setup()?;
         let querier = Querier::new(database.connection());
 
         let session = &Session::default();
         let args = &InsertSessionArgs { session };
-        querier.insert_session(args)?;
+        let session = querier.insert_session(args)?;
 
-        let session_event = &SessionEvent {
-            session_id: session.id,
-            ..SessionEvent::default()
+        let mut session_events = Vec::new();
+        for session_event in [
+            SessionEvent::started(session.id),
+            SessionEvent::paused(session.id),
+            SessionEvent::resumed(session.id),
+            SessionEvent::completed(session.id),
+        ] {
+            let args = &InsertSessionEventArgs {
+                session_event: &session_event,
+            };
+            session_events.push(querier.insert_session_event(args)?);
+        }
+
+        let args = &ListSessionEventsArgs {
+            after_id: Some(session_events[1].id),
+            limit: None,
+            ..ListSessionEventsArgs::default()
         };
-        let args = &InsertSessionEventArgs { session_event };
-        let session_event = querier.insert_session_event(args)?;
+        let result = querier.list_session_events(args)?;
+        assert_eq!(
+            result,
+            vec![session_events[3].clone(), session_events[2].clone()],
+            "Cursor should return only the events after the given id, newest first"
+        );
 
-        let args = &ListSessionEventsArgs::first();
+        Ok(())
+    }
+
+    #[test]
+    fn list_session_events_kind_returns_only_events_of_that_kind() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let session = &Session::default();
+        let args = &InsertSessionArgs { session };
+        let session = querier.insert_session(args)?;
+
+        let mut session_events = Vec::new();
+        for session_event in [
+            SessionEvent::started(session.id),
+            SessionEvent::paused(session.id),
+            SessionEvent::resumed(session.id),
+            SessionEvent::paused(session.id),
+            SessionEvent::completed(session.id),
+        ] {
+            let args = &InsertSessionEventArgs {
+                session_event: &session_event,
+            };
+            session_events.push(querier.insert_session_event(args)?);
+        }
+
+        let args = &ListSessionEventsArgs {
+            kind: Some(SessionEventKind::Paused),
+            limit: None,
+            ..ListSessionEventsArgs::default()
+        };
         let result = querier.list_session_events(args)?;
         assert_eq!(
-            result.len(),
-            1,
-            "Should return exactly one session event after single insert"
+            result,
+            vec![session_events[3].clone(), session_events[1].clone()],
+            "Filter should return only the paused events, newest first"
         );
+
+        Ok(())
+    }
+
+    #[test]
+    fn list_session_events_leaves_out_interruptions_unless_asked() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let session = &Session::default();
+        let session = querier.insert_session(&InsertSessionArgs { session })?;
+        let mut session_events = Vec::new();
+        for session_event in [
+            SessionEvent::started(session.id),
+            SessionEvent::interrupted(
+                session.id,
+                InterruptionSource::External,
+                Some("phone call".to_string()),
+            ),
+        ] {
+            let args = &InsertSessionEventArgs {
+                session_event: &session_event,
+            };
+            session_events.push(querier.insert_session_event(args)?);
+        }
+
+        // The most recent event is the state: the interruption changed nothing.
+        let result = querier.list_session_events(&ListSessionEventsArgs::first())?;
+        assert_eq!(result, vec![session_events[0].clone()]);
+
+        let args = &ListSessionEventsArgs {
+            interruptions: true,
+            ..ListSessionEventsArgs::first()
+        };
+        let result = querier.list_session_events(args)?;
+        assert_eq!(result, vec![session_events[1].clone()]);
+        assert_eq!(result[0].note.as_deref(), Some("phone call"));
+        Ok(())
+    }
+
+    #[test]
+    fn list_sessions_kind_returns_only_sessions_of_that_kind() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        for kind in [SessionKind::Focus, SessionKind::Break, SessionKind::Focus] {
+            let session = &Session {
+                kind,
+                ..Session::default()
+            };
+            querier.insert_session(&InsertSessionArgs { session })?;
+        }
+
+        let args = &ListSessionsArgs {
+            kind: Some(SessionKind::Break),
+            ..ListSessionsArgs::default()
+        };
+        let result = querier.list_sessions(args)?;
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].kind, SessionKind::Break);
+
+        Ok(())
+    }
+
+    #[test]
+    fn transaction_retries_until_the_write_lock_is_released() -> Result<()> {
+        let uri = "file:transaction_retries?mode=memory&cache=shared";
+        let flags = rusqlite::OpenFlags::default() | rusqlite::OpenFlags::SQLITE_OPEN_URI;
+
+        let mut database = Database {
+            conn: Connection::open_with_flags(uri, flags)?,
+            retry: RetryPolicy {
+                max_retries: 10,
+                backoff: std::time::Duration::from_millis(5),
+            },
+            recovering: false,
+        };
+        database.migrate()?;
+
+        // Hold the write lock from another connection for a short while.
+        let (locked, wait_locked) = std::sync::mpsc::channel();
+        let holder = std::thread::spawn(move || -> Result<()> {
+            let mut conn = Connection::open_with_flags(uri, flags)?;
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+            locked.send(())?;
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            tx.commit()?;
+            Ok(())
+        });
+        wait_locked.recv()?;
+
+        let tx = database.transaction_with(TransactionBehavior::Immediate)?;
+        tx.commit()?;
+
+        holder.join().expect("lock holder panicked")?;
+        Ok(())
+    }
+
+    #[test]
+    fn deferred_transaction_does_not_block_a_concurrent_read() -> Result<()> {
+        let uri = "file:deferred_transaction?mode=memory&cache=shared";
+        let flags = rusqlite::OpenFlags::default() | rusqlite::OpenFlags::SQLITE_OPEN_URI;
+        let open = || -> Result<Database> {
+            Ok(Database {
+                conn: Connection::open_with_flags(uri, flags)?,
+                retry: RetryPolicy {
+                    max_retries: 0,
+                    backoff: std::time::Duration::from_millis(1),
+                },
+                recovering: false,
+            })
+        };
+
+        let mut reader = open()?;
+        reader.migrate()?;
+        let tx = reader.transaction_with(TransactionBehavior::Deferred)?;
+        Querier::new(&tx).list_sessions(&ListSessionsArgs::default())?;
+
+        // Neither a second reader nor a writer has to wait for the first reader.
+        let mut other = open()?;
+        let other_tx = other.transaction_with(TransactionBehavior::Deferred)?;
+        Querier::new(&other_tx).list_sessions(&ListSessionsArgs::default())?;
+        drop(other_tx);
+        other
+            .transaction_with(TransactionBehavior::Immediate)?
+            .commit()?;
+
+        drop(tx);
+        Ok(())
+    }
+
+    #[test]
+    fn transaction_gives_up_after_max_retries() -> Result<()> {
+        let uri = "file:transaction_gives_up?mode=memory&cache=shared";
+        let flags = rusqlite::OpenFlags::default() | rusqlite::OpenFlags::SQLITE_OPEN_URI;
+
+        let mut database = Database {
+            conn: Connection::open_with_flags(uri, flags)?,
+            retry: RetryPolicy {
+                max_retries: 2,
+                backoff: std::time::Duration::from_millis(1),
+            },
+            recovering: false,
+        };
+        database.migrate()?;
+
+        let mut holder = Connection::open_with_flags(uri, flags)?;
+        let _tx = holder.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+        let error = database
+            .transaction_with(TransactionBehavior::Immediate)
+            .expect_err("lock is held");
         assert_eq!(
-            &session_event, &result[0],
-            "Listed session event should match the created session event"
+            error.to_string(),
+            "Failed to start transaction after 2 retries"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn with_transaction_retries_the_closure_after_busy() -> Result<()> {
+        let mut database = setup()?.with_retry_policy(RetryPolicy {
+            max_retries: 1,
+            backoff: std::time::Duration::from_millis(1),
+        });
+
+        let mut attempts = 0;
+        let session = database.with_transaction(TransactionBehavior::Immediate, |querier| {
+            attempts += 1;
+            let session = &Session::default();
+            let session = querier.insert_session(&InsertSessionArgs { session })?;
+            if attempts == 1 {
+                let busy = rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY);
+                Err(rusqlite::Error::SqliteFailure(busy, None))
+                    .context("Failed to execute query")?;
+            }
+            Ok(session)
+        })?;
+        assert_eq!(attempts, 2);
+
+        // The first attempt was rolled back; the second one is committed.
+        let querier = Querier::new(database.connection());
+        let sessions = querier.list_sessions(&ListSessionsArgs::default())?;
+        assert_eq!(sessions, vec![session]);
+        Ok(())
+    }
+
+    #[test]
+    fn with_transaction_rolls_back_without_retrying_other_errors() -> Result<()> {
+        let mut database = setup()?;
+
+        let mut attempts = 0;
+        let error = database
+            .with_transaction(TransactionBehavior::Immediate, |querier| -> Result<()> {
+                attempts += 1;
+                let session = &Session::default();
+                querier.insert_session(&InsertSessionArgs { session })?;
+                anyhow::bail!("No active session to complete.")
+            })
+            .expect_err("the closure fails");
+        assert_eq!(error.to_string(), "No active session to complete.");
+        assert_eq!(attempts, 1);
+
+        let querier = Querier::new(database.connection());
+        let sessions = querier.list_sessions(&ListSessionsArgs::default())?;
+        assert!(sessions.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn list_sessions_reports_unexpected_planned_secs_type() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let session = &Session::default();
+        querier.insert_session(&InsertSessionArgs { session })?;
+        database
+            .connection()
+            .execute("UPDATE session SET planned_secs = 'abc'", [])?;
+
+        let error = querier
+            .list_sessions(&ListSessionsArgs::first())
+            .expect_err("a text planned_secs must not be read as a duration");
+        assert!(
+            format!("{error:#}").contains("unexpected planned_secs type: Text"),
+            "unexpected error: {error:#}"
         );
+        Ok(())
+    }
+
+    #[test]
+    fn insert_session_event_round_trips_completion_source() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
+
+        let session = &Session::default();
+        querier.insert_session(&InsertSessionArgs { session })?;
+        for session_event in [
+            &SessionEvent::paused(session.id),
+            &SessionEvent::completed_manually(session.id),
+        ] {
+            let persisted =
+                querier.insert_session_event(&InsertSessionEventArgs { session_event })?;
+            assert_eq!(&persisted, session_event);
+        }
+
+        let events = querier.list_session_events(&ListSessionEventsArgs::first())?;
+        assert_eq!(events[0].source, Some(CompletionSource::Manual));
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_records_schema_version() -> Result<()> {
+        let database = setup()?;
+        let version: i64 =
+            database
+                .connection()
+                .pragma_query_value(None, "user_version", |row| row.get(0))?;
+        assert_eq!(version, MIGRATIONS.len() as i64);
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_refuses_database_from_newer_binary() -> Result<()> {
+        let database = Database::open_in_memory()?;
+        database
+            .connection()
+            .pragma_update(None, "user_version", MIGRATIONS.len() as i64 + 1)?;
+
+        let error = database
+            .migrate()
+            .expect_err("a newer schema must be refused");
+        assert!(error
+            .to_string()
+            .starts_with("The database is newer than this binary"));
+
+        // Nothing was created in the refused database.
+        let tables: i64 = database.connection().query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(tables, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_adds_planned_human_to_existing_database() -> Result<()> {
+        let database = Database::open_in_memory()?;
+        // The session table as created before planned_human existed.
+        database.connection().execute_batch(
+            "CREATE TABLE session (
+                session_id TEXT PRIMARY KEY,
+                session_kind TEXT NOT NULL,
+                planned_secs INTEGER NOT NULL CHECK (planned_secs > 0),
+                created_at INTEGER NOT NULL
+            );",
+        )?;
+
+        database.migrate()?;
+        // Migrating twice must not try to add the column again.
+        database.migrate()?;
+
+        let querier = Querier::new(database.connection());
+        let session = &Session {
+            planned_human: Some("1h30m".to_string()),
+            ..Session::default()
+        };
+        let session = querier.insert_session(&InsertSessionArgs { session })?;
+        assert_eq!(session.planned_human.as_deref(), Some("1h30m"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_adds_tag_to_existing_database() -> Result<()> {
+        let database = Database::open_in_memory()?;
+        // The session table as created before tag existed, with a session in it.
+        database.connection().execute_batch(
+            "CREATE TABLE session (
+                session_id TEXT PRIMARY KEY,
+                session_kind TEXT NOT NULL,
+                planned_secs INTEGER NOT NULL CHECK (planned_secs > 0),
+                created_at INTEGER NOT NULL,
+                planned_human TEXT
+            );
+            CREATE TABLE session_event (
+                session_event_id TEXT PRIMARY KEY,
+                session_event_kind TEXT NOT NULL,
+                session_id TEXT NOT NULL REFERENCES session (session_id) ON DELETE CASCADE,
+                created_at INTEGER NOT NULL,
+                completion_source TEXT
+            );
+            PRAGMA user_version = 1;",
+        )?;
+        let session_id = Uuid::now_v7();
+        database.connection().execute(
+            "INSERT INTO session VALUES (?1, 'focus', 1500, ?2, '25m')",
+            rusqlite::params![session_id, Utc::now()],
+        )?;
+
+        database.migrate()?;
+
+        let querier = Querier::new(database.connection());
+        let session_id = &session_id;
+        let session = querier.get_session_by_id(&GetSessionByIdArgs { session_id })?;
+        assert_eq!(session.tag, None);
+
+        let session = &Session {
+            tag: Some("writing".to_string()),
+            ..Session::default()
+        };
+        let session = querier.insert_session(&InsertSessionArgs { session })?;
+        assert_eq!(session.tag.as_deref(), Some("writing"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_passes_on_healthy_database() -> Result<()> {
+        let database = setup()?;
+        let querier = Querier::new(database.connection());
 
+        let session = &Session::default();
+        let args = &InsertSessionArgs { session };
+        querier.insert_session(args)?;
+
+        database.check()
+    }
+
+    #[test]
+    fn check_retries_until_the_lock_is_released() -> Result<()> {
+        let uri = "file:check_retries?mode=memory&cache=shared";
+        let flags = rusqlite::OpenFlags::default() | rusqlite::OpenFlags::SQLITE_OPEN_URI;
+
+        let database = Database {
+            conn: Connection::open_with_flags(uri, flags)?,
+            retry: RetryPolicy {
+                max_retries: 10,
+                backoff: std::time::Duration::from_millis(5),
+            },
+            recovering: true,
+        };
+        database.migrate()?;
+
+        // Write from another connection, locking the tables for a short while.
+        let (locked, wait_locked) = std::sync::mpsc::channel();
+        let holder = std::thread::spawn(move || -> Result<()> {
+            let mut conn = Connection::open_with_flags(uri, flags)?;
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+            let session = &Session::default();
+            Querier::new(&tx).insert_session(&InsertSessionArgs { session })?;
+            locked.send(())?;
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            tx.commit()?;
+            Ok(())
+        });
+        wait_locked.recv()?;
+
+        database.check()?;
+
+        holder.join().expect("lock holder panicked")?;
         Ok(())
     }
 }
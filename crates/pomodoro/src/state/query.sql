@@ -7,12 +7,16 @@ INSERT INTO session (
     session_id,
     session_kind,
     planned_secs,
+    planned_human,
+    tag,
     created_at
 )
 VALUES (
     :session_id,
     :session_kind,
     :planned_secs,
+    :planned_human,
+    :tag,
     :created_at
 )
 RETURNING *;
@@ -23,6 +27,8 @@ SELECT
     session_id,
     session_kind,
     planned_secs,
+    planned_human,
+    tag,
     created_at
 FROM session
 WHERE
@@ -34,24 +40,43 @@ SELECT
     session_id,
     session_kind,
     planned_secs,
+    planned_human,
+    tag,
     created_at
 FROM session
+WHERE
+    (:session_kind IS NULL OR session_kind = :session_kind)
 ORDER BY session_id DESC
 LIMIT COALESCE(:limit, -1) OFFSET COALESCE(:offset, 0);
 --
 
+-- name: update_session_planned_duration
+UPDATE session
+SET
+    planned_secs = :planned_secs,
+    planned_human = :planned_human
+WHERE
+    session_id = :session_id;
+--
+
 -- name: insert_session_event
 INSERT INTO session_event (
     session_event_id,
     session_event_kind,
     session_id,
-    created_at
+    created_at,
+    completion_source,
+    interruption_source,
+    note
 )
 VALUES (
     :session_event_id,
     :session_event_kind,
     :session_id,
-    :created_at
+    :created_at,
+    :completion_source,
+    :interruption_source,
+    :note
 )
 RETURNING *;
 --
@@ -61,7 +86,10 @@ SELECT
     session_event_id,
     session_event_kind,
     session_id,
-    created_at
+    created_at,
+    completion_source,
+    interruption_source,
+    note
 FROM session_event
 WHERE
     session_event_id = :session_event_id;
@@ -72,10 +100,158 @@ SELECT
     session_event_id,
     session_event_kind,
     session_id,
-    created_at
+    created_at,
+    completion_source,
+    interruption_source,
+    note
 FROM session_event
 WHERE
     (:session_id IS NULL OR session_id = :session_id)
+    AND (:after_id IS NULL OR session_event_id > :after_id)
+    AND (:session_event_kind IS NULL OR session_event_kind = :session_event_kind)
+    AND (:interruptions OR session_event_kind != 'interrupted')
 ORDER BY session_event_id DESC
 LIMIT COALESCE(:limit, -1) OFFSET COALESCE(:offset, 0);
 --
+
+-- name: update_session_event
+UPDATE session_event
+SET created_at = :created_at
+WHERE
+    session_event_id = :session_event_id;
+--
+
+-- name: delete_session_event
+DELETE FROM session_event
+WHERE
+    session_event_id = :session_event_id;
+--
+
+-- name: count_sessions
+SELECT COUNT(*) AS num_sessions
+FROM session
+WHERE
+    (:session_kind IS NULL OR session_kind = :session_kind);
+--
+
+-- name: count_session_events
+SELECT COUNT(*) AS num_session_events
+FROM session_event
+WHERE
+    (:session_id IS NULL OR session_id = :session_id);
+--
+
+-- name: count_completed_sessions
+SELECT
+    session.session_kind,
+    COUNT(*) AS num_sessions,
+    COALESCE(SUM(session.planned_secs), 0) AS planned_secs
+FROM session
+INNER JOIN session_event ON session.session_id = session_event.session_id
+WHERE
+    session_event.session_event_kind = 'completed'
+    AND session_event.created_at >= :since
+GROUP BY session.session_kind
+ORDER BY session.session_kind;
+--
+
+-- name: count_focus_since_long_break
+SELECT COUNT(*) AS num_sessions
+FROM session
+WHERE
+    session.session_kind = 'focus'
+    AND session.session_id > COALESCE((
+        SELECT MAX(long_break.session_id)
+        FROM session AS long_break
+        WHERE
+            long_break.session_kind = 'break'
+            AND long_break.planned_secs >= :long_break_secs
+    ), '')
+    AND (
+        SELECT session_event.session_event_kind
+        FROM session_event
+        WHERE
+            session_event.session_id = session.session_id
+            AND session_event.session_event_kind != 'interrupted'
+        ORDER BY session_event.session_event_id DESC
+        LIMIT 1
+    ) = 'completed';
+--
+
+-- name: aggregate_stats
+SELECT
+    COUNT(*) AS num_sessions,
+    COALESCE(SUM(last_event.session_event_kind = 'completed'), 0) AS num_completed,
+    COALESCE(SUM(last_event.session_event_kind = 'aborted'), 0) AS num_aborted,
+    COALESCE(SUM(CASE WHEN last_event.session_event_kind = 'completed' THEN session.planned_secs END), 0) AS planned_secs,
+    COALESCE(SUM((
+        SELECT COUNT(*)
+        FROM session_event
+        WHERE
+            session_event.session_id = session.session_id
+            AND session_event.session_event_kind = 'interrupted'
+    )), 0) AS num_interruptions
+FROM session
+LEFT JOIN session_event AS last_event ON last_event.session_event_id = (
+    SELECT session_event.session_event_id
+    FROM session_event
+    WHERE
+        session_event.session_id = session.session_id
+        AND session_event.session_event_kind != 'interrupted'
+    ORDER BY session_event.session_event_id DESC
+    LIMIT 1
+)
+WHERE
+    session.session_kind = 'focus'
+    AND session.created_at >= :since;
+--
+
+-- name: insert_note
+INSERT INTO note (
+    note_id,
+    note_text,
+    created_at
+)
+VALUES (
+    :note_id,
+    :note_text,
+    :created_at
+)
+RETURNING *;
+--
+
+-- name: list_notes
+SELECT
+    note_id,
+    note_text,
+    created_at
+FROM note
+WHERE
+    (:since IS NULL OR created_at >= :since)
+ORDER BY note_id ASC;
+--
+
+-- name: get_meta
+SELECT meta_value
+FROM meta
+WHERE
+    meta_key = :meta_key;
+--
+
+-- name: set_meta
+INSERT INTO meta (
+    meta_key,
+    meta_value
+)
+VALUES (
+    :meta_key,
+    :meta_value
+)
+ON CONFLICT (meta_key) DO UPDATE SET meta_value = excluded.meta_value;
+--
+
+-- name: delete_meta
+DELETE FROM meta
+WHERE
+    meta_key = :meta_key;
+--
@@ -16,7 +16,9 @@ fn test_start_break_session() {
         .args(["--in-memory", "--no-hooks", "start", "--mode", "break"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("Started a new break session."));
+        .stdout(predicate::str::contains(
+            "Started a new break session (5m).",
+        ));
 }
 
 #[test]
@@ -28,6 +30,17 @@ fn test_start_custom_duration() {
         .stdout(predicate::str::contains("Started a new focus session."));
 }
 
+#[test]
+fn test_start_reads_duration_from_stdin() {
+    cargo_bin_cmd!()
+        .args(["--in-memory", "--no-hooks", "start", "--duration", "-"])
+        .args(["--format", "{{ remaining_secs }}"])
+        .write_stdin("30m\n")
+        .assert()
+        .success()
+        .stdout("1800\n");
+}
+
 #[test]
 fn test_stop_command() {
     cargo_bin_cmd!()
@@ -63,6 +76,36 @@ fn test_help_flag() {
         .stdout(predicate::str::contains("A simple pomodoro timer"));
 }
 
+#[test]
+fn test_help_lists_advanced_flags() {
+    cargo_bin_cmd!()
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"(?s)Advanced:\n.*--in-memory.*--no-hooks").unwrap());
+}
+
+#[test]
+fn test_start_help_shows_examples() {
+    cargo_bin_cmd!()
+        .args(["start", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "pomodoro start --mode break --duration 10m",
+        ));
+}
+
+#[test]
+fn test_status_help_shows_template_example() {
+    cargo_bin_cmd!()
+        .args(["status", "-h"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Examples:"))
+        .stdout(predicate::str::contains("--format '{{ kind }}"));
+}
+
 #[test]
 fn test_start_with_no_hooks() {
     cargo_bin_cmd!()
@@ -88,3 +131,620 @@ fn test_status_with_no_hooks() {
         .assert()
         .success();
 }
+
+#[test]
+fn test_start_wait_then_runs_command_on_completion() {
+    let path = std::env::temp_dir().join(format!("pomodoro-then-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    cargo_bin_cmd!()
+        .args([
+            "--in-memory",
+            "--no-hooks",
+            "start",
+            "--duration",
+            "1s",
+            "--wait",
+        ])
+        .args(["--then", &format!("touch {}", path.display())])
+        .assert()
+        .success();
+
+    assert!(path.exists(), "--then command did not run on completion");
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_start_wait_progress_prints_status_lines() {
+    let output = cargo_bin_cmd!()
+        .args(["--in-memory", "--no-hooks", "start", "--duration", "2s"])
+        .args(["--wait", "--progress", "500ms", "--output", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // The confirmation, then a status line every 500ms until completion.
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines = stdout.lines().skip_while(|line| *line != "}").skip(1);
+    let states = lines
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["state"].clone())
+        .collect::<Vec<_>>();
+    assert!(states.len() >= 3, "{stdout}");
+    assert!(
+        states[..2].iter().all(|state| state == "running"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn test_start_wait_progress_rejects_a_zero_interval() {
+    cargo_bin_cmd!()
+        .args(["--in-memory", "--no-hooks", "start", "--duration", "2s"])
+        .args(["--wait", "--progress", "0s"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "Expected an interval longer than 0s",
+        ));
+}
+
+#[test]
+fn test_start_then_requires_wait() {
+    cargo_bin_cmd!()
+        .args(["--in-memory", "--no-hooks", "start", "--then", "true"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_start_custom_format() {
+    cargo_bin_cmd!()
+        .args(["--in-memory", "--no-hooks", "start"])
+        .args(["--format", "{{ action }} {{ kind }}"])
+        .assert()
+        .success()
+        .stdout("started focus\n");
+}
+
+#[test]
+fn test_stop_custom_format() {
+    cargo_bin_cmd!()
+        .args(["--in-memory", "--no-hooks", "stop"])
+        .args(["--format", "{{ action }} {{ kind }}"])
+        .assert()
+        .success()
+        .stdout("none none\n");
+}
+
+#[test]
+fn test_disabled_start_does_not_create_session() {
+    let state = std::env::temp_dir().join(format!("pomodoro-disabled-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&state);
+
+    cargo_bin_cmd!()
+        .env("XDG_STATE_HOME", &state)
+        .env("POMODORO_DISABLED", "1")
+        .args(["--no-hooks", "start"])
+        .assert()
+        .success()
+        .stdout("pomodoro disabled\n");
+
+    // Every other command that writes is switched off as well.
+    for args in [
+        vec!["note", "standup"],
+        vec!["gc", "--threshold", "0s"],
+        vec!["focus-on", "--clear"],
+    ] {
+        cargo_bin_cmd!()
+            .env("XDG_STATE_HOME", &state)
+            .env("POMODORO_DISABLED", "1")
+            .arg("--no-hooks")
+            .args(&args)
+            .assert()
+            .success()
+            .stdout("pomodoro disabled\n");
+    }
+    cargo_bin_cmd!()
+        .env("XDG_STATE_HOME", &state)
+        .env_remove("POMODORO_DISABLED")
+        .args(["--no-hooks", "notes"])
+        .assert()
+        .success()
+        .stdout("\n");
+
+    cargo_bin_cmd!()
+        .env("XDG_STATE_HOME", &state)
+        .env("POMODORO_DISABLED", "1")
+        .args(["--no-hooks", "status", "--json-path", "state"])
+        .assert()
+        .success()
+        .stdout("disabled\n");
+
+    cargo_bin_cmd!()
+        .env("XDG_STATE_HOME", &state)
+        .env_remove("POMODORO_DISABLED")
+        .args(["--no-hooks", "status", "--json-path", "state"])
+        .assert()
+        .success()
+        .stdout("none\n");
+
+    std::fs::remove_dir_all(&state).ok();
+}
+
+#[test]
+fn test_refresh_tmux_only_after_a_command_that_wrote() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // A fake tmux that records every invocation.
+    let bin = std::env::temp_dir().join(format!("pomodoro-refresh-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&bin);
+    std::fs::create_dir_all(&bin).unwrap();
+    let calls = bin.join("calls");
+    let tmux = bin.join("tmux");
+    std::fs::write(
+        &tmux,
+        format!("#!/bin/sh\necho \"$@\" >> {}\n", calls.display()),
+    )
+    .unwrap();
+    std::fs::set_permissions(&tmux, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+
+    let refreshed = |args: &[&str]| {
+        let _ = std::fs::remove_file(&calls);
+        cargo_bin_cmd!()
+            .env("PATH", &path)
+            .env("TMUX", "/tmp/tmux-1000/default,1,0")
+            .args(["--in-memory", "--no-hooks", "--refresh-tmux"])
+            .args(args)
+            .assert()
+            .success();
+        calls.exists()
+    };
+    assert!(refreshed(&["start"]));
+    assert!(!refreshed(&["stats"]));
+    assert!(!refreshed(&["stop"]), "nothing to stop");
+
+    std::fs::remove_dir_all(&bin).ok();
+}
+
+#[test]
+fn test_log_rejects_event_without_session() {
+    cargo_bin_cmd!()
+        .args(["--in-memory", "--no-hooks", "log", "paused"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No session to log an event for"));
+}
+
+#[test]
+fn test_serve_answers_status_on_socket() {
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::net::UnixStream;
+    use std::process::{Command, Stdio};
+
+    let socket = std::env::temp_dir().join(format!("pomodoro-serve-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&socket);
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_pomodoro"))
+        .args(["--in-memory", "--no-hooks", "serve", "--socket"])
+        .arg(&socket)
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn pomodoro serve");
+
+    // The server reports that it is listening once the socket is bound.
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    stdout.read_line(&mut line).unwrap();
+    assert!(
+        line.starts_with("Listening on"),
+        "unexpected output: {line}"
+    );
+
+    let mut status = String::new();
+    BufReader::new(UnixStream::connect(&socket).unwrap())
+        .read_line(&mut status)
+        .unwrap();
+    child.kill().ok();
+    child.wait().ok();
+    std::fs::remove_file(&socket).ok();
+
+    let status: serde_json::Value = serde_json::from_str(&status).unwrap();
+    assert_eq!(status["state"], "none");
+}
+
+#[test]
+fn test_serve_streams_to_several_clients_at_once() {
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::net::UnixStream;
+    use std::process::{Command, Stdio};
+
+    let socket = std::env::temp_dir().join(format!("pomodoro-stream-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&socket);
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_pomodoro"))
+        .args([
+            "--in-memory",
+            "--no-hooks",
+            "serve",
+            "--interval",
+            "1s",
+            "--socket",
+        ])
+        .arg(&socket)
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn pomodoro serve");
+
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    stdout.read_line(&mut line).unwrap();
+
+    // The first client keeps streaming while the second one connects.
+    let connect = || {
+        let stream = UnixStream::connect(&socket).unwrap();
+        stream
+            .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+            .unwrap();
+        BufReader::new(stream)
+    };
+    let mut first = connect();
+    let mut second = connect();
+    let mut status = String::new();
+    let read_first = first.read_line(&mut status);
+    let read_second = second.read_line(&mut status);
+    child.kill().ok();
+    child.wait().ok();
+    std::fs::remove_file(&socket).ok();
+
+    assert!(read_first.is_ok(), "the first client got no status");
+    assert!(read_second.is_ok(), "the second client got no status");
+    assert_eq!(status.lines().count(), 2);
+}
+
+#[test]
+fn test_require_hooks_fails_on_missing_hook() {
+    let config = std::env::temp_dir().join(format!("pomodoro-require-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&config);
+
+    cargo_bin_cmd!()
+        .env("XDG_CONFIG_HOME", &config)
+        .args(["--in-memory", "--require-hooks", "start"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("hooks/start does not exist"));
+}
+
+#[test]
+fn test_edit_config_creates_commented_template() {
+    let config = std::env::temp_dir().join(format!("pomodoro-edit-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&config);
+
+    cargo_bin_cmd!()
+        .env("XDG_CONFIG_HOME", &config)
+        .env_remove("VISUAL")
+        .env("EDITOR", "true")
+        .arg("edit-config")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("is valid."));
+
+    let content = std::fs::read_to_string(config.join("pomodoro/config.toml")).unwrap();
+    std::fs::remove_dir_all(&config).ok();
+    assert!(content.contains("# focus_duration = \"25m\""));
+    assert!(content.contains("# [colors]"));
+}
+
+#[test]
+fn test_init_scaffolds_config_and_hooks_once() {
+    let config = std::env::temp_dir().join(format!("pomodoro-init-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&config);
+
+    cargo_bin_cmd!()
+        .env("XDG_CONFIG_HOME", &config)
+        .arg("init")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created").count(3));
+
+    cargo_bin_cmd!()
+        .env("XDG_CONFIG_HOME", &config)
+        .arg("init")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Kept").count(3));
+
+    let root = config.join("pomodoro");
+    let exists = ["config.toml", "hooks/start", "hooks/stop"].map(|file| root.join(file).is_file());
+    std::fs::remove_dir_all(&config).ok();
+    assert_eq!(exists, [true; 3]);
+}
+
+#[test]
+fn test_strict_stop_without_session_fails() {
+    cargo_bin_cmd!()
+        .args(["--in-memory", "--no-hooks", "--strict", "stop"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No active session found."));
+}
+
+#[test]
+fn test_strict_start_while_running_fails() {
+    let state = std::env::temp_dir().join(format!("pomodoro-strict-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&state);
+
+    cargo_bin_cmd!()
+        .env("XDG_STATE_HOME", &state)
+        .args(["--no-hooks", "--strict", "start"])
+        .assert()
+        .success();
+
+    cargo_bin_cmd!()
+        .env("XDG_STATE_HOME", &state)
+        .args(["--no-hooks", "--strict", "start"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "A focus session is already running.",
+        ));
+
+    cargo_bin_cmd!()
+        .env("XDG_STATE_HOME", &state)
+        .args(["--no-hooks", "--strict", "resume-from-suspend"])
+        .assert()
+        .failure();
+
+    std::fs::remove_dir_all(&state).ok();
+}
+
+#[test]
+fn test_status_no_newline() {
+    cargo_bin_cmd!()
+        .args([
+            "--in-memory",
+            "--no-hooks",
+            "status",
+            "--json-path",
+            "state",
+        ])
+        .arg("--no-newline")
+        .assert()
+        .success()
+        .stdout("none");
+}
+
+#[test]
+fn test_status_debug_includes_totals() {
+    cargo_bin_cmd!()
+        .args(["--in-memory", "--no-hooks", "status", "--output", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("total_sessions").not());
+
+    cargo_bin_cmd!()
+        .args(["--in-memory", "--no-hooks", "status", "--output", "json"])
+        .arg("--debug")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"total_sessions\": 0"))
+        .stdout(predicate::str::contains("\"total_events\": 0"));
+}
+
+#[test]
+fn test_replay_without_session_fails() {
+    cargo_bin_cmd!()
+        .args(["--in-memory", "--no-hooks", "replay"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No session to replay"));
+}
+
+#[test]
+fn test_stop_json_lists_hooks_fired() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let root = std::env::temp_dir().join(format!("pomodoro-fired-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let hooks = root.join("config/pomodoro/hooks");
+    std::fs::create_dir_all(&hooks).unwrap();
+    let hook = hooks.join("stop");
+    std::fs::write(&hook, "#!/bin/sh\ncat > /dev/null\n").unwrap();
+    std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let pomodoro = |args: &[&str]| {
+        let output = cargo_bin_cmd!()
+            .env("XDG_CONFIG_HOME", root.join("config"))
+            .env("XDG_STATE_HOME", root.join("state"))
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        output.stdout
+    };
+
+    // There is no start hook; the stop hook is installed.
+    let started = pomodoro(&["start", "--format", "{{ hooks_fired | length }}"]);
+    assert_eq!(String::from_utf8_lossy(&started), "0\n");
+    let stopped: serde_json::Value =
+        serde_json::from_slice(&pomodoro(&["stop", "--output", "json"])).unwrap();
+    std::fs::remove_dir_all(&root).ok();
+
+    assert_eq!(stopped["hooks_fired"], serde_json::json!(["stop"]));
+}
+
+#[test]
+fn test_status_cached_reads_without_database() {
+    let root = std::env::temp_dir().join(format!("pomodoro-cache-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("config/pomodoro")).unwrap();
+    std::fs::write(
+        root.join("config/pomodoro/config.toml"),
+        "status_cache = true\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!()
+        .env("XDG_CONFIG_HOME", root.join("config"))
+        .env("XDG_STATE_HOME", root.join("state"))
+        .args(["--no-hooks", "start", "--duration", "10m"])
+        .assert()
+        .success();
+
+    // Without the database, the status can only come from the cache.
+    let database = root.join("state/pomodoro/state.db");
+    std::fs::remove_file(&database).unwrap();
+    let output = cargo_bin_cmd!()
+        .env("XDG_CONFIG_HOME", root.join("config"))
+        .env("XDG_STATE_HOME", root.join("state"))
+        .args([
+            "--no-hooks",
+            "status",
+            "--cached",
+            "--json-path",
+            "remaining_secs",
+        ])
+        .output()
+        .unwrap();
+    let recreated = database.exists();
+    std::fs::remove_dir_all(&root).ok();
+
+    assert!(output.status.success());
+    let remaining: i64 = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .unwrap();
+    assert!((590..=600).contains(&remaining), "remaining: {remaining}");
+    assert!(!recreated, "status --cached opened the database");
+}
+
+#[test]
+fn test_start_print_ends_at_quiet() {
+    let before = chrono::Utc::now();
+    let output = cargo_bin_cmd!()
+        .args(["--in-memory", "--no-hooks", "start", "--duration", "10m"])
+        .args(["--print-ends-at", "--quiet"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let ends_at = chrono::DateTime::parse_from_rfc3339(stdout.trim()).unwrap();
+    let expected = before + chrono::Duration::minutes(10);
+    assert!(
+        (ends_at.to_utc() - expected).num_seconds().abs() <= 2,
+        "{stdout}"
+    );
+    assert_eq!(stdout.lines().count(), 1);
+}
+
+#[test]
+fn test_state_changing_commands_report_action_as_json() {
+    let root = std::env::temp_dir().join(format!("pomodoro-actions-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+
+    let pomodoro = |args: &[&str]| {
+        let output = cargo_bin_cmd!()
+            .env("XDG_CONFIG_HOME", root.join("config"))
+            .env("XDG_STATE_HOME", root.join("state"))
+            .arg("--no-hooks")
+            .args(args)
+            .args(["--output", "json"])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{args:?}");
+        let outcome: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        outcome["action"].as_str().unwrap().to_string()
+    };
+
+    let actions = [
+        (vec!["start"], "started"),
+        (vec!["suspend"], "paused"),
+        (vec!["resume-from-suspend"], "resumed"),
+        (vec!["pause"], "paused"),
+        (vec!["pause"], "none"),
+        (vec!["resume"], "resumed"),
+        (vec!["resume"], "none"),
+        (vec!["extend", "10m"], "extended"),
+        (vec!["interrupt", "--external", "call"], "interrupted"),
+        (vec!["log", "paused"], "paused"),
+        (vec!["log", "resumed"], "resumed"),
+        (vec!["stop", "--reset"], "aborted"),
+        (vec!["start"], "started"),
+        (vec!["cancel"], "aborted"),
+        (vec!["abort"], "none"),
+    ]
+    .map(|(args, expected)| (pomodoro(&args), expected));
+    std::fs::remove_dir_all(&root).ok();
+
+    for (action, expected) in actions {
+        assert_eq!(action, expected);
+    }
+}
+
+#[test]
+fn test_status_assert_running_exits_with_the_running_state() {
+    let root = std::env::temp_dir().join(format!("pomodoro-assert-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let pomodoro = |args: &[&str]| {
+        let mut command = cargo_bin_cmd!();
+        command
+            .env("XDG_CONFIG_HOME", root.join("config"))
+            .env("XDG_STATE_HOME", root.join("state"))
+            .arg("--no-hooks")
+            .args(args);
+        command
+    };
+
+    // Idle: fails without printing anything.
+    pomodoro(&["status", "--assert-running"])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr("");
+
+    // Running: succeeds, quietly unless --verbose.
+    pomodoro(&["start"]).assert().success();
+    pomodoro(&["status", "--assert-running"])
+        .assert()
+        .success()
+        .stdout("");
+    pomodoro(&["status", "--assert-running", "--verbose"])
+        .args(["--format", "{{ state }}"])
+        .assert()
+        .success()
+        .stdout("running\n");
+
+    // Paused counts as not running.
+    pomodoro(&["pause"]).assert().success();
+    pomodoro(&["status", "--assert-running"]).assert().code(1);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn test_status_set_tmux_option_falls_back_to_printing_outside_tmux() {
+    // `--tmux` is an alias of `--set-tmux-option`.
+    for flag in ["--set-tmux-option", "--tmux"] {
+        cargo_bin_cmd!()
+            .env_remove("TMUX")
+            .args(["--in-memory", "--no-hooks", "status", flag])
+            .args(["--format", "{{ state }}"])
+            .assert()
+            .success()
+            .stdout("none\n")
+            .stderr(predicate::str::contains(
+                "warning: not running inside tmux; printing the status instead",
+            ));
+    }
+}
+
+#[test]
+fn test_status_renders_template_vars() {
+    cargo_bin_cmd!()
+        .args(["--in-memory", "--no-hooks", "status"])
+        .args(["--format", "{{ pane }}: {{ state }}", "--var", "pane=main"])
+        .assert()
+        .success()
+        .stdout("main: none\n");
+}